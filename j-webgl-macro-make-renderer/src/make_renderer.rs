@@ -165,7 +165,7 @@ pub fn expand_macro(tokens: TokenStream) -> SynResult<TokenStream> {
     }
 
     // without method to remove a renderables
-    if let Some(renderable_type) = renderable_types.get(0) {
+    if let Some(renderable_type) = renderable_types.first() {
       let without_method = quote! {
         pub fn without(mut self, id: String) -> std::result::Result<Self, wasm_bindgen::JsValue> {
             self.inner.with_renderable::<#renderable_type>(id, None)?;
@@ -175,6 +175,99 @@ pub fn expand_macro(tokens: TokenStream) -> SynResult<TokenStream> {
       methods.push(without_method);
     }
 
+    // set_transform method to place a renderable, from a 16 column-major values matrix
+    let set_transform_method = quote! {
+        pub fn set_transform(&mut self, id: String, matrix: &[f32]) -> std::result::Result<(), wasm_bindgen::JsValue> {
+            let matrix = j_webgl::algebra::Matrix4::try_from(matrix).map_err(j_webgl::Error::from)?;
+            Ok(self.inner.set_transform(&id, matrix)?)
+        }
+    };
+    methods.push(set_transform_method);
+
+    // with_light and without_light methods to add, update or remove a light
+    let light_methods = quote! {
+        pub fn with_light(mut self, id: String, light: j_webgl::structs::light::Light) -> std::result::Result<Self, wasm_bindgen::JsValue> {
+            self.inner.with_light(id, Some(light))?;
+            Ok(self)
+        }
+
+        pub fn without_light(mut self, id: String) -> std::result::Result<Self, wasm_bindgen::JsValue> {
+            self.inner.with_light(id, None)?;
+            Ok(self)
+        }
+
+        pub fn set_ambient(&mut self, red: f32, green: f32, blue: f32) {
+            self.inner.set_ambient(red, green, blue);
+        }
+    };
+    methods.push(light_methods);
+
+    // set_shadows and clear_shadows methods to enable or disable shadows
+    let shadow_methods = quote! {
+        #[allow(clippy::too_many_arguments)]
+        pub fn set_shadows(&mut self, size: u32, bias: f32, pcf_radius: u32, center_x: f32, center_y: f32, center_z: f32, extent: f32) {
+            self.inner.set_shadows(Some(j_webgl::shadow::ShadowSettings {
+                size, bias, pcf_radius,
+                center: j_webgl::algebra::Point3::new(center_x, center_y, center_z),
+                extent,
+            }));
+        }
+
+        pub fn clear_shadows(&mut self) {
+            self.inner.set_shadows(None);
+        }
+    };
+    methods.push(shadow_methods);
+
+    // set_* methods to add or update post-processing effects, remove_effect to remove one
+    let effect_methods = quote! {
+        pub fn set_fxaa(&mut self, id: String) -> std::result::Result<(), wasm_bindgen::JsValue> {
+            Ok(self.inner.with_effect(id, Some(j_webgl::post::Fxaa::new()))?)
+        }
+
+        pub fn set_tone_mapping(&mut self, id: String, aces: bool, exposure: f32) -> std::result::Result<(), wasm_bindgen::JsValue> {
+            let operator = if aces { j_webgl::post::ToneMappingOperator::Aces } else { j_webgl::post::ToneMappingOperator::Reinhard };
+            let mut effect = j_webgl::post::ToneMapping::new(operator);
+            effect.set_exposure(exposure);
+            Ok(self.inner.with_effect(id, Some(effect))?)
+        }
+
+        pub fn set_gamma(&mut self, id: String, gamma: f32) -> std::result::Result<(), wasm_bindgen::JsValue> {
+            let mut effect = j_webgl::post::Gamma::new();
+            effect.set_gamma(gamma);
+            Ok(self.inner.with_effect(id, Some(effect))?)
+        }
+
+        pub fn set_bloom(&mut self, id: String, threshold: f32, intensity: f32, iterations: u32) -> std::result::Result<(), wasm_bindgen::JsValue> {
+            let mut effect = j_webgl::post::Bloom::new();
+            effect.set_threshold(threshold);
+            effect.set_intensity(intensity);
+            effect.set_iterations(iterations);
+            Ok(self.inner.with_effect(id, Some(effect))?)
+        }
+
+        pub fn set_vignette(&mut self, id: String, intensity: f32, radius: f32, softness: f32) -> std::result::Result<(), wasm_bindgen::JsValue> {
+            let mut effect = j_webgl::post::Vignette::new();
+            effect.set_intensity(intensity);
+            effect.set_radius(radius, softness);
+            Ok(self.inner.with_effect(id, Some(effect))?)
+        }
+
+        pub fn set_shader_effect(&mut self, id: String, fragment_shader: String) -> std::result::Result<(), wasm_bindgen::JsValue> {
+            Ok(self.inner.with_effect(id, Some(j_webgl::post::ShaderEffect::new(&fragment_shader)))?)
+        }
+
+        pub fn remove_effect(&mut self, id: String) -> std::result::Result<(), wasm_bindgen::JsValue> {
+            Ok(self.inner.with_effect::<j_webgl::post::Fxaa>(id, None)?)
+        }
+
+        pub fn set_post_target(&mut self, hdr: bool, samples: u32) {
+            let color = if hdr { j_webgl::texture::TextureFormat::Rgba16F } else { j_webgl::texture::TextureFormat::Rgba8 };
+            self.inner.set_post_settings(j_webgl::target::RenderTargetSettings { color: Some(color), samples, ..Default::default() });
+        }
+    };
+    methods.push(effect_methods);
+
     // resize method to handle canvas resize
     let resize_method = quote! {
         pub fn resize(&self) -> std::result::Result<(), wasm_bindgen::JsValue> {
@@ -191,6 +284,26 @@ pub fn expand_macro(tokens: TokenStream) -> SynResult<TokenStream> {
     };
     methods.push(render_method);
 
+    // pick method returning the id of the renderable under a canvas position
+    let pick_method = quote! {
+        pub fn pick(&self, x: f32, y: f32) -> std::result::Result<Option<String>, wasm_bindgen::JsValue> {
+            Ok(self.inner.pick(x, y)?)
+        }
+    };
+    methods.push(pick_method);
+
+    // counters of the renderables drawn and culled by the last render
+    let count_methods = quote! {
+        pub fn rendered_count(&self) -> usize {
+            self.inner.rendered_count()
+        }
+
+        pub fn culled_count(&self) -> usize {
+            self.inner.culled_count()
+        }
+    };
+    methods.push(count_methods);
+
     let impl_block = quote! {
         #[wasm_bindgen::prelude::wasm_bindgen]
         impl #struct_name {
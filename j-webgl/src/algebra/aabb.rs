@@ -0,0 +1,76 @@
+use super::{Matrix4, Point3, Vector3};
+
+/// An axis-aligned bounding box, the set of points between two corners
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    min: Point3,
+    max: Point3,
+}
+
+impl Aabb {
+    /// Creates the box spanning two opposite corners, in any order
+    pub fn new(a: Point3, b: Point3) -> Self {
+        Aabb {
+            min: Point3::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z())),
+            max: Point3::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z())),
+        }
+    }
+
+    /// Returns the smallest box containing all the points, `None` if there are none
+    pub fn from_points<'a, I: IntoIterator<Item = &'a Point3>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        Some(points.fold(Aabb { min: first, max: first }, |aabb, p| aabb.extend(p)))
+    }
+
+    /// Returns the minimum corner
+    pub fn min(&self) -> Point3 {
+        self.min
+    }
+
+    /// Returns the maximum corner
+    pub fn max(&self) -> Point3 {
+        self.max
+    }
+
+    /// Returns the center of the box
+    pub fn center(&self) -> Point3 {
+        &self.min + &(0.5 * &self.size())
+    }
+
+    /// Returns the vector from the minimum to the maximum corner
+    pub fn size(&self) -> Vector3 {
+        &self.max - &self.min
+    }
+
+    /// Returns the smallest box containing this box and a point
+    pub fn extend(&self, point: &Point3) -> Self {
+        Aabb::new(
+            Point3::new(self.min.x().min(point.x()), self.min.y().min(point.y()), self.min.z().min(point.z())),
+            Point3::new(self.max.x().max(point.x()), self.max.y().max(point.y()), self.max.z().max(point.z())),
+        )
+    }
+
+    /// Returns the smallest box containing both boxes
+    pub fn union(&self, other: &Aabb) -> Self {
+        self.extend(&other.min).extend(&other.max)
+    }
+
+    /// Returns the box bounding this box transformed by an affine matrix
+    pub fn transform(&self, matrix: &Matrix4) -> Self {
+        let (min, max) = (self.min, self.max);
+        let corners = [0, 1, 2, 3, 4, 5, 6, 7].map(|i| matrix.transform_point(&Point3::new(
+            if i & 1 == 0 { min.x() } else { max.x() },
+            if i & 2 == 0 { min.y() } else { max.y() },
+            if i & 4 == 0 { min.z() } else { max.z() },
+        )));
+        Aabb::from_points(&corners).expect("a box has corners")
+    }
+
+    /// Returns `true` if the point is inside the box or on its boundary
+    pub fn contains(&self, point: &Point3) -> bool {
+        (self.min.x()..=self.max.x()).contains(&point.x())
+            && (self.min.y()..=self.max.y()).contains(&point.y())
+            && (self.min.z()..=self.max.z()).contains(&point.z())
+    }
+}
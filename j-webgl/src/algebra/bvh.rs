@@ -0,0 +1,208 @@
+use super::{Aabb, Point3, Ray, Vector3};
+
+/// Maximum number of triangles in a leaf node
+const LEAF_SIZE: usize = 4;
+
+/// Bounding volume hierarchy over triangles, to intersect rays with large
+/// meshes without testing every triangle
+///
+/// Nodes split their triangles at the median centroid along the longest axis
+/// of the centroid bounds. Triangles keep the index they were given in, which
+/// [`MeshHit::triangle`] reports.
+///
+/// # Example
+///
+/// ```rust
+/// use j_webgl::algebra::{Bvh, Point3, Ray, Vector3};
+///
+/// let bvh = Bvh::new(vec![[Point3::new(-1.0, -1.0, 0.0), Point3::new(1.0, -1.0, 0.0), Point3::new(0.0, 1.0, 0.0)]]);
+/// let hit = bvh.intersect(&Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0))).unwrap();
+/// assert_eq!((hit.triangle, hit.distance), (0, 5.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    triangles: Vec<[Point3; 3]>,
+    /// Index given to `new` of each triangle, in the order of `triangles`
+    indices: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Aabb,
+    /// First triangle of a leaf, or index of the first child of an inner node,
+    /// whose second child follows it
+    first: usize,
+    /// Number of triangles of a leaf, 0 for inner nodes
+    count: usize,
+}
+
+/// Closest intersection of a [`Ray`] with the triangles of a [`Bvh`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshHit {
+    /// Distance along the ray
+    pub distance: f32,
+    /// Point hit
+    pub point: Point3,
+    /// Unit normal of the triangle hit, facing counter-clockwise vertices
+    pub normal: Vector3,
+    /// Index of the triangle hit
+    pub triangle: usize,
+    /// Barycentric coordinates, see [`TriangleHit`](super::TriangleHit)
+    pub u: f32,
+    pub v: f32,
+}
+
+impl Bvh {
+    /// Build the hierarchy over triangles given by their three corners
+    pub fn new(triangles: Vec<[Point3; 3]>) -> Self {
+        let mut bvh = Bvh { indices: (0..triangles.len()).collect(), triangles, nodes: Vec::new() };
+        if !bvh.triangles.is_empty() {
+            bvh.nodes.push(Node { bounds: bvh.bounds_of(0, bvh.triangles.len()), first: 0, count: bvh.triangles.len() });
+            bvh.split(0);
+        }
+        bvh
+    }
+
+    /// Number of triangles
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Returns the bounding box of all the triangles, `None` if there are none
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.nodes.first().map(|node| node.bounds)
+    }
+
+    /// Returns the closest triangle hit by the ray, from either side
+    pub fn intersect(&self, ray: &Ray) -> Option<MeshHit> {
+        let mut closest: Option<(f32, usize, f32, f32)> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() { stack.push(0); }
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            let max_distance = closest.map(|c| c.0).unwrap_or(f32::INFINITY);
+            if ray.aabb_range(&node.bounds, max_distance).is_none() { continue; }
+            if node.count > 0 {
+                for j in node.first..node.first + node.count {
+                    let [a, b, c] = &self.triangles[j];
+                    let hit = ray.intersect_triangle(a, b, c)
+                        .filter(|hit| closest.is_none_or(|c| hit.distance < c.0));
+                    if let Some(hit) = hit {
+                        closest = Some((hit.distance, j, hit.u, hit.v));
+                    }
+                }
+            } else {
+                // Visit the child closest to the ray origin first, to prune the other one
+                let (left, right) = (node.first, node.first + 1);
+                let entry = |n: usize| ray.aabb_range(&self.nodes[n].bounds, max_distance).map(|r| r.0).unwrap_or(f32::INFINITY);
+                let (near, far) = if entry(left) <= entry(right) { (left, right) } else { (right, left) };
+                stack.push(far);
+                stack.push(near);
+            }
+        }
+        closest.map(|(distance, j, u, v)| {
+            let [a, b, c] = &self.triangles[j];
+            MeshHit {
+                distance,
+                point: ray.at(distance),
+                normal: (b - a).cross(&(c - a)).normalize(),
+                triangle: self.indices[j],
+                u,
+                v,
+            }
+        })
+    }
+
+    fn bounds_of(&self, first: usize, count: usize) -> Aabb {
+        let points = self.triangles[first..first + count].iter().flatten();
+        Aabb::from_points(points).expect("nodes hold at least one triangle")
+    }
+
+    fn split(&mut self, i: usize) {
+        let Node { first, count, .. } = self.nodes[i];
+        if count <= LEAF_SIZE { return; }
+        let centroid = |t: &[Point3; 3]| -> [f32; 3] {
+            [0, 1, 2].map(|axis| t.iter().map(|p| [p.x(), p.y(), p.z()][axis]).sum::<f32>() / 3.0)
+        };
+        let centroids: Vec<Point3> = self.triangles[first..first + count].iter()
+            .map(|t| { let [x, y, z] = centroid(t); Point3::new(x, y, z) })
+            .collect();
+        let size = Aabb::from_points(&centroids).expect("nodes hold at least one triangle").size();
+        let axis = if size.dx() >= size.dy() && size.dx() >= size.dz() { 0 } else if size.dy() >= size.dz() { 1 } else { 2 };
+
+        // Sort the triangles of the node, with their indices, by centroid along the axis
+        let mut order: Vec<usize> = (0..count).collect();
+        let key = |k: &usize| centroid(&self.triangles[first + k])[axis];
+        order.sort_by(|a, b| key(a).total_cmp(&key(b)));
+        let triangles: Vec<_> = order.iter().map(|k| self.triangles[first + k]).collect();
+        let indices: Vec<_> = order.iter().map(|k| self.indices[first + k]).collect();
+        self.triangles[first..first + count].copy_from_slice(&triangles);
+        self.indices[first..first + count].copy_from_slice(&indices);
+
+        let half = count / 2;
+        let left = self.nodes.len();
+        for (first, count) in [(first, half), (first + half, count - half)] {
+            self.nodes.push(Node { bounds: self.bounds_of(first, count), first, count });
+        }
+        self.nodes[i].first = left;
+        self.nodes[i].count = 0;
+        self.split(left);
+        self.split(left + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn point() -> impl Strategy<Value = Point3> {
+        (-5.0f32..5.0, -5.0f32..5.0, -5.0f32..5.0).prop_map(|(x, y, z)| Point3::new(x, y, z))
+    }
+
+    proptest! {
+        #[test]
+        fn matches_brute_force(
+            triangles in prop::collection::vec([point(), point(), point()], 0..64),
+            origin in point(),
+            target in point(),
+        ) {
+            let ray = Ray::new(&origin + &Vector3::new(0.0, 0.0, 10.0), &target - &origin);
+            let expected = triangles.iter()
+                .filter_map(|[a, b, c]| ray.intersect_triangle(a, b, c))
+                .map(|hit| hit.distance)
+                .min_by(f32::total_cmp);
+            let bvh = Bvh::new(triangles.clone());
+            let hit = bvh.intersect(&ray);
+            prop_assert_eq!(hit.map(|h| h.distance), expected);
+            if let Some(hit) = hit {
+                let [a, b, c] = &triangles[hit.triangle];
+                prop_assert_eq!(ray.intersect_triangle(a, b, c).map(|h| h.distance), Some(hit.distance));
+            }
+        }
+    }
+
+    #[test]
+    fn finds_closest_of_stacked_quads() {
+        let quad = |z: f32| [
+            [Point3::new(-1.0, -1.0, z), Point3::new(1.0, -1.0, z), Point3::new(1.0, 1.0, z)],
+            [Point3::new(-1.0, -1.0, z), Point3::new(1.0, 1.0, z), Point3::new(-1.0, 1.0, z)],
+        ];
+        let bvh = Bvh::new((0..10).flat_map(|i| quad(i as f32)).collect());
+        assert_eq!(bvh.len(), 20);
+        assert_eq!(bvh.bounds(), Some(Aabb::new(Point3::new(-1.0, -1.0, 0.0), Point3::new(1.0, 1.0, 9.0))));
+
+        let hit = bvh.intersect(&Ray::new(Point3::new(0.5, -0.5, 20.0), Vector3::new(0.0, 0.0, -1.0))).unwrap();
+        assert_eq!((hit.triangle, hit.distance, hit.point), (18, 11.0, Point3::new(0.5, -0.5, 9.0)));
+        assert_eq!(hit.normal, Vector3::z());
+        let hit = bvh.intersect(&Ray::new(Point3::new(-0.5, 0.5, -3.0), Vector3::z())).unwrap();
+        assert_eq!((hit.triangle, hit.distance), (1, 3.0));
+        assert!(bvh.intersect(&Ray::new(Point3::new(2.0, 0.0, 20.0), Vector3::new(0.0, 0.0, -1.0))).is_none());
+        assert!(Bvh::new(Vec::new()).intersect(&Ray::new(Point3::origin(), Vector3::x())).is_none());
+    }
+}
@@ -0,0 +1,98 @@
+use super::{Aabb, Matrix4, Plane, Point3, Sphere, Vector3};
+
+/// The six planes bounding the volume visible through a view-projection matrix
+///
+/// Planes are extracted from the rows of the matrix (Gribb and Hartmann) in
+/// the order left, right, bottom, top, near, far, with normals pointing
+/// inside the volume. The tests are conservative: a volume near a corner of
+/// the frustum may be reported as intersecting while being outside.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the frustum of a view-projection matrix, in world space
+    ///
+    /// Given a projection matrix alone, the frustum is in view space, and given
+    /// a model-view-projection matrix, in model space.
+    pub fn from_matrix(view_projection: &Matrix4) -> Self {
+        let row = |r: usize| [0, 1, 2, 3].map(|c| view_projection.get(r, c));
+        let w = row(3);
+        let plane = |r: [f32; 4], sign: f32| {
+            let [a, b, c, d] = [0, 1, 2, 3].map(|i| w[i] + sign * r[i]);
+            Plane::new(Vector3::new(a, b, c), d)
+        };
+        let (x, y, z) = (row(0), row(1), row(2));
+        Frustum {
+            planes: [plane(x, 1.0), plane(x, -1.0), plane(y, 1.0), plane(y, -1.0), plane(z, 1.0), plane(z, -1.0)],
+        }
+    }
+
+    /// Returns the planes, left, right, bottom, top, near and far
+    pub fn planes(&self) -> &[Plane; 6] {
+        &self.planes
+    }
+
+    /// Returns `true` if the point is inside the frustum or on its boundary
+    pub fn contains_point(&self, point: &Point3) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    /// Returns `false` if the sphere is entirely outside of one of the planes
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(&sphere.center()) >= -sphere.radius())
+    }
+
+    /// Returns `false` if the box is entirely outside of one of the planes
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let (min, max) = (aabb.min(), aabb.max());
+        self.planes.iter().all(|plane| {
+            // Corner of the box furthest along the normal
+            let normal = plane.normal();
+            let corner = Point3::new(
+                if normal.dx() >= 0.0 { max.x() } else { min.x() },
+                if normal.dy() >= 0.0 { max.y() } else { min.y() },
+                if normal.dz() >= 0.0 { max.z() } else { min.z() },
+            );
+            plane.signed_distance(&corner) >= 0.0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn culls_volumes_outside_of_perspective() {
+        let projection = Matrix4::new_perspective(1.0, std::f32::consts::FRAC_PI_2, 1.0, 10.0);
+        let view = Matrix4::look_at_rh(&Point3::new(0.0, 0.0, 5.0), &Point3::origin(), &Vector3::y());
+        let frustum = Frustum::from_matrix(&(&projection * &view));
+        let near = frustum.planes()[4];
+        assert!((near.normal().dz() + 1.0).abs() < 1e-5 && (near.signed_distance(&Point3::new(0.0, 0.0, 4.0))).abs() < 1e-4);
+
+        assert!(frustum.contains_point(&Point3::origin()));
+        assert!(!frustum.contains_point(&Point3::new(0.0, 0.0, 4.5)));
+        assert!(!frustum.contains_point(&Point3::new(0.0, 0.0, -6.0)));
+        // The half-width of the view is the distance to the eye at a 90° field of view
+        assert!(frustum.contains_point(&Point3::new(4.9, 0.0, 0.0)));
+        assert!(!frustum.contains_point(&Point3::new(0.0, -5.1, 0.0)));
+
+        assert!(frustum.intersects_sphere(&Sphere::new(Point3::new(6.0, 0.0, 0.0), 1.0)));
+        assert!(!frustum.intersects_sphere(&Sphere::new(Point3::new(8.0, 0.0, 0.0), 1.0)));
+        assert!(!frustum.intersects_sphere(&Sphere::new(Point3::new(0.0, 0.0, 7.0), 1.0)));
+        assert!(frustum.intersects_aabb(&Aabb::new(Point3::new(4.0, 4.0, -1.0), Point3::new(6.0, 6.0, 1.0))));
+        assert!(!frustum.intersects_aabb(&Aabb::new(Point3::new(7.0, -1.0, -1.0), Point3::new(8.0, 1.0, 1.0))));
+        assert!(!frustum.intersects_aabb(&Aabb::new(Point3::new(-1.0, -1.0, -8.0), Point3::new(1.0, 1.0, -6.0))));
+    }
+
+    #[test]
+    fn orthographic_frustum_is_the_box() {
+        let frustum = Frustum::from_matrix(&Matrix4::new_orthographic(-2.0, 2.0, -1.0, 1.0, 0.0, 10.0));
+        assert!(frustum.contains_point(&Point3::new(1.9, 0.9, -9.9)));
+        assert!(!frustum.contains_point(&Point3::new(2.1, 0.0, -1.0)));
+        assert!(!frustum.contains_point(&Point3::new(0.0, 0.0, 0.1)));
+        assert!(frustum.intersects_aabb(&Aabb::new(Point3::new(1.0, 0.0, -20.0), Point3::new(3.0, 5.0, -9.0))));
+    }
+}
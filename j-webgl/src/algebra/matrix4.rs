@@ -1,6 +1,15 @@
 use super::{Point3, Vector3};
 use std::ops::Mul;
 
+/// A 4x4 matrix of `f32` stored in column-major order
+///
+/// Elements follow the OpenGL/WebGL convention: element `i` of the underlying
+/// array is at row `i % 4` and column `i / 4`, so that the translation of an
+/// affine transform occupies elements 12, 13 and 14. Points and vectors are
+/// column vectors multiplied on the right, so `&a * &b` applies `b` first and
+/// [`as_slice`](Matrix4::as_slice) can be passed directly to
+/// `uniform_matrix4fv` with `transpose` set to `false`.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Matrix4 {
     elements: [f32; 16],
 }
@@ -11,7 +20,27 @@ impl std::convert::From<[f32; 16]> for Matrix4 {
   }
 }
 
+impl std::convert::TryFrom<&[f32]> for Matrix4 {
+  type Error = std::array::TryFromSliceError;
+  fn try_from(elements: &[f32]) -> Result<Self, Self::Error> {
+    Ok(Matrix4 { elements: elements.try_into()? })
+  }
+}
+
+impl Default for Matrix4 {
+  fn default() -> Self { Matrix4::identity() }
+}
+
 impl Matrix4 {
+    /// Returns the identity matrix
+    pub fn identity() -> Self {
+      [ 1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+      ].into()
+    }
+
     pub fn new_perspective(aspect: f32, field_of_view_in_radians: f32, near: f32, far: f32) -> Self {
       // let f = (std::f32::consts::PI * 0.5 - 0.5 * field_of_view_in_radians).tan();
       let f = 1.0 / (0.5 * field_of_view_in_radians).tan();
@@ -24,7 +53,26 @@ impl Matrix4 {
       ].into()
     }
 
+    /// Returns an orthographic projection mapping the box `[left, right] x
+    /// [bottom, top] x [-near, -far]` (in a right-handed view space looking
+    /// down -z) onto the clip-space cube
+    pub fn new_orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+      let rl = 1.0 / (right - left);
+      let tb = 1.0 / (top - bottom);
+      let fneg = 1.0 / (near - far);
+
+      [ 2.0 * rl, 0.0, 0.0, 0.0,
+        0.0, 2.0 * tb, 0.0, 0.0,
+        0.0, 0.0, 2.0 * fneg, 0.0,
+        -(right + left) * rl, -(top + bottom) * tb, (far + near) * fneg, 1.0,
+      ].into()
+    }
+
+    /// Returns the elements in column-major order
     pub fn as_slice(&self) -> &[f32] { &self.elements }
+
+    /// Returns the element at the given row and column
+    pub fn get(&self, row: usize, column: usize) -> f32 { self.elements[column * 4 + row] }
   
     pub fn new_projection(width: f32, height: f32, depth: f32) -> Self {
       // Note: This matrix flips the Y axis so 0 is at the top.
@@ -35,6 +83,7 @@ impl Matrix4 {
       ].into()
     }
 
+    /// Returns the product `self * other`, i.e. the transform applying `other` then `self`
     pub fn multiply(&self, other: &Matrix4) -> Matrix4 {
       multiply(&self.elements, &other.elements).into()
     }
@@ -47,6 +96,131 @@ impl Matrix4 {
       ].into()
     }
 
+    /// Returns a counter-clockwise rotation around the x axis
+    pub fn new_rotation_x(angle_in_radians: f32) -> Matrix4 {
+      let (s, c) = angle_in_radians.sin_cos();
+      [ 1.0, 0.0, 0.0, 0.0,
+        0.0,   c,   s, 0.0,
+        0.0,  -s,   c, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+      ].into()
+    }
+
+    /// Returns a counter-clockwise rotation around the y axis
+    pub fn new_rotation_y(angle_in_radians: f32) -> Matrix4 {
+      let (s, c) = angle_in_radians.sin_cos();
+      [   c, 0.0,  -s, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+          s, 0.0,   c, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+      ].into()
+    }
+
+    /// Returns a counter-clockwise rotation around the z axis
+    pub fn new_rotation_z(angle_in_radians: f32) -> Matrix4 {
+      let (s, c) = angle_in_radians.sin_cos();
+      [   c,   s, 0.0, 0.0,
+         -s,   c, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+      ].into()
+    }
+
+    pub fn new_scaling(sx: f32, sy: f32, sz: f32) -> Matrix4 {
+      [  sx, 0.0, 0.0, 0.0,
+        0.0,  sy, 0.0, 0.0,
+        0.0, 0.0,  sz, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+      ].into()
+    }
+
+    /// Post-multiply by a translation, so that it applies before the current transform
+    pub fn translate(&mut self, tx: f32, ty: f32, tz: f32) -> &mut Self {
+      *self = self.multiply(&Matrix4::new_translation(tx, ty, tz));
+      self
+    }
+
+    /// Post-multiply by a rotation around the x axis
+    pub fn rotate_x(&mut self, angle_in_radians: f32) -> &mut Self {
+      *self = self.multiply(&Matrix4::new_rotation_x(angle_in_radians));
+      self
+    }
+
+    /// Post-multiply by a rotation around the y axis
+    pub fn rotate_y(&mut self, angle_in_radians: f32) -> &mut Self {
+      *self = self.multiply(&Matrix4::new_rotation_y(angle_in_radians));
+      self
+    }
+
+    /// Post-multiply by a rotation around the z axis
+    pub fn rotate_z(&mut self, angle_in_radians: f32) -> &mut Self {
+      *self = self.multiply(&Matrix4::new_rotation_z(angle_in_radians));
+      self
+    }
+
+    /// Post-multiply by a scaling
+    pub fn scale(&mut self, sx: f32, sy: f32, sz: f32) -> &mut Self {
+      *self = self.multiply(&Matrix4::new_scaling(sx, sy, sz));
+      self
+    }
+
+    pub fn transpose(&self) -> Matrix4 {
+      let m = &self.elements;
+      [ m[0], m[4], m[8], m[12],
+        m[1], m[5], m[9], m[13],
+        m[2], m[6], m[10], m[14],
+        m[3], m[7], m[11], m[15],
+      ].into()
+    }
+
+    pub fn determinant(&self) -> f32 {
+      determinant(&self.elements)
+    }
+
+    /// Returns the inverse matrix, or `None` if the matrix is singular
+    pub fn inverse(&self) -> Option<Matrix4> {
+      let d = self.determinant();
+      if d == 0.0 || !d.is_finite() { return None; }
+      Some(inverse(&self.elements).into())
+    }
+
+    /// Returns the matrix transforming normals for this model transform: the
+    /// inverse transpose of its upper 3x3 block, with no translation. Falls
+    /// back to identity when the transform is not invertible.
+    pub fn normal_matrix(&self) -> Matrix4 {
+      let mut linear = *self;
+      for i in [3, 7, 11, 12, 13, 14] { linear.elements[i] = 0.0; }
+      linear.elements[15] = 1.0;
+      linear.inverse().map(|m| m.transpose()).unwrap_or_default()
+    }
+
+    /// Transform a point, applying the perspective division when the
+    /// resulting `w` is neither 0 nor 1
+    pub fn transform_point(&self, point: &Point3) -> Point3 {
+      let m = &self.elements;
+      let (x, y, z) = (point.x(), point.y(), point.z());
+      let tx = m[0] * x + m[4] * y + m[8] * z + m[12];
+      let ty = m[1] * x + m[5] * y + m[9] * z + m[13];
+      let tz = m[2] * x + m[6] * y + m[10] * z + m[14];
+      let w = m[3] * x + m[7] * y + m[11] * z + m[15];
+      if w != 0.0 && w != 1.0 {
+        Point3::new(tx / w, ty / w, tz / w)
+      } else {
+        Point3::new(tx, ty, tz)
+      }
+    }
+
+    /// Transform a vector, ignoring the translation part of the matrix
+    pub fn transform_vector(&self, vector: &Vector3) -> Vector3 {
+      let m = &self.elements;
+      let (x, y, z) = (vector.dx(), vector.dy(), vector.dz());
+      Vector3::new(
+        m[0] * x + m[4] * y + m[8] * z,
+        m[1] * x + m[5] * y + m[9] * z,
+        m[2] * x + m[6] * y + m[10] * z,
+      )
+    }
+
     pub fn look_at_rh(camera_position: &Point3, target: &Point3, up: &Vector3) -> Matrix4 {
       let z_axis = (camera_position - target).normalize();
       let x_axis = up.cross(&z_axis).normalize();
@@ -62,74 +236,6 @@ impl Matrix4 {
         1.0,
       ]).into()
     }
-  
-  /*
-    xRotation: function(angleInRadians) {
-      var c = Math.cos(angleInRadians);
-      var s = Math.sin(angleInRadians);
-  
-      return [
-        1, 0, 0, 0,
-        0, c, s, 0,
-        0, -s, c, 0,
-        0, 0, 0, 1,
-      ];
-    },
-  
-    yRotation: function(angleInRadians) {
-      var c = Math.cos(angleInRadians);
-      var s = Math.sin(angleInRadians);
-  
-      return [
-        c, 0, -s, 0,
-        0, 1, 0, 0,
-        s, 0, c, 0,
-        0, 0, 0, 1,
-      ];
-    },
-  
-    zRotation: function(angleInRadians) {
-      var c = Math.cos(angleInRadians);
-      var s = Math.sin(angleInRadians);
-  
-      return [
-         c, s, 0, 0,
-        -s, c, 0, 0,
-         0, 0, 1, 0,
-         0, 0, 0, 1,
-      ];
-    },
-  
-    scaling: function(sx, sy, sz) {
-      return [
-        sx, 0,  0,  0,
-        0, sy,  0,  0,
-        0,  0, sz,  0,
-        0,  0,  0,  1,
-      ];
-    },
-  
-    translate: function(m, tx, ty, tz) {
-      return m4.multiply(m, m4.translation(tx, ty, tz));
-    },
-  
-    xRotate: function(m, angleInRadians) {
-      return m4.multiply(m, m4.xRotation(angleInRadians));
-    },
-  
-    yRotate: function(m, angleInRadians) {
-      return m4.multiply(m, m4.yRotation(angleInRadians));
-    },
-  
-    zRotate: function(m, angleInRadians) {
-      return m4.multiply(m, m4.zRotation(angleInRadians));
-    },
-  
-    scale: function(m, sx, sy, sz) {
-      return m4.multiply(m, m4.scaling(sx, sy, sz));
-    },
-  
-    */
 }
 
 impl Mul<&Matrix4> for &Matrix4 {
@@ -193,6 +299,24 @@ fn multiply(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
       ]
 }
 
+fn determinant(m: &[f32; 16]) -> f32 {
+      // Laplace expansion along the first two columns: each 2x2 minor s of
+      // these columns is multiplied by the complementary minor c of the last two
+      let s0 = m[0] * m[5] - m[4] * m[1];
+      let s1 = m[0] * m[6] - m[4] * m[2];
+      let s2 = m[0] * m[7] - m[4] * m[3];
+      let s3 = m[1] * m[6] - m[5] * m[2];
+      let s4 = m[1] * m[7] - m[5] * m[3];
+      let s5 = m[2] * m[7] - m[6] * m[3];
+      let c5 = m[10] * m[15] - m[14] * m[11];
+      let c4 = m[9] * m[15] - m[13] * m[11];
+      let c3 = m[9] * m[14] - m[13] * m[10];
+      let c2 = m[8] * m[15] - m[12] * m[11];
+      let c1 = m[8] * m[14] - m[12] * m[10];
+      let c0 = m[8] * m[13] - m[12] * m[9];
+      s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0
+}
+
 fn inverse(m: &[f32; 16]) -> [f32; 16] {
       let m00 = m[        0];
       let m01 = m[        1];
@@ -277,3 +401,107 @@ fn inverse(m: &[f32; 16]) -> [f32; 16] {
              (tmp_20 * m12 + tmp_23 * m22 + tmp_17 * m02)),
       ]
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+
+  fn assert_close(a: &Matrix4, b: &Matrix4, tolerance: f32) {
+    for (x, y) in a.as_slice().iter().zip(b.as_slice()) {
+      assert!((x - y).abs() <= tolerance, "{:?} != {:?}", a, b);
+    }
+  }
+
+  /// Affine transforms composed of a translation, rotations and a non-degenerate scaling
+  fn transform() -> impl Strategy<Value = Matrix4> {
+    let angle = -std::f32::consts::PI..std::f32::consts::PI;
+    let scale = prop_oneof![-5.0f32..-0.2, 0.2f32..5.0];
+    (
+      (-10.0f32..10.0, -10.0f32..10.0, -10.0f32..10.0),
+      (angle.clone(), angle.clone(), angle),
+      (scale.clone(), scale.clone(), scale),
+    ).prop_map(|((tx, ty, tz), (ax, ay, az), (sx, sy, sz))| {
+      let mut m = Matrix4::new_translation(tx, ty, tz);
+      m.rotate_x(ax).rotate_y(ay).rotate_z(az).scale(sx, sy, sz);
+      m
+    })
+  }
+
+  proptest! {
+    #[test]
+    fn inverse_is_left_and_right_inverse(m in transform()) {
+      let inverse = m.inverse().unwrap();
+      assert_close(&(&m * &inverse), &Matrix4::identity(), 1e-4);
+      assert_close(&(&inverse * &m), &Matrix4::identity(), 1e-4);
+    }
+
+    #[test]
+    fn transpose_is_an_involution_preserving_determinant(m in transform()) {
+      assert_eq!(m.transpose().transpose(), m);
+      prop_assert!((m.transpose().determinant() - m.determinant()).abs() <= 1e-3 * m.determinant().abs().max(1.0));
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular(m in transform(), x in -5.0f32..5.0, y in -5.0f32..5.0, z in -5.0f32..5.0) {
+      // (x, y, z) is tangent to the plane of normal (-y, x, 0)
+      let tangent = m.transform_vector(&Vector3::new(x, y, z));
+      let normal = m.normal_matrix().transform_vector(&Vector3::new(-y, x, 0.0));
+      prop_assert!(tangent.dot(&normal).abs() <= 1e-2 * tangent.norm().max(1.0) * normal.norm().max(1.0));
+    }
+
+    #[test]
+    fn determinant_is_multiplicative(a in transform(), b in transform()) {
+      let expected = a.determinant() * b.determinant();
+      prop_assert!(((&a * &b).determinant() - expected).abs() <= 1e-3 * expected.abs().max(1.0));
+    }
+
+    #[test]
+    fn transform_point_matches_composition(a in transform(), b in transform(), x in -5.0f32..5.0, y in -5.0f32..5.0, z in -5.0f32..5.0) {
+      let p = Point3::new(x, y, z);
+      let expected = a.transform_point(&b.transform_point(&p));
+      let actual = (&a * &b).transform_point(&p);
+      prop_assert!((&expected - &actual).dot(&(&expected - &actual)).sqrt() <= 1e-2);
+    }
+
+    #[test]
+    fn rotations_preserve_length(angle in -10.0f32..10.0, x in -5.0f32..5.0, y in -5.0f32..5.0, z in -5.0f32..5.0) {
+      let v = Vector3::new(x, y, z);
+      for m in [Matrix4::new_rotation_x(angle), Matrix4::new_rotation_y(angle), Matrix4::new_rotation_z(angle)] {
+        let r = m.transform_vector(&v);
+        prop_assert!((r.dot(&r) - v.dot(&v)).abs() <= 1e-3 * v.dot(&v).max(1.0));
+        prop_assert!((m.determinant() - 1.0).abs() <= 1e-5);
+      }
+    }
+  }
+
+  #[test]
+  fn rotations_are_counter_clockwise() {
+    let quarter = std::f32::consts::FRAC_PI_2;
+    let close = |a: Vector3, b: Vector3| (&a + &(-1.0 * &b)).dot(&(&a + &(-1.0 * &b))) < 1e-10;
+    assert!(close(Matrix4::new_rotation_x(quarter).transform_vector(&Vector3::y()), Vector3::z()));
+    assert!(close(Matrix4::new_rotation_y(quarter).transform_vector(&Vector3::z()), Vector3::x()));
+    assert!(close(Matrix4::new_rotation_z(quarter).transform_vector(&Vector3::x()), Vector3::y()));
+  }
+
+  #[test]
+  fn translation_moves_points_but_not_vectors() {
+    let m = Matrix4::new_translation(1.0, 2.0, 3.0);
+    assert_eq!(m.transform_point(&Point3::origin()), Point3::new(1.0, 2.0, 3.0));
+    assert_eq!(m.transform_vector(&Vector3::x()), Vector3::x());
+    assert_eq!(m.get(0, 3), 1.0);
+  }
+
+  #[test]
+  fn orthographic_maps_box_onto_clip_cube() {
+    let m = Matrix4::new_orthographic(-2.0, 4.0, -1.0, 3.0, 0.5, 10.0);
+    let distance = |a: Point3, b: Point3| (&a - &b).dot(&(&a - &b)).sqrt();
+    assert!(distance(m.transform_point(&Point3::new(-2.0, -1.0, -0.5)), Point3::new(-1.0, -1.0, -1.0)) < 1e-5);
+    assert!(distance(m.transform_point(&Point3::new(4.0, 3.0, -10.0)), Point3::new(1.0, 1.0, 1.0)) < 1e-5);
+  }
+
+  #[test]
+  fn singular_matrix_has_no_inverse() {
+    assert!(Matrix4::new_scaling(1.0, 0.0, 1.0).inverse().is_none());
+  }
+}
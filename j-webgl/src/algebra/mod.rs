@@ -1,3 +1,10 @@
 mod point3; pub use point3::Point3;
 mod vector3; pub use vector3::Vector3;
 mod matrix4; pub use matrix4::Matrix4;
+mod quaternion; pub use quaternion::Quaternion;
+mod aabb; pub use aabb::Aabb;
+mod sphere; pub use sphere::Sphere;
+mod plane; pub use plane::Plane;
+mod ray; pub use ray::{Ray, TriangleHit};
+mod bvh; pub use bvh::{Bvh, MeshHit};
+mod frustum; pub use frustum::Frustum;
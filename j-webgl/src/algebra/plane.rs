@@ -0,0 +1,47 @@
+use super::{Point3, Vector3};
+
+/// A plane of points `p` such that `normal . p + distance = 0`
+///
+/// The normal is unit length, so that [`signed_distance`](Plane::signed_distance)
+/// is a Euclidean distance, positive on the side the normal points to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    normal: Vector3,
+    distance: f32,
+}
+
+impl Plane {
+    /// Creates the plane `normal . p + distance = 0`, normalizing the equation
+    ///
+    /// A zero normal yields a degenerate plane with a zero normal.
+    pub fn new(normal: Vector3, distance: f32) -> Self {
+        let norm = normal.norm();
+        if norm > 0.0 {
+            Plane { normal: (1.0 / norm) * &normal, distance: distance / norm }
+        } else {
+            Plane { normal: Vector3::zero(), distance: 0.0 }
+        }
+    }
+
+    /// Creates the plane through `point` perpendicular to `normal`
+    pub fn from_point_normal(point: &Point3, normal: &Vector3) -> Self {
+        let normal = normal.normalize();
+        let distance = -normal.dot(&(point - &Point3::origin()));
+        Plane { normal, distance }
+    }
+
+    /// Returns the unit normal
+    pub fn normal(&self) -> Vector3 {
+        self.normal
+    }
+
+    /// Returns the signed distance of the plane from the origin, along minus the normal
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    /// Returns the signed distance from the plane to a point
+    pub fn signed_distance(&self, point: &Point3) -> f32 {
+        self.normal.dot(&(point - &Point3::origin())) + self.distance
+    }
+}
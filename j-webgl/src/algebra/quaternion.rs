@@ -0,0 +1,254 @@
+use super::{Matrix4, Point3, Vector3};
+use std::ops::Mul;
+
+/// A quaternion `w + xi + yj + zk` representing an orientation in 3D space
+///
+/// Rotations are expected to use unit quaternions. Products compose like
+/// matrices: `&a * &b` applies `b` first, then `a`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    x: f32,
+    y: f32,
+    z: f32,
+    w: f32,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self { Quaternion::identity() }
+}
+
+impl Quaternion {
+    /// Creates a new Quaternion with the given vector (x, y, z) and scalar (w) parts
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Quaternion { x, y, z, w }
+    }
+
+    /// Returns the identity rotation
+    pub fn identity() -> Self {
+        Quaternion::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    /// Creates a counter-clockwise rotation of `angle_in_radians` around `axis`
+    ///
+    /// The axis does not need to be normalized. A zero axis yields the identity.
+    pub fn from_axis_angle(axis: &Vector3, angle_in_radians: f32) -> Self {
+        let axis = axis.normalize();
+        let (s, c) = (0.5 * angle_in_radians).sin_cos();
+        Quaternion::new(axis.dx() * s, axis.dy() * s, axis.dz() * s, c)
+    }
+
+    /// Creates a rotation from Euler angles applied around the x, then y, then z axis
+    ///
+    /// The result is equivalent to
+    /// `Matrix4::new_rotation_z(z) * Matrix4::new_rotation_y(y) * Matrix4::new_rotation_x(x)`.
+    pub fn from_euler(x: f32, y: f32, z: f32) -> Self {
+        let qx = Quaternion::from_axis_angle(&Vector3::x(), x);
+        let qy = Quaternion::from_axis_angle(&Vector3::y(), y);
+        let qz = Quaternion::from_axis_angle(&Vector3::z(), z);
+        &(&qz * &qy) * &qx
+    }
+
+    /// Extracts the rotation of a matrix whose upper 3x3 part is a rotation
+    ///
+    /// Any scaling is removed by normalizing the matrix columns.
+    pub fn from_matrix4(matrix: &Matrix4) -> Self {
+        let column = |c: usize| Vector3::new(matrix.get(0, c), matrix.get(1, c), matrix.get(2, c)).normalize();
+        let (c0, c1, c2) = (column(0), column(1), column(2));
+        let (m00, m10, m20) = (c0.dx(), c0.dy(), c0.dz());
+        let (m01, m11, m21) = (c1.dx(), c1.dy(), c1.dz());
+        let (m02, m12, m22) = (c2.dx(), c2.dy(), c2.dz());
+
+        let trace = m00 + m11 + m22;
+        let q = if trace > 0.0 {
+            let s = 0.5 / (trace + 1.0).sqrt();
+            Quaternion::new((m21 - m12) * s, (m02 - m20) * s, (m10 - m01) * s, 0.25 / s)
+        } else if m00 > m11 && m00 > m22 {
+            let s = 2.0 * (1.0 + m00 - m11 - m22).sqrt();
+            Quaternion::new(0.25 * s, (m01 + m10) / s, (m02 + m20) / s, (m21 - m12) / s)
+        } else if m11 > m22 {
+            let s = 2.0 * (1.0 + m11 - m00 - m22).sqrt();
+            Quaternion::new((m01 + m10) / s, 0.25 * s, (m12 + m21) / s, (m02 - m20) / s)
+        } else {
+            let s = 2.0 * (1.0 + m22 - m00 - m11).sqrt();
+            Quaternion::new((m02 + m20) / s, (m12 + m21) / s, 0.25 * s, (m10 - m01) / s)
+        };
+        q.normalize()
+    }
+
+    /// Returns the rotation matrix for this (unit) quaternion
+    pub fn to_matrix4(&self) -> Matrix4 {
+        let Quaternion { x, y, z, w } = *self;
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (wx, wy, wz) = (w * x, w * y, w * z);
+        [ 1.0 - 2.0 * (yy + zz), 2.0 * (xy + wz), 2.0 * (xz - wy), 0.0,
+          2.0 * (xy - wz), 1.0 - 2.0 * (xx + zz), 2.0 * (yz + wx), 0.0,
+          2.0 * (xz + wy), 2.0 * (yz - wx), 1.0 - 2.0 * (xx + yy), 0.0,
+          0.0, 0.0, 0.0, 1.0,
+        ].into()
+    }
+
+    /// Returns the x component of the vector part
+    pub fn x(&self) -> f32 { self.x }
+
+    /// Returns the y component of the vector part
+    pub fn y(&self) -> f32 { self.y }
+
+    /// Returns the z component of the vector part
+    pub fn z(&self) -> f32 { self.z }
+
+    /// Returns the scalar part
+    pub fn w(&self) -> f32 { self.w }
+
+    /// Computes the dot product of two quaternions
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// Returns the norm (magnitude) of the quaternion
+    pub fn norm(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns a new normalized quaternion
+    /// If the quaternion has zero length, returns the identity
+    pub fn normalize(&self) -> Self {
+        let n = self.norm();
+        if n > 0.0 {
+            Quaternion::new(self.x / n, self.y / n, self.z / n, self.w / n)
+        } else {
+            Quaternion::identity()
+        }
+    }
+
+    /// Returns the conjugate, which is the inverse rotation for unit quaternions
+    pub fn conjugate(&self) -> Self {
+        Quaternion::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Returns the product `self * other`
+    pub fn multiply(&self, other: &Quaternion) -> Quaternion {
+        let (a, b) = (self, other);
+        Quaternion::new(
+            a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+            a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+        )
+    }
+
+    /// Spherical linear interpolation between `self` (`t = 0`) and `other` (`t = 1`)
+    ///
+    /// Interpolation follows the shortest arc. Nearly identical orientations
+    /// fall back to a normalized linear interpolation.
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Quaternion {
+        let mut cos_theta = self.dot(other);
+        let other = if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            Quaternion::new(-other.x, -other.y, -other.z, -other.w)
+        } else {
+            *other
+        };
+
+        let (a, b) = if cos_theta > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (((1.0 - t) * theta).sin() / sin_theta, (t * theta).sin() / sin_theta)
+        };
+        Quaternion::new(
+            a * self.x + b * other.x,
+            a * self.y + b * other.y,
+            a * self.z + b * other.z,
+            a * self.w + b * other.w,
+        ).normalize()
+    }
+
+    /// Rotate a vector by this (unit) quaternion
+    pub fn rotate_vector(&self, vector: &Vector3) -> Vector3 {
+        // v' = v + 2w (q x v) + 2 q x (q x v), with q the vector part
+        let q = Vector3::new(self.x, self.y, self.z);
+        let t = 2.0 * &q.cross(vector);
+        let v = vector + &(self.w * &t);
+        &v + &q.cross(&t)
+    }
+
+    /// Rotate a point around the origin by this (unit) quaternion
+    pub fn rotate_point(&self, point: &Point3) -> Point3 {
+        &Point3::origin() + &self.rotate_vector(&(point - &Point3::origin()))
+    }
+}
+
+impl Mul<&Quaternion> for &Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, other: &Quaternion) -> Quaternion {
+        self.multiply(other)
+    }
+}
+
+impl From<&Quaternion> for Matrix4 {
+    fn from(quaternion: &Quaternion) -> Self {
+        quaternion.to_matrix4()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn assert_vector_close(a: &Vector3, b: &Vector3) {
+        let d = Vector3::new(a.dx() - b.dx(), a.dy() - b.dy(), a.dz() - b.dz());
+        assert!(d.norm() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    fn unit() -> impl Strategy<Value = Quaternion> {
+        (-1.0f32..1.0, -1.0f32..1.0, -1.0f32..1.0, -1.0f32..1.0)
+            .prop_filter("non-zero quaternion", |(x, y, z, w)| x * x + y * y + z * z + w * w > 1e-2)
+            .prop_map(|(x, y, z, w)| Quaternion::new(x, y, z, w).normalize())
+    }
+
+    proptest! {
+        #[test]
+        fn rotation_matches_matrix(q in unit(), x in -5.0f32..5.0, y in -5.0f32..5.0, z in -5.0f32..5.0) {
+            let v = Vector3::new(x, y, z);
+            assert_vector_close(&q.rotate_vector(&v), &q.to_matrix4().transform_vector(&v));
+        }
+
+        #[test]
+        fn matrix_round_trip(q in unit()) {
+            let r = Quaternion::from_matrix4(&q.to_matrix4());
+            // q and -q represent the same rotation
+            prop_assert!((r.dot(&q).abs() - 1.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn product_composes_rotations(a in unit(), b in unit()) {
+            let v = Vector3::new(1.0, 2.0, 3.0);
+            assert_vector_close(&(&a * &b).rotate_vector(&v), &a.rotate_vector(&b.rotate_vector(&v)));
+        }
+    }
+
+    #[test]
+    fn euler_matches_matrix_composition() {
+        let (x, y, z) = (0.3, -1.2, 2.1);
+        let expected = &(&Matrix4::new_rotation_z(z) * &Matrix4::new_rotation_y(y)) * &Matrix4::new_rotation_x(x);
+        let v = Vector3::new(0.5, -1.0, 2.0);
+        assert_vector_close(&Quaternion::from_euler(x, y, z).rotate_vector(&v), &expected.transform_vector(&v));
+    }
+
+    #[test]
+    fn slerp_interpolates_along_shortest_arc() {
+        let a = Quaternion::identity();
+        let b = Quaternion::from_axis_angle(&Vector3::z(), std::f32::consts::FRAC_PI_2);
+        assert_eq!(a.slerp(&b, 0.0), a);
+        assert!((a.slerp(&b, 1.0).dot(&b) - 1.0).abs() < 1e-6);
+        let half = a.slerp(&b, 0.5);
+        assert_vector_close(
+            &half.rotate_vector(&Vector3::x()),
+            &Vector3::new(std::f32::consts::FRAC_1_SQRT_2, std::f32::consts::FRAC_1_SQRT_2, 0.0),
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use super::{Aabb, Matrix4, Plane, Point3, Sphere, Vector3};
+
+/// A half-line starting at an origin, with a unit direction
+///
+/// Intersection tests return distances along the direction, which are
+/// Euclidean distances from the origin: [`at`](Ray::at) gives the hit point.
+/// Only hits at a non-negative distance are reported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    origin: Point3,
+    direction: Vector3,
+}
+
+/// Intersection of a [`Ray`] with a triangle `(a, b, c)`
+///
+/// The hit point is `(1 - u - v) * a + u * b + v * c`, so that vertex
+/// attributes can be interpolated with the same barycentric coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleHit {
+    pub distance: f32,
+    pub u: f32,
+    pub v: f32,
+}
+
+impl Ray {
+    /// Creates a new Ray, normalizing the direction
+    pub fn new(origin: Point3, direction: Vector3) -> Self {
+        Ray { origin, direction: direction.normalize() }
+    }
+
+    /// Creates the ray through a pixel of a viewport, from the near to the far plane
+    ///
+    /// `x` and `y` are measured from the top left corner of a viewport of
+    /// `width` by `height`, in the same unit, and are unprojected with the
+    /// inverse of `view_projection`. Pass the center of a pixel, `x + 0.5`,
+    /// to match rasterization. Returns `None` if the matrix is singular or
+    /// the viewport empty.
+    pub fn from_screen(view_projection: &Matrix4, x: f32, y: f32, width: f32, height: f32) -> Option<Self> {
+        if width <= 0.0 || height <= 0.0 { return None; }
+        let inverse = view_projection.inverse()?;
+        let (ndc_x, ndc_y) = (2.0 * x / width - 1.0, 1.0 - 2.0 * y / height);
+        let near = inverse.transform_point(&Point3::new(ndc_x, ndc_y, -1.0));
+        let far = inverse.transform_point(&Point3::new(ndc_x, ndc_y, 1.0));
+        let direction = &far - &near;
+        if direction.norm() == 0.0 || !direction.norm().is_finite() { return None; }
+        Some(Ray::new(near, direction))
+    }
+
+    /// Returns the origin
+    pub fn origin(&self) -> Point3 {
+        self.origin
+    }
+
+    /// Returns the unit direction
+    pub fn direction(&self) -> Vector3 {
+        self.direction
+    }
+
+    /// Returns the point at `distance` from the origin
+    pub fn at(&self, distance: f32) -> Point3 {
+        &self.origin + &(distance * &self.direction)
+    }
+
+    /// Returns the ray transformed by an affine matrix, such as from world to
+    /// model space with the inverse model matrix
+    ///
+    /// Distances along the transformed ray are not the same as along this ray
+    /// when the matrix scales: compare hit points rather than distances.
+    pub fn transform(&self, matrix: &Matrix4) -> Self {
+        Ray::new(matrix.transform_point(&self.origin), matrix.transform_vector(&self.direction))
+    }
+
+    /// Returns the distance to the first point of the box surface hit,
+    /// where the ray leaves the box if its origin is inside
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let (near, far) = self.aabb_range(aabb, f32::INFINITY)?;
+        Some(if near > 0.0 { near } else { far })
+    }
+
+    /// Returns the range of distances within the box, clipped to `[0, max_distance]`
+    pub(super) fn aabb_range(&self, aabb: &Aabb, max_distance: f32) -> Option<(f32, f32)> {
+        let (min, max) = (aabb.min(), aabb.max());
+        let axes = [
+            (self.origin.x(), self.direction.dx(), min.x(), max.x()),
+            (self.origin.y(), self.direction.dy(), min.y(), max.y()),
+            (self.origin.z(), self.direction.dz(), min.z(), max.z()),
+        ];
+        let (mut near, mut far) = (0.0f32, max_distance);
+        for (origin, direction, min, max) in axes {
+            if direction == 0.0 {
+                if origin < min || origin > max { return None; }
+                continue;
+            }
+            let (t0, t1) = ((min - origin) / direction, (max - origin) / direction);
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+            if near > far { return None; }
+        }
+        Some((near, far))
+    }
+
+    /// Returns the distance to the first point of the sphere surface hit,
+    /// where the ray leaves the sphere if its origin is inside
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        let offset = &self.origin - &sphere.center();
+        let b = offset.dot(&self.direction);
+        let c = offset.dot(&offset) - sphere.radius() * sphere.radius();
+        let discriminant = b * b - c;
+        if discriminant < 0.0 { return None; }
+        let root = discriminant.sqrt();
+        let (near, far) = (-b - root, -b + root);
+        if far < 0.0 { return None; }
+        Some(if near >= 0.0 { near } else { far })
+    }
+
+    /// Returns the distance to the plane, `None` if the ray is parallel to
+    /// the plane or points away from it
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<f32> {
+        let denominator = plane.normal().dot(&self.direction);
+        if denominator == 0.0 { return None; }
+        let distance = -plane.signed_distance(&self.origin) / denominator;
+        (distance >= 0.0).then_some(distance)
+    }
+
+    /// Intersects a triangle from either side with the Möller–Trumbore algorithm
+    ///
+    /// Returns `None` for rays in the plane of the triangle and for degenerate triangles.
+    pub fn intersect_triangle(&self, a: &Point3, b: &Point3, c: &Point3) -> Option<TriangleHit> {
+        let (edge1, edge2) = (b - a, c - a);
+        let p = self.direction.cross(&edge2);
+        let determinant = edge1.dot(&p);
+        if determinant.abs() <= f32::EPSILON * edge1.norm() * edge2.norm() { return None; }
+        let inverse = 1.0 / determinant;
+
+        let s = &self.origin - a;
+        let u = s.dot(&p) * inverse;
+        if !(0.0..=1.0).contains(&u) { return None; }
+        let q = s.cross(&edge1);
+        let v = self.direction.dot(&q) * inverse;
+        if v < 0.0 || u + v > 1.0 { return None; }
+        let distance = edge2.dot(&q) * inverse;
+        (distance >= 0.0).then_some(TriangleHit { distance, u, v })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn assert_point_close(a: &Point3, b: &Point3, tolerance: f32) {
+        assert!((a - b).norm() <= tolerance, "{:?} != {:?}", a, b);
+    }
+
+    fn camera() -> Matrix4 {
+        let projection = Matrix4::new_perspective(2.0, std::f32::consts::FRAC_PI_4, 0.1, 100.0);
+        let view = Matrix4::look_at_rh(&Point3::new(0.0, 0.0, 5.0), &Point3::origin(), &Vector3::y());
+        &projection * &view
+    }
+
+    proptest! {
+        #[test]
+        fn screen_ray_passes_through_projected_point(x in -1.0f32..1.0, y in -1.0f32..1.0, z in -2.0f32..2.0) {
+            let view_projection = camera();
+            let point = Point3::new(x, y, z);
+            let ndc = view_projection.transform_point(&point);
+            let (width, height) = (800.0, 400.0);
+            let ray = Ray::from_screen(&view_projection, (ndc.x() + 1.0) * 0.5 * width, (1.0 - ndc.y()) * 0.5 * height, width, height).unwrap();
+            let distance = (&point - &ray.origin()).dot(&ray.direction());
+            prop_assert!(distance > 0.0);
+            prop_assert!((&ray.at(distance) - &point).norm() <= 1e-3);
+        }
+    }
+
+    #[test]
+    fn screen_center_looks_at_target() {
+        let ray = Ray::from_screen(&camera(), 400.0, 200.0, 800.0, 400.0).unwrap();
+        assert_point_close(&ray.origin(), &Point3::new(0.0, 0.0, 4.9), 1e-4);
+        assert!((&ray.direction() - &Vector3::new(0.0, 0.0, -1.0)).norm() < 1e-5);
+        let top_left = Ray::from_screen(&camera(), 0.0, 0.0, 800.0, 400.0).unwrap();
+        assert!(top_left.direction().dx() < 0.0 && top_left.direction().dy() > 0.0);
+        assert!(Ray::from_screen(&Matrix4::new_scaling(1.0, 0.0, 1.0), 0.0, 0.0, 1.0, 1.0).is_none());
+        assert!(Ray::from_screen(&camera(), 0.0, 0.0, 0.0, 400.0).is_none());
+    }
+
+    #[test]
+    fn intersects_shapes() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -2.0));
+        let aabb = Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        assert_eq!(ray.intersect_aabb(&aabb), Some(4.0));
+        assert_eq!(Ray::new(Point3::origin(), Vector3::x()).intersect_aabb(&aabb), Some(1.0));
+        assert_eq!(Ray::new(Point3::new(0.0, 2.0, 5.0), Vector3::new(0.0, 0.0, -1.0)).intersect_aabb(&aabb), None);
+        assert_eq!(Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::z()).intersect_aabb(&aabb), None);
+
+        let sphere = Sphere::new(Point3::new(0.0, 0.0, -1.0), 2.0);
+        assert_eq!(ray.intersect_sphere(&sphere), Some(4.0));
+        assert_eq!(Ray::new(Point3::new(0.0, 0.0, -1.0), Vector3::y()).intersect_sphere(&sphere), Some(2.0));
+        assert_eq!(Ray::new(Point3::new(3.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0)).intersect_sphere(&sphere), None);
+
+        let plane = Plane::from_point_normal(&Point3::new(0.0, 0.0, -2.0), &Vector3::z());
+        assert_eq!(ray.intersect_plane(&plane), Some(7.0));
+        assert_eq!(Ray::new(Point3::origin(), Vector3::x()).intersect_plane(&plane), None);
+        assert_eq!(Ray::new(Point3::origin(), Vector3::z()).intersect_plane(&plane), None);
+    }
+
+    #[test]
+    fn intersects_triangles_from_both_sides() {
+        let (a, b, c) = (Point3::new(0.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0), Point3::new(0.0, 2.0, 0.0));
+        let front = Ray::new(Point3::new(0.5, 1.0, 3.0), Vector3::new(0.0, 0.0, -1.0));
+        let hit = front.intersect_triangle(&a, &b, &c).unwrap();
+        assert_eq!(hit, TriangleHit { distance: 3.0, u: 0.25, v: 0.5 });
+        assert_point_close(&front.at(hit.distance), &Point3::new(0.5, 1.0, 0.0), 1e-6);
+
+        let back = Ray::new(Point3::new(0.5, 1.0, -3.0), Vector3::z());
+        assert_eq!(back.intersect_triangle(&a, &b, &c).map(|h| h.distance), Some(3.0));
+        assert!(Ray::new(Point3::new(1.5, 1.5, 3.0), Vector3::new(0.0, 0.0, -1.0)).intersect_triangle(&a, &b, &c).is_none());
+        assert!(Ray::new(Point3::new(-1.0, 0.5, 0.0), Vector3::x()).intersect_triangle(&a, &b, &c).is_none());
+        assert!(front.intersect_triangle(&a, &b, &b).is_none());
+    }
+}
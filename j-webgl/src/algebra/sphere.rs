@@ -0,0 +1,34 @@
+use super::{Matrix4, Point3, Vector3};
+
+/// A sphere given by its center and radius
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere {
+    center: Point3,
+    radius: f32,
+}
+
+impl Sphere {
+    /// Creates a new Sphere, a negative radius being replaced by its absolute value
+    pub fn new(center: Point3, radius: f32) -> Self {
+        Sphere { center, radius: radius.abs() }
+    }
+
+    /// Returns the center
+    pub fn center(&self) -> Point3 {
+        self.center
+    }
+
+    /// Returns the radius
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Returns the sphere bounding this sphere transformed by an affine matrix,
+    /// scaled by the largest scaling of the matrix
+    pub fn transform(&self, matrix: &Matrix4) -> Self {
+        let scale = [Vector3::x(), Vector3::y(), Vector3::z()].iter()
+            .map(|axis| matrix.transform_vector(axis).norm())
+            .fold(0.0, f32::max);
+        Sphere::new(matrix.transform_point(&self.center), self.radius * scale)
+    }
+}
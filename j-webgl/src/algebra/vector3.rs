@@ -1,4 +1,4 @@
-use std::ops::{Add, Mul};
+use std::ops::{Add, Mul, Sub};
 
 /// A vector in 3D space with dx, dy, dz components
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -50,7 +50,7 @@ impl Vector3 {
     }
 
     /// Returns the norm (magnitude) of the vector
-    fn norm(&self) -> f32 {
+    pub fn norm(&self) -> f32 {
         (self.dx * self.dx + self.dy * self.dy + self.dz * self.dz).sqrt()
     }
 
@@ -97,6 +97,19 @@ impl Add for &Vector3 {
     }
 }
 
+// Implementation for v1 = v2 - v3
+impl Sub for &Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, other: Self) -> Vector3 {
+        Vector3 {
+            dx: self.dx - other.dx,
+            dy: self.dy - other.dy,
+            dz: self.dz - other.dz,
+        }
+    }
+}
+
 // Implementation for v1 = a * v2 (scalar multiplication from the left)
 impl Mul<&Vector3> for f32 {
     type Output = Vector3;
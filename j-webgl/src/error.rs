@@ -10,6 +10,24 @@ pub enum Error {
 
     #[error(transparent)]
     TryFromIntError(#[from] std::num::TryFromIntError),
+    #[error(transparent)]
+    TryFromSliceError(#[from] std::array::TryFromSliceError),
+    #[error("Invalid mesh: {0}")]
+    InvalidMesh(String),
+    #[error("OBJ line {line}: {message}")]
+    ObjParse { line: usize, message: String },
+    #[error("MTL line {line}: {message}")]
+    MtlParse { line: usize, message: String },
+    #[error("STL: {0}")]
+    StlParse(String),
+    #[error("PLY: {0}")]
+    PlyParse(String),
+    #[error("glTF: {0}")]
+    GltfParse(String),
+    #[error("Image decode: {0}")]
+    ImageDecode(String),
+    #[error("Invalid texture: {0}")]
+    InvalidTexture(String),
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
     #[error("{0}")]
@@ -0,0 +1,179 @@
+use super::{Result, traits, algebra, structs::light::Light, shadow::FrameShadow};
+use std::rc::Rc;
+
+/// Per-frame information passed to [`Renderable::render`](crate::Renderable::render)
+///
+/// The frame context carries the camera view and projection matrices, their
+/// product, the eye position in world space and the viewport size in pixels,
+/// so that renderables can do lighting in world or view space, compute
+/// specular highlights or orient billboards.
+///
+/// The [`Renderer`](crate::Renderer) hands each renderable a copy carrying its
+/// model transform, see [`with_model`](FrameContext::with_model), so that
+/// [`mvp`](FrameContext::mvp) places the object in the scene, along with the
+/// lights registered with the renderer, see [`with_lights`](FrameContext::with_lights),
+/// and the shadow map rendered for the frame, see [`with_shadow`](FrameContext::with_shadow).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameContext {
+  view: algebra::Matrix4,
+  projection: algebra::Matrix4,
+  view_projection: algebra::Matrix4,
+  eye_position: algebra::Point3,
+  viewport: (i32, i32),
+  model: algebra::Matrix4,
+  model_view_projection: algebra::Matrix4,
+  normal_matrix: algebra::Matrix4,
+  ambient: [f32; 3],
+  lights: Rc<[Light]>,
+  shadow: Option<FrameShadow>,
+  frustum: algebra::Frustum,
+}
+
+impl FrameContext {
+  /// Create a frame context from explicit matrices, with an identity model transform
+  pub fn new(view: algebra::Matrix4, projection: algebra::Matrix4, eye_position: algebra::Point3, viewport: (i32, i32)) -> Self {
+    let view_projection = &projection * &view;
+    FrameContext {
+      view,
+      projection,
+      view_projection,
+      eye_position,
+      viewport,
+      model: algebra::Matrix4::identity(),
+      model_view_projection: view_projection,
+      normal_matrix: algebra::Matrix4::identity(),
+      ambient: [0.0; 3],
+      lights: Rc::new([]),
+      shadow: None,
+      frustum: algebra::Frustum::from_matrix(&view_projection),
+    }
+  }
+
+  /// Create a frame context from a camera and the viewport size in pixels
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the camera cannot provide its matrices.
+  pub fn from_camera<C: traits::Camera + ?Sized>(camera: &C, viewport: (i32, i32)) -> Result<Self> {
+    let view_projection = camera.view_projection()?;
+    Ok(FrameContext {
+      view: camera.view()?,
+      projection: camera.projection()?,
+      view_projection,
+      eye_position: camera.eye_position()?,
+      viewport,
+      model: algebra::Matrix4::identity(),
+      model_view_projection: view_projection,
+      normal_matrix: algebra::Matrix4::identity(),
+      ambient: [0.0; 3],
+      lights: Rc::new([]),
+      shadow: None,
+      frustum: algebra::Frustum::from_matrix(&view_projection),
+    })
+  }
+
+  /// Returns a copy of the frame context for an object placed with the given model transform
+  pub fn with_model(&self, model: &algebra::Matrix4) -> Self {
+    FrameContext {
+      model: *model,
+      model_view_projection: &self.view_projection * model,
+      normal_matrix: model.normal_matrix(),
+      ..self.clone()
+    }
+  }
+
+  /// Returns a copy of the frame context with an ambient light color and a set of lights
+  pub fn with_lights(&self, ambient: [f32; 3], lights: Vec<Light>) -> Self {
+    FrameContext { ambient, lights: lights.into(), ..self.clone() }
+  }
+
+  /// Returns a copy of the frame context with the shadow map of one of its lights
+  pub fn with_shadow(&self, shadow: Option<FrameShadow>) -> Self {
+    FrameContext { shadow, ..self.clone() }
+  }
+
+  pub fn view(&self) -> &algebra::Matrix4 { &self.view }
+  pub fn projection(&self) -> &algebra::Matrix4 { &self.projection }
+  pub fn view_projection(&self) -> &algebra::Matrix4 { &self.view_projection }
+  pub fn eye_position(&self) -> &algebra::Point3 { &self.eye_position }
+  pub fn model(&self) -> &algebra::Matrix4 { &self.model }
+  pub fn model_view_projection(&self) -> &algebra::Matrix4 { &self.model_view_projection }
+
+  /// Matrix transforming model-space normals into world space, see
+  /// [`Matrix4::normal_matrix`](algebra::Matrix4::normal_matrix)
+  pub fn normal_matrix(&self) -> &algebra::Matrix4 { &self.normal_matrix }
+
+  /// Ambient light color, added to the lighting of every surface
+  pub fn ambient(&self) -> [f32; 3] { self.ambient }
+
+  /// Lights in world space, none unless set with [`with_lights`](FrameContext::with_lights)
+  pub fn lights(&self) -> &[Light] { &self.lights }
+
+  /// Shadow map of the frame, `None` unless set with [`with_shadow`](FrameContext::with_shadow)
+  pub fn shadow(&self) -> Option<&FrameShadow> { self.shadow.as_ref() }
+
+  /// Viewport (width, height) in pixels
+  pub fn viewport(&self) -> (i32, i32) { self.viewport }
+
+  /// World space frustum of the camera
+  pub fn frustum(&self) -> &algebra::Frustum { &self.frustum }
+
+  /// Returns `false` if the bounding volumes of a renderable, placed with
+  /// the model transform of this frame, are outside of the view frustum
+  ///
+  /// Renderables without bounding volumes are always visible.
+  pub fn is_visible(&self, renderable: &dyn traits::Renderable) -> bool {
+    renderable.bounding_sphere().is_none_or(|s| self.frustum.intersects_sphere(&s.transform(&self.model)))
+      && renderable.bounding_box().is_none_or(|b| self.frustum.intersects_aabb(&b.transform(&self.model)))
+  }
+
+  /// Model-View-Projection matrix as 16 column-major `f32`, ready for `uniform_matrix4fv`
+  pub fn mvp(&self) -> &[f32] { self.model_view_projection.as_slice() }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct MvpOnly(algebra::Matrix4);
+
+  impl traits::Camera for MvpOnly {
+    fn as_f32_array(&self) -> Result<&[f32]> { Ok(self.0.as_slice()) }
+  }
+
+  #[test]
+  fn mvp_only_camera_uses_defaults() {
+    let mvp = algebra::Matrix4::new_translation(1.0, 2.0, 3.0);
+    let frame = FrameContext::from_camera(&MvpOnly(mvp), (640, 480)).unwrap();
+    assert_eq!(frame.view(), &algebra::Matrix4::identity());
+    assert_eq!(frame.view_projection(), &mvp);
+    assert_eq!(frame.eye_position(), &algebra::Point3::origin());
+    assert_eq!(frame.mvp(), mvp.as_slice());
+  }
+
+  #[test]
+  fn camera_exposes_eye_and_split_matrices() {
+    let camera = crate::structs::camera::Orbit::new(640.0, 480.0).unwrap();
+    let frame = FrameContext::from_camera(&camera, (640, 480)).unwrap();
+    let eye = camera.eye();
+    let recovered = frame.view().inverse().unwrap().transform_point(&algebra::Point3::origin());
+    assert!((&recovered - &eye).norm() < 1e-4);
+    assert_eq!(frame.eye_position(), &eye);
+    assert_eq!(&(frame.projection() * frame.view()), frame.view_projection());
+  }
+
+  #[test]
+  fn with_model_composes_model_view_projection() {
+    let frame = FrameContext::new(
+      algebra::Matrix4::new_translation(0.0, 0.0, -5.0), algebra::Matrix4::new_scaling(2.0, 2.0, 2.0),
+      algebra::Point3::new(0.0, 0.0, 5.0), (640, 480),
+    );
+    let mut model = algebra::Matrix4::new_translation(1.0, 0.0, 0.0);
+    model.scale(1.0, 3.0, 1.0);
+    let object = frame.with_model(&model);
+    assert_eq!(object.view_projection(), frame.view_projection());
+    assert_eq!(object.model(), &model);
+    assert_eq!(object.mvp(), (frame.view_projection() * &model).as_slice());
+    assert_eq!(object.normal_matrix(), &algebra::Matrix4::new_scaling(1.0, 1.0 / 3.0, 1.0));
+  }
+}
@@ -0,0 +1,53 @@
+use super::*;
+
+/// Capsule along the y axis centered on the origin: a cylinder of length
+/// `height` capped by hemispheres of `radius`, for a total length of
+/// `height + 2 * radius`
+///
+/// Each hemisphere has `rings` parallels. Texture coordinates map the angle
+/// to `u` and the arc length from the top to `v`.
+///
+/// # Errors
+///
+/// Returns an error with fewer than 3 segments or 1 ring.
+pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Result<Mesh> {
+  check_segments("Capsule", segments, 3)?;
+  check_segments("Capsule ring", rings, 1)?;
+  let half = 0.5 * height.max(0.0);
+  let length = std::f32::consts::PI * radius + 2.0 * half;
+  let mut profile = Vec::new();
+  for (hemisphere, center) in [(0, half), (1, -half)] {
+    for i in hemisphere * rings..=(hemisphere + 1) * rings {
+      // Without a cylinder section, both hemispheres share the equator
+      if hemisphere == 1 && i == rings && half == 0.0 { continue; }
+      let phi = i as f32 / (2 * rings) as f32 * std::f32::consts::PI;
+      let (sin, cos) = phi.sin_cos();
+      let sin = if i == 2 * rings { 0.0 } else { sin };
+      let arc = radius * phi + 2.0 * half * hemisphere as f32;
+      profile.push(ProfilePoint::new(radius * sin, center + radius * cos, (sin, cos), arc / length));
+    }
+  }
+  let mut builder = MeshBuilder::default();
+  builder.revolve(&profile, segments);
+  builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::super::tests::{assert_well_formed, assert_bounds};
+
+  #[test]
+  fn capsule_counts_and_orientation() {
+    let mesh = capsule(0.5, 2.0, 12, 4).unwrap();
+    assert_eq!(mesh.vertex_count(), 13 * 10);
+    assert_eq!(mesh.triangle_count(), 2 * 12 * 9 - 2 * 12);
+    assert_well_formed(&mesh);
+    assert_bounds(&mesh, [-0.5, -1.5, -0.5], [0.5, 1.5, 0.5]);
+
+    // Without a cylinder section, the capsule is a sphere
+    let sphere = capsule(1.0, 0.0, 12, 4).unwrap();
+    assert_eq!(sphere.vertex_count(), 13 * 9);
+    assert_well_formed(&sphere);
+  }
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+/// Box centered on the origin spanning `width` along x, `height` along y and `depth` along z
+///
+/// Each face has its own 4 vertices so that edges stay sharp, and texture
+/// coordinates covering the whole [0, 1] square.
+pub fn cuboid(width: f32, height: f32, depth: f32) -> Result<Mesh> {
+  // Face normals with two tangent axes such that u x v = normal
+  let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+    ([ 0.0,  0.0,  1.0], [1.0, 0.0,  0.0], [0.0, 1.0,  0.0]), // Front face
+    ([ 0.0,  0.0, -1.0], [0.0, 1.0,  0.0], [1.0, 0.0,  0.0]), // Back face
+    ([ 0.0,  1.0,  0.0], [0.0, 0.0,  1.0], [1.0, 0.0,  0.0]), // Top face
+    ([ 0.0, -1.0,  0.0], [1.0, 0.0,  0.0], [0.0, 0.0,  1.0]), // Bottom face
+    ([ 1.0,  0.0,  0.0], [0.0, 1.0,  0.0], [0.0, 0.0,  1.0]), // Right face
+    ([-1.0,  0.0,  0.0], [0.0, 0.0,  1.0], [0.0, 1.0,  0.0]), // Left face
+  ];
+  let size = [width, height, depth];
+  let mut builder = MeshBuilder::default();
+  for (n, u, v) in faces.iter() {
+    let first = (builder.positions.len() / 3) as u32;
+    for (su, sv) in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+      let position = [0, 1, 2].map(|k| (0.5 * n[k] + su * u[k] + sv * v[k]) * size[k]);
+      builder.vertex(position, *n, [su + 0.5, 0.5 - sv]);
+    }
+    builder.indices.extend([0, 1, 2, 2, 3, 0].map(|i| first + i));
+  }
+  builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::super::tests::{assert_well_formed, assert_bounds};
+
+  #[test]
+  fn cuboid_counts_and_orientation() {
+    let mesh = cuboid(1.0, 2.0, 3.0).unwrap();
+    assert_eq!(mesh.vertex_count(), 24);
+    assert_eq!(mesh.triangle_count(), 12);
+    assert_well_formed(&mesh);
+    assert_bounds(&mesh, [-0.5, -1.0, -1.5], [0.5, 1.0, 1.5]);
+  }
+}
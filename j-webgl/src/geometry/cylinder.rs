@@ -0,0 +1,79 @@
+use super::*;
+
+/// Cylinder along the y axis centered on the origin, with capped ends
+///
+/// The side and each cap have their own vertices so that edges stay sharp.
+/// Caps use polar texture coordinates, `u` following the angle and `v` the
+/// distance to the axis.
+///
+/// # Errors
+///
+/// Returns an error with fewer than 3 segments.
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> Result<Mesh> {
+  check_segments("Cylinder", segments, 3)?;
+  let half = 0.5 * height;
+  let mut builder = MeshBuilder::default();
+  builder.revolve(&[
+    ProfilePoint::new(0.0, half, (0.0, 1.0), 0.0),
+    ProfilePoint::new(radius, half, (0.0, 1.0), 1.0),
+  ], segments);
+  builder.revolve(&[
+    ProfilePoint::new(radius, half, (1.0, 0.0), 0.0),
+    ProfilePoint::new(radius, -half, (1.0, 0.0), 1.0),
+  ], segments);
+  add_bottom_cap(&mut builder, radius, -half, segments);
+  builder.build()
+}
+
+/// Cone along the y axis centered on the origin, with its apex up and a capped base
+///
+/// Texture coordinates are the same as on a [`cylinder`].
+///
+/// # Errors
+///
+/// Returns an error with fewer than 3 segments.
+pub fn cone(radius: f32, height: f32, segments: u32) -> Result<Mesh> {
+  check_segments("Cone", segments, 3)?;
+  let half = 0.5 * height;
+  let slant = (radius * radius + height * height).sqrt();
+  let normal = (height / slant, radius / slant);
+  let mut builder = MeshBuilder::default();
+  builder.revolve(&[
+    ProfilePoint::new(0.0, half, normal, 0.0),
+    ProfilePoint::new(radius, -half, normal, 1.0),
+  ], segments);
+  add_bottom_cap(&mut builder, radius, -half, segments);
+  builder.build()
+}
+
+fn add_bottom_cap(builder: &mut MeshBuilder, radius: f32, y: f32, segments: u32) {
+  builder.revolve(&[
+    ProfilePoint::new(radius, y, (0.0, -1.0), 1.0),
+    ProfilePoint::new(0.0, y, (0.0, -1.0), 0.0),
+  ], segments);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::super::tests::{assert_well_formed, assert_bounds};
+
+  #[test]
+  fn cylinder_counts_and_orientation() {
+    let mesh = cylinder(0.5, 2.0, 12).unwrap();
+    assert_eq!(mesh.vertex_count(), 3 * 2 * 13);
+    assert_eq!(mesh.triangle_count(), 12 + 2 * 12 + 12);
+    assert_well_formed(&mesh);
+    assert_bounds(&mesh, [-0.5, -1.0, -0.5], [0.5, 1.0, 0.5]);
+    assert!(cylinder(1.0, 1.0, 2).is_err());
+  }
+
+  #[test]
+  fn cone_counts_and_orientation() {
+    let mesh = cone(1.0, 3.0, 8).unwrap();
+    assert_eq!(mesh.vertex_count(), 2 * 2 * 9);
+    assert_eq!(mesh.triangle_count(), 8 + 8);
+    assert_well_formed(&mesh);
+    assert_bounds(&mesh, [-1.0, -1.5, -1.0], [1.0, 1.5, 1.0]);
+  }
+}
@@ -0,0 +1,132 @@
+use super::*;
+
+use mesh::{Mesh, Indices};
+
+mod sphere; pub use sphere::{uv_sphere, icosphere};
+mod capsule; pub use capsule::capsule;
+mod cylinder; pub use cylinder::{cylinder, cone};
+mod torus; pub use torus::torus;
+mod plane; pub use plane::plane;
+mod cuboid; pub use cuboid::cuboid;
+
+/// Mesh under construction, with normals, texture coordinates and indices
+#[derive(Default)]
+struct MeshBuilder {
+  positions: Vec<f32>,
+  normals: Vec<f32>,
+  uvs: Vec<f32>,
+  indices: Vec<u32>,
+}
+
+/// Point of a profile revolved around the y axis, at distance `rho` from the
+/// axis, with the normal (`normal_rho`, `normal_y`) in the profile plane and
+/// texture coordinate `v`
+#[derive(Clone, Copy)]
+struct ProfilePoint {
+  rho: f32,
+  y: f32,
+  normal_rho: f32,
+  normal_y: f32,
+  v: f32,
+}
+
+impl ProfilePoint {
+  fn new(rho: f32, y: f32, (normal_rho, normal_y): (f32, f32), v: f32) -> Self {
+    ProfilePoint { rho, y, normal_rho, normal_y, v }
+  }
+}
+
+impl MeshBuilder {
+  fn vertex(&mut self, position: [f32; 3], normal: [f32; 3], uv: [f32; 2]) -> u32 {
+    let index = (self.positions.len() / 3) as u32;
+    self.positions.extend(position);
+    self.normals.extend(normal);
+    self.uvs.extend(uv);
+    index
+  }
+
+  /// Add the surface swept by `profile` revolving around the y axis, with
+  /// `segments` subdivisions. The texture `u` coordinate follows the angle,
+  /// starting and ending on the +z axis. The profile should run with the
+  /// outside on its left in the (rho, y) plane, e.g. from top to bottom on a
+  /// sphere. Triangles collapsing on the axis are skipped.
+  fn revolve(&mut self, profile: &[ProfilePoint], segments: u32) {
+    let first = (self.positions.len() / 3) as u32;
+    for p in profile {
+      for j in 0..=segments {
+        let u = j as f32 / segments as f32;
+        let (sin, cos) = (u * std::f32::consts::TAU).sin_cos();
+        self.vertex([p.rho * sin, p.y, p.rho * cos], [p.normal_rho * sin, p.normal_y, p.normal_rho * cos], [u, p.v]);
+      }
+    }
+    let row = segments + 1;
+    for (i, pair) in profile.windows(2).enumerate() {
+      for j in 0..segments {
+        let a = first + i as u32 * row + j;
+        let (b, c, d) = (a + row, a + row + 1, a + 1);
+        if pair[1].rho > 0.0 { self.indices.extend([a, b, c]); }
+        if pair[0].rho > 0.0 { self.indices.extend([a, c, d]); }
+      }
+    }
+  }
+
+  fn build(self) -> Result<Mesh> {
+    Mesh::new(self.positions)?
+      .with_normals(self.normals)?
+      .with_uvs(self.uvs)?
+      .with_indices(Indices::compact(self.indices))
+  }
+}
+
+fn check_segments(name: &str, segments: u32, minimum: u32) -> Result<()> {
+  if segments < minimum {
+    return Err(Error::InvalidMesh(format!("{name} needs at least {minimum} segments, got {segments}")));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Check unit normals, texture coordinates in [0, 1] and counter-clockwise
+  /// triangles seen from the side the vertex normals point to
+  pub fn assert_well_formed(mesh: &Mesh) {
+    assert!(mesh.uvs().unwrap().iter().all(|v| (0.0..=1.0).contains(v)));
+    assert_well_wound(mesh);
+  }
+
+  /// Check unit normals and counter-clockwise triangles seen from the side
+  /// the vertex normals point to
+  pub fn assert_well_wound(mesh: &Mesh) {
+    let normals = mesh.normals().unwrap();
+    for n in normals.chunks_exact(3) {
+      assert!(((n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() - 1.0).abs() < 1e-5, "normal {:?} is not unit", n);
+    }
+    for triangle in mesh.triangles() {
+      let [a, b, c] = triangle.map(|i| mesh.position(i));
+      let (u, v) = ([0, 1, 2].map(|k| b[k] - a[k]), [0, 1, 2].map(|k| c[k] - a[k]));
+      let face = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+      let area = (face[0] * face[0] + face[1] * face[1] + face[2] * face[2]).sqrt();
+      assert!(area > 1e-7, "degenerate triangle {:?}", triangle);
+      let normal = triangle.iter().fold([0.0; 3], |n, i| [0, 1, 2].map(|k| n[k] + normals[3 * i + k]));
+      let dot = face[0] * normal[0] + face[1] * normal[1] + face[2] * normal[2];
+      assert!(dot > 0.0, "triangle {:?} winds against its normals", triangle);
+    }
+  }
+
+  /// Returns the smallest and largest coordinates of the mesh vertices
+  pub fn bounds(mesh: &Mesh) -> ([f32; 3], [f32; 3]) {
+    mesh.positions().chunks_exact(3).fold(([f32::MAX; 3], [f32::MIN; 3]), |(min, max), p| {
+      ([0, 1, 2].map(|k| min[k].min(p[k])), [0, 1, 2].map(|k| max[k].max(p[k])))
+    })
+  }
+
+  pub fn assert_bounds(mesh: &Mesh, min: [f32; 3], max: [f32; 3]) {
+    let (actual_min, actual_max) = bounds(mesh);
+    for k in 0..3 {
+      assert!((actual_min[k] - min[k]).abs() < 1e-5 && (actual_max[k] - max[k]).abs() < 1e-5,
+        "bounds {:?} {:?} differ from {:?} {:?}", actual_min, actual_max, min, max);
+    }
+  }
+}
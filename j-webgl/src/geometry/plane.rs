@@ -0,0 +1,48 @@
+use super::*;
+
+/// Grid in the xz plane centered on the origin, facing +y
+///
+/// The plane spans `width` along x and `depth` along z, subdivided in
+/// `x_segments` by `z_segments` quads. Texture coordinates run from (0, 0)
+/// at the -x, -z corner to (1, 1) at the +x, +z corner.
+///
+/// # Errors
+///
+/// Returns an error with no segment in either direction.
+pub fn plane(width: f32, depth: f32, x_segments: u32, z_segments: u32) -> Result<Mesh> {
+  check_segments("Plane", x_segments, 1)?;
+  check_segments("Plane", z_segments, 1)?;
+  let mut builder = MeshBuilder::default();
+  for row in 0..=z_segments {
+    let v = row as f32 / z_segments as f32;
+    for column in 0..=x_segments {
+      let u = column as f32 / x_segments as f32;
+      builder.vertex([(u - 0.5) * width, 0.0, (v - 0.5) * depth], [0.0, 1.0, 0.0], [u, v]);
+    }
+  }
+  let stride = x_segments + 1;
+  for row in 0..z_segments {
+    for column in 0..x_segments {
+      let a = row * stride + column;
+      let (b, c, d) = (a + stride, a + stride + 1, a + 1);
+      builder.indices.extend([a, b, c, a, c, d]);
+    }
+  }
+  builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::super::tests::{assert_well_formed, assert_bounds};
+
+  #[test]
+  fn plane_counts_and_orientation() {
+    let mesh = plane(4.0, 2.0, 4, 3).unwrap();
+    assert_eq!(mesh.vertex_count(), 5 * 4);
+    assert_eq!(mesh.triangle_count(), 2 * 4 * 3);
+    assert_well_formed(&mesh);
+    assert_bounds(&mesh, [-2.0, 0.0, -1.0], [2.0, 0.0, 1.0]);
+    assert!(plane(1.0, 1.0, 0, 1).is_err());
+  }
+}
@@ -0,0 +1,145 @@
+use super::*;
+
+/// Sphere centered on the origin, made of `segments` meridians and `rings` parallels
+///
+/// Texture coordinates map longitude to `u` and latitude to `v`, from the
+/// north pole (`v = 0`) to the south pole (`v = 1`).
+///
+/// # Errors
+///
+/// Returns an error with fewer than 3 segments or 2 rings.
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Result<Mesh> {
+  check_segments("UV sphere", segments, 3)?;
+  check_segments("UV sphere ring", rings, 2)?;
+  let profile: Vec<ProfilePoint> = (0..=rings)
+    .map(|i| {
+      let v = i as f32 / rings as f32;
+      let (sin, cos) = (v * std::f32::consts::PI).sin_cos();
+      // Exact zero at the south pole so that the collapsed triangles are skipped
+      let sin = if i == rings { 0.0 } else { sin };
+      ProfilePoint::new(radius * sin, radius * cos, (sin, cos), v)
+    })
+    .collect();
+  let mut builder = MeshBuilder::default();
+  builder.revolve(&profile, segments);
+  builder.build()
+}
+
+/// Sphere centered on the origin obtained by subdividing an icosahedron
+/// `subdivisions` times, with evenly sized triangles
+///
+/// Each subdivision splits every triangle in four, for `20 * 4^subdivisions`
+/// triangles. Texture coordinates follow the same longitude and latitude
+/// mapping as [`uv_sphere`]. Vertices are duplicated along the seam, where
+/// the triangles crossing it continue past `u = 1` so that a repeating
+/// sampler wraps the texture without discontinuity, and at the poles, which
+/// take the longitude of each of their triangles.
+pub fn icosphere(radius: f32, subdivisions: u32) -> Result<Mesh> {
+  let t = (1.0 + 5f32.sqrt()) / 2.0;
+  let mut vertices: Vec<[f32; 3]> = vec![
+    [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+    [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+    [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+  ].into_iter().map(normalize).collect();
+  let mut faces: Vec<[u32; 3]> = vec![
+    [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+    [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+    [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+    [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+  ];
+
+  for _ in 0..subdivisions {
+    let mut midpoints = std::collections::HashMap::new();
+    let mut midpoint = |a: u32, b: u32, vertices: &mut Vec<[f32; 3]>| -> u32 {
+      *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+        let (p, q) = (vertices[a as usize], vertices[b as usize]);
+        vertices.push(normalize([0, 1, 2].map(|k| p[k] + q[k])));
+        (vertices.len() - 1) as u32
+      })
+    };
+    faces = faces.iter()
+      .flat_map(|&[a, b, c]| {
+        let (ab, bc, ca) = (midpoint(a, b, &mut vertices), midpoint(b, c, &mut vertices), midpoint(c, a, &mut vertices));
+        [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+      })
+      .collect();
+  }
+
+  let longitude = |n: [f32; 3]| 0.5 + n[0].atan2(n[2]) / std::f32::consts::TAU;
+  let is_pole = |n: [f32; 3]| n[0].abs() < 1e-6 && n[2].abs() < 1e-6;
+  let mut builder = MeshBuilder::default();
+  // Index of the copy of each vertex with a given u
+  let mut copies = std::collections::HashMap::new();
+  for face in faces {
+    let u = face.map(|i| longitude(vertices[i as usize]));
+    let poles = face.map(|i| is_pole(vertices[i as usize]));
+    let around: Vec<f32> = (0..3).filter(|k| !poles[*k]).map(|k| u[k]).collect();
+    let span = around.iter().cloned().fold(f32::MIN, f32::max) - around.iter().cloned().fold(f32::MAX, f32::min);
+    // Faces across the seam continue from u = 1 rather than wrap back to 0
+    let u = u.map(|u| if span > 0.5 && u < 0.5 { u + 1.0 } else { u });
+    let mean = (0..3).filter(|k| !poles[*k]).map(|k| u[k]).sum::<f32>() / around.len() as f32;
+    for k in 0..3 {
+      let u = if poles[k] { mean } else { u[k] };
+      let index = *copies.entry((face[k], u.to_bits())).or_insert_with(|| {
+        let n = vertices[face[k] as usize];
+        let v = n[1].clamp(-1.0, 1.0).acos() / std::f32::consts::PI;
+        builder.vertex(n.map(|c| c * radius), n, [u, v])
+      });
+      builder.indices.push(index);
+    }
+  }
+  builder.build()
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+  let n = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+  v.map(|c| c / n)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::super::tests::{assert_well_formed, assert_well_wound, assert_bounds};
+
+  #[test]
+  fn uv_sphere_counts_and_orientation() {
+    let mesh = uv_sphere(2.0, 16, 8).unwrap();
+    assert_eq!(mesh.vertex_count(), 17 * 9);
+    // Pole rings have one triangle per segment, other rings two
+    assert_eq!(mesh.triangle_count(), 2 * 16 * 8 - 2 * 16);
+    assert_well_formed(&mesh);
+    assert_bounds(&mesh, [-2.0, -2.0, -2.0], [2.0, 2.0, 2.0]);
+    assert!(uv_sphere(1.0, 2, 8).is_err());
+    assert!(uv_sphere(1.0, 3, 1).is_err());
+  }
+
+  #[test]
+  fn icosphere_counts_and_orientation() {
+    for subdivisions in 0..3 {
+      let mesh = icosphere(1.5, subdivisions).unwrap();
+      // Seam and pole vertices are duplicated
+      assert!(mesh.vertex_count() > 10 * 4usize.pow(subdivisions) + 2);
+      assert_eq!(mesh.triangle_count(), 20 * 4usize.pow(subdivisions));
+      assert_well_wound(&mesh);
+      for p in mesh.positions().chunks_exact(3) {
+        assert!(((p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt() - 1.5).abs() < 1e-5);
+      }
+    }
+  }
+
+  #[test]
+  fn icosphere_texture_coordinates_are_continuous() {
+    for subdivisions in 0..4 {
+      let mesh = icosphere(1.0, subdivisions).unwrap();
+      let uvs = mesh.uvs().unwrap();
+      assert!(uvs.chunks_exact(2).all(|uv| (0.0..1.5).contains(&uv[0]) && (0.0..=1.0).contains(&uv[1])));
+      for triangle in mesh.triangles() {
+        let u = triangle.map(|i| uvs[2 * i]);
+        let span = u.iter().cloned().fold(f32::MIN, f32::max) - u.iter().cloned().fold(f32::MAX, f32::min);
+        // Up to half a turn for the faces of the icosahedron, nearly a full
+        // turn if a face wrapped around the seam
+        assert!(span <= 0.5 + 1e-5, "triangle {:?} spans u {:?}", triangle, u);
+      }
+    }
+  }
+}
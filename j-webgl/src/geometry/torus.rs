@@ -0,0 +1,43 @@
+use super::*;
+
+/// Torus around the y axis centered on the origin
+///
+/// The tube of radius `minor_radius` follows a circle of radius
+/// `major_radius` in the xz plane, with `major_segments` subdivisions around
+/// the y axis and `minor_segments` around the tube. Texture coordinates map
+/// the angle around the y axis to `u` and the angle around the tube, from
+/// its top towards the outside, to `v`.
+///
+/// # Errors
+///
+/// Returns an error with fewer than 3 segments in either direction.
+pub fn torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Result<Mesh> {
+  check_segments("Torus", major_segments, 3)?;
+  check_segments("Torus tube", minor_segments, 3)?;
+  let profile: Vec<ProfilePoint> = (0..=minor_segments)
+    .map(|i| {
+      let v = i as f32 / minor_segments as f32;
+      let (sin, cos) = (std::f32::consts::FRAC_PI_2 - v * std::f32::consts::TAU).sin_cos();
+      ProfilePoint::new(major_radius + minor_radius * cos, minor_radius * sin, (cos, sin), v)
+    })
+    .collect();
+  let mut builder = MeshBuilder::default();
+  builder.revolve(&profile, major_segments);
+  builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::super::tests::{assert_well_formed, assert_bounds};
+
+  #[test]
+  fn torus_counts_and_orientation() {
+    let mesh = torus(2.0, 0.5, 24, 12).unwrap();
+    assert_eq!(mesh.vertex_count(), 25 * 13);
+    assert_eq!(mesh.triangle_count(), 2 * 24 * 12);
+    assert_well_formed(&mesh);
+    assert_bounds(&mesh, [-2.5, -0.5, -2.5], [2.5, 0.5, 2.5]);
+    assert!(torus(2.0, 0.5, 24, 2).is_err());
+  }
+}
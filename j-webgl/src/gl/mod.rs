@@ -0,0 +1,199 @@
+use super::{Result, Error, shaders};
+
+mod web;
+mod recording; pub use recording::{RecordingContext, Call, BoundState};
+mod software; pub use software::SoftwareContext;
+
+/// Opaque handle to an object created by a [`GlContext`]
+///
+/// WebGL objects are JavaScript values, while in-memory contexts identify
+/// their objects with a numerical identifier.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Handle {
+  /// JavaScript object created by a WebGL 2.0 context
+  Js(wasm_bindgen::JsValue),
+  /// Identifier allocated by an in-memory context
+  Id(u32),
+}
+
+impl Handle {
+  /// Returns the identifier for handles allocated by in-memory contexts
+  pub fn id(&self) -> Option<u32> {
+    match self {
+      Handle::Id(id) => Some(*id),
+      Handle::Js(_) => None,
+    }
+  }
+}
+
+/// Handle to a buffer object
+#[derive(Clone, Debug, PartialEq)]
+pub struct Buffer(pub Handle);
+
+/// Handle to a shader object
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shader(pub Handle);
+
+/// Handle to a linked shader program
+#[derive(Clone, Debug, PartialEq)]
+pub struct Program(pub Handle);
+
+/// Handle to a vertex array object
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexArray(pub Handle);
+
+/// Handle to a texture object
+#[derive(Clone, Debug, PartialEq)]
+pub struct Texture(pub Handle);
+
+/// Handle to a framebuffer object
+#[derive(Clone, Debug, PartialEq)]
+pub struct Framebuffer(pub Handle);
+
+/// Handle to a renderbuffer object
+#[derive(Clone, Debug, PartialEq)]
+pub struct Renderbuffer(pub Handle);
+
+/// Handle to the location of a uniform within a program
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniformLocation(pub Handle);
+
+/// Value set on a uniform by in-memory contexts
+#[derive(Clone, Debug, PartialEq)]
+pub enum UniformValue {
+  Int(i32),
+  Float(f32),
+  Vec3([f32; 3]),
+  Vec4([f32; 4]),
+  Mat4([f32; 16]),
+}
+
+/// Trait covering the WebGL 2.0 calls made by the library
+///
+/// Method names and enumeration values (`ARRAY_BUFFER`, `TRIANGLES`, ...) follow
+/// `web_sys::WebGl2RenderingContext` so that implementations map one to one onto
+/// the browser API. The trait is implemented for `web_sys::WebGl2RenderingContext`
+/// and for two in-memory implementations used without a browser:
+/// [`RecordingContext`] logs calls, and [`SoftwareContext`] rasterizes the
+/// library built-in programs on the CPU.
+pub trait GlContext {
+  /// Width of the drawing buffer in pixels
+  fn drawing_buffer_width(&self) -> i32;
+  /// Height of the drawing buffer in pixels
+  fn drawing_buffer_height(&self) -> i32;
+
+  fn viewport(&self, x: i32, y: i32, width: i32, height: i32);
+  fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32);
+  fn clear(&self, mask: u32);
+  /// Clear draw buffer `drawbuffer` of an unsigned integer color `buffer` to `values`
+  fn clear_bufferuiv_with_u32_array(&self, buffer: u32, drawbuffer: i32, values: &[u32]);
+  fn enable(&self, capability: u32);
+  fn disable(&self, capability: u32);
+  /// Enable extension `name`, returns whether it is supported
+  fn get_extension(&self, name: &str) -> bool;
+
+  fn create_shader(&self, shader_type: u32) -> Option<Shader>;
+  fn shader_source(&self, shader: &Shader, source: &str);
+  fn compile_shader(&self, shader: &Shader);
+  /// Returns `true` if the last compilation of `shader` succeeded
+  fn get_shader_compile_status(&self, shader: &Shader) -> bool;
+  fn get_shader_info_log(&self, shader: &Shader) -> Option<String>;
+
+  fn create_program(&self) -> Option<Program>;
+  fn attach_shader(&self, program: &Program, shader: &Shader);
+  fn link_program(&self, program: &Program);
+  /// Returns `true` if the last link of `program` succeeded
+  fn get_program_link_status(&self, program: &Program) -> bool;
+  fn get_program_info_log(&self, program: &Program) -> Option<String>;
+  fn use_program(&self, program: Option<&Program>);
+
+  fn get_attrib_location(&self, program: &Program, name: &str) -> i32;
+  fn get_uniform_location(&self, program: &Program, name: &str) -> Option<UniformLocation>;
+
+  fn create_buffer(&self) -> Option<Buffer>;
+  fn bind_buffer(&self, target: u32, buffer: Option<&Buffer>);
+  fn buffer_data_with_f32_array(&self, target: u32, data: &[f32], usage: u32);
+  fn buffer_data_with_u16_array(&self, target: u32, data: &[u16], usage: u32);
+  fn buffer_data_with_u32_array(&self, target: u32, data: &[u32], usage: u32);
+  fn delete_buffer(&self, buffer: Option<&Buffer>);
+
+  fn create_vertex_array(&self) -> Option<VertexArray>;
+  fn bind_vertex_array(&self, vertex_array: Option<&VertexArray>);
+  fn delete_vertex_array(&self, vertex_array: Option<&VertexArray>);
+  fn enable_vertex_attrib_array(&self, index: u32);
+  fn vertex_attrib_pointer_with_i32(&self, index: u32, size: i32, data_type: u32, normalized: bool, stride: i32, offset: i32);
+
+  fn create_texture(&self) -> Option<Texture>;
+  fn bind_texture(&self, target: u32, texture: Option<&Texture>);
+  fn delete_texture(&self, texture: Option<&Texture>);
+  /// Select the texture unit affected by `bind_texture`, `TEXTURE0 + i`
+  fn active_texture(&self, unit: u32);
+  fn pixel_storei(&self, pname: u32, param: i32);
+  fn tex_parameteri(&self, target: u32, pname: u32, param: i32);
+  /// Specify level `level` of the bound texture from rows of bytes ordered
+  /// from the first texture row (`t = 0`), or allocate it when `data` is `None`
+  #[allow(clippy::too_many_arguments)]
+  fn tex_image_2d_with_u8_array(
+    &self, target: u32, level: i32, internal_format: i32, width: i32, height: i32,
+    format: u32, data_type: u32, data: Option<&[u8]>,
+  ) -> Result<()>;
+  /// Specify level `level` of the bound texture from `FLOAT` values
+  #[allow(clippy::too_many_arguments)]
+  fn tex_image_2d_with_f32_array(
+    &self, target: u32, level: i32, internal_format: i32, width: i32, height: i32,
+    format: u32, data: &[f32],
+  ) -> Result<()>;
+  /// Specify level `level` of the bound texture from a decoded HTML image
+  fn tex_image_2d_with_html_image_element(
+    &self, target: u32, level: i32, internal_format: i32, format: u32, data_type: u32,
+    source: &web_sys::HtmlImageElement,
+  ) -> Result<()>;
+  /// Specify level `level` of the bound texture from an image bitmap
+  fn tex_image_2d_with_image_bitmap(
+    &self, target: u32, level: i32, internal_format: i32, format: u32, data_type: u32,
+    source: &web_sys::ImageBitmap,
+  ) -> Result<()>;
+  fn generate_mipmap(&self, target: u32);
+
+  fn create_framebuffer(&self) -> Option<Framebuffer>;
+  /// Bind a framebuffer to `target`, `None` binding the drawing buffer
+  fn bind_framebuffer(&self, target: u32, framebuffer: Option<&Framebuffer>);
+  fn delete_framebuffer(&self, framebuffer: Option<&Framebuffer>);
+  /// Attach level `level` of a texture to `attachment` (`COLOR_ATTACHMENT0`,
+  /// `DEPTH_ATTACHMENT`, ...) of the framebuffer bound to `target`
+  fn framebuffer_texture_2d(&self, target: u32, attachment: u32, textarget: u32, texture: Option<&Texture>, level: i32);
+  /// Returns `FRAMEBUFFER_COMPLETE` if the framebuffer bound to `target` can be drawn to
+  fn check_framebuffer_status(&self, target: u32) -> u32;
+  /// Read pixels of the framebuffer bound to `READ_FRAMEBUFFER` into `data`,
+  /// for `UNSIGNED_INT` values such as `RGBA_INTEGER` pixels of integer buffers
+  #[allow(clippy::too_many_arguments)]
+  fn read_pixels_with_u32_array(
+    &self, x: i32, y: i32, width: i32, height: i32, format: u32, data_type: u32, data: &mut [u32],
+  ) -> Result<()>;
+  /// Copy a rectangle of the `READ_FRAMEBUFFER` into a rectangle of the
+  /// `DRAW_FRAMEBUFFER`, resolving multisampled buffers
+  #[allow(clippy::too_many_arguments)]
+  fn blit_framebuffer(
+    &self, src_x0: i32, src_y0: i32, src_x1: i32, src_y1: i32,
+    dst_x0: i32, dst_y0: i32, dst_x1: i32, dst_y1: i32, mask: u32, filter: u32,
+  );
+
+  fn create_renderbuffer(&self) -> Option<Renderbuffer>;
+  fn bind_renderbuffer(&self, target: u32, renderbuffer: Option<&Renderbuffer>);
+  fn delete_renderbuffer(&self, renderbuffer: Option<&Renderbuffer>);
+  /// Allocate the renderbuffer bound to `target` with `samples` samples per pixel, 0 for no multisampling
+  fn renderbuffer_storage_multisample(&self, target: u32, samples: i32, internal_format: u32, width: i32, height: i32);
+  /// Attach a renderbuffer to `attachment` of the framebuffer bound to `target`
+  fn framebuffer_renderbuffer(&self, target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: Option<&Renderbuffer>);
+
+  fn uniform1i(&self, location: Option<&UniformLocation>, x: i32);
+  fn uniform1f(&self, location: Option<&UniformLocation>, x: f32);
+  fn uniform4f(&self, location: Option<&UniformLocation>, x: f32, y: f32, z: f32, w: f32);
+  fn uniform3fv_with_f32_array(&self, location: Option<&UniformLocation>, data: &[f32]);
+  fn uniform_matrix4fv_with_f32_array(&self, location: Option<&UniformLocation>, transpose: bool, data: &[f32]);
+
+  fn draw_arrays(&self, mode: u32, first: i32, count: i32);
+  /// Draw `count` vertices read from the bound `ELEMENT_ARRAY_BUFFER` at byte
+  /// `offset`, `data_type` being `UNSIGNED_BYTE`, `UNSIGNED_SHORT` or `UNSIGNED_INT`
+  fn draw_elements_with_i32(&self, mode: u32, count: i32, data_type: u32, offset: i32);
+}
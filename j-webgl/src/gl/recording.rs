@@ -0,0 +1,509 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use web_sys::WebGl2RenderingContext as GL;
+
+/// Call issued on a [`RecordingContext`]
+///
+/// Objects are identified by the identifier of their [`Handle`]. Queries
+/// (locations, compile and link status, info logs) are not recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Call {
+  Viewport { x: i32, y: i32, width: i32, height: i32 },
+  ClearColor([f32; 4]),
+  Clear(u32),
+  ClearBufferuiv { buffer: u32, drawbuffer: i32, values: Vec<u32> },
+  Enable(u32),
+  GetExtension(String),
+  Disable(u32),
+  CreateShader { shader: u32, shader_type: u32 },
+  ShaderSource { shader: u32, source: String },
+  CompileShader(u32),
+  CreateProgram(u32),
+  AttachShader { program: u32, shader: u32 },
+  LinkProgram(u32),
+  UseProgram(Option<u32>),
+  CreateBuffer(u32),
+  BindBuffer { target: u32, buffer: Option<u32> },
+  BufferData { target: u32, buffer: Option<u32>, data: Vec<f32>, usage: u32 },
+  BufferDataU16 { target: u32, buffer: Option<u32>, data: Vec<u16>, usage: u32 },
+  BufferDataU32 { target: u32, buffer: Option<u32>, data: Vec<u32>, usage: u32 },
+  DeleteBuffer(u32),
+  CreateVertexArray(u32),
+  BindVertexArray(Option<u32>),
+  DeleteVertexArray(u32),
+  EnableVertexAttribArray(u32),
+  VertexAttribPointer { index: u32, size: i32, data_type: u32, normalized: bool, stride: i32, offset: i32 },
+  CreateTexture(u32),
+  BindTexture { target: u32, texture: Option<u32> },
+  DeleteTexture(u32),
+  ActiveTexture(u32),
+  PixelStorei { pname: u32, param: i32 },
+  TexParameteri { target: u32, pname: u32, param: i32 },
+  TexImage2D { target: u32, level: i32, internal_format: i32, width: i32, height: i32, format: u32, data_type: u32, data: Option<Vec<u8>> },
+  TexImage2DF32 { target: u32, level: i32, internal_format: i32, width: i32, height: i32, format: u32, data: Vec<f32> },
+  /// Upload from an `HtmlImageElement` or `ImageBitmap`, whose size is not recorded
+  TexImage2DSource { target: u32, level: i32, internal_format: i32, format: u32, data_type: u32 },
+  GenerateMipmap(u32),
+  CreateFramebuffer(u32),
+  BindFramebuffer { target: u32, framebuffer: Option<u32> },
+  DeleteFramebuffer(u32),
+  FramebufferTexture2D { target: u32, attachment: u32, textarget: u32, texture: Option<u32>, level: i32 },
+  BlitFramebuffer { src: [i32; 4], dst: [i32; 4], mask: u32, filter: u32 },
+  ReadPixels { x: i32, y: i32, width: i32, height: i32, format: u32, data_type: u32 },
+  CreateRenderbuffer(u32),
+  BindRenderbuffer { target: u32, renderbuffer: Option<u32> },
+  DeleteRenderbuffer(u32),
+  RenderbufferStorageMultisample { target: u32, samples: i32, internal_format: u32, width: i32, height: i32 },
+  FramebufferRenderbuffer { target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: Option<u32> },
+  Uniform { program: u32, name: String, value: UniformValue },
+  DrawArrays { mode: u32, first: i32, count: i32 },
+  DrawElements { mode: u32, count: i32, data_type: u32, offset: i32 },
+}
+
+/// State bound on a [`RecordingContext`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoundState {
+  pub program: Option<u32>,
+  pub vertex_array: Option<u32>,
+  pub array_buffer: Option<u32>,
+  pub element_array_buffer: Option<u32>,
+  pub capabilities: BTreeSet<u32>,
+  pub viewport: [i32; 4],
+  pub clear_color: [f32; 4],
+  /// Active texture unit, `TEXTURE0 + i`
+  pub active_texture: u32,
+  /// Texture bound to `TEXTURE_2D` on each texture unit, by unit index
+  pub textures: std::collections::BTreeMap<u32, u32>,
+  /// Framebuffer bound to `FRAMEBUFFER` or `DRAW_FRAMEBUFFER`, `None` for the drawing buffer
+  pub framebuffer: Option<u32>,
+  /// Framebuffer bound to `FRAMEBUFFER` or `READ_FRAMEBUFFER`, `None` for the drawing buffer
+  pub read_framebuffer: Option<u32>,
+}
+
+#[derive(Default)]
+struct Inner {
+  width: i32,
+  height: i32,
+  next_id: u32,
+  calls: Vec<Call>,
+  state: BoundState,
+  attrib_locations: HashMap<(u32, String), i32>,
+  uniform_locations: HashMap<(u32, String), u32>,
+  uniform_names: HashMap<u32, (u32, String)>,
+  uniforms: HashMap<(u32, String), UniformValue>,
+}
+
+impl Inner {
+  fn allocate(&mut self) -> u32 {
+    self.next_id += 1;
+    self.next_id
+  }
+}
+
+/// In-memory [`GlContext`] logging every call and tracking bound state
+///
+/// Shaders always compile and programs always link, so that rendering code
+/// can be exercised with `cargo test` on a machine without a browser.
+///
+/// # Example
+///
+/// ```rust
+/// use j_webgl::gl::{GlContext, RecordingContext, Call};
+///
+/// let context = RecordingContext::new(640, 480);
+/// context.draw_arrays(j_webgl::exports::web_sys::WebGl2RenderingContext::TRIANGLES, 0, 3);
+/// assert!(matches!(context.calls().last(), Some(Call::DrawArrays { count: 3, .. })));
+/// ```
+pub struct RecordingContext {
+  inner: RefCell<Inner>,
+}
+
+impl RecordingContext {
+  /// Create a context with a drawing buffer of the given size in pixels
+  pub fn new(width: i32, height: i32) -> Self {
+    RecordingContext {
+      inner: RefCell::new(Inner { width, height, ..Default::default() }),
+    }
+  }
+
+  /// Change the drawing buffer size, as a canvas resize would
+  pub fn set_drawing_buffer_size(&self, width: i32, height: i32) {
+    let mut inner = self.inner.borrow_mut();
+    (inner.width, inner.height) = (width, height);
+  }
+
+  /// Returns the calls recorded so far
+  pub fn calls(&self) -> Vec<Call> {
+    self.inner.borrow().calls.clone()
+  }
+
+  /// Returns and forgets the calls recorded so far. Bound state is kept.
+  pub fn take_calls(&self) -> Vec<Call> {
+    std::mem::take(&mut self.inner.borrow_mut().calls)
+  }
+
+  /// Returns the currently bound state
+  pub fn state(&self) -> BoundState {
+    self.inner.borrow().state.clone()
+  }
+
+  /// Returns the value last set on uniform `name` of the program currently in use
+  pub fn uniform(&self, name: &str) -> Option<UniformValue> {
+    let inner = self.inner.borrow();
+    let program = inner.state.program?;
+    inner.uniforms.get(&(program, name.to_string())).cloned()
+  }
+
+  /// Returns the buffer bound to `target`, if tracked
+  fn bound_buffer(&self, target: u32) -> Option<u32> {
+    let state = &self.inner.borrow().state;
+    match target {
+      GL::ARRAY_BUFFER => state.array_buffer,
+      GL::ELEMENT_ARRAY_BUFFER => state.element_array_buffer,
+      _ => None,
+    }
+  }
+
+  fn record(&self, call: Call) {
+    self.inner.borrow_mut().calls.push(call);
+  }
+
+  fn set_uniform(&self, location: Option<&UniformLocation>, value: UniformValue) {
+    let mut inner = self.inner.borrow_mut();
+    let Some((program, name)) = location
+      .and_then(|l| l.0.id())
+      .and_then(|id| inner.uniform_names.get(&id).cloned()) else { return; };
+    inner.uniforms.insert((program, name.clone()), value.clone());
+    inner.calls.push(Call::Uniform { program, name, value });
+  }
+}
+
+impl GlContext for RecordingContext {
+  fn drawing_buffer_width(&self) -> i32 { self.inner.borrow().width }
+  fn drawing_buffer_height(&self) -> i32 { self.inner.borrow().height }
+
+  fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+    self.inner.borrow_mut().state.viewport = [x, y, width, height];
+    self.record(Call::Viewport { x, y, width, height });
+  }
+
+  fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
+    self.inner.borrow_mut().state.clear_color = [red, green, blue, alpha];
+    self.record(Call::ClearColor([red, green, blue, alpha]));
+  }
+
+  fn clear(&self, mask: u32) { self.record(Call::Clear(mask)); }
+
+  fn clear_bufferuiv_with_u32_array(&self, buffer: u32, drawbuffer: i32, values: &[u32]) {
+    self.record(Call::ClearBufferuiv { buffer, drawbuffer, values: values.to_vec() });
+  }
+
+  fn enable(&self, capability: u32) {
+    self.inner.borrow_mut().state.capabilities.insert(capability);
+    self.record(Call::Enable(capability));
+  }
+
+  fn get_extension(&self, name: &str) -> bool {
+    self.record(Call::GetExtension(name.to_string()));
+    true
+  }
+
+  fn disable(&self, capability: u32) {
+    self.inner.borrow_mut().state.capabilities.remove(&capability);
+    self.record(Call::Disable(capability));
+  }
+
+  fn create_shader(&self, shader_type: u32) -> Option<Shader> {
+    let shader = self.inner.borrow_mut().allocate();
+    self.record(Call::CreateShader { shader, shader_type });
+    Some(Shader(Handle::Id(shader)))
+  }
+
+  fn shader_source(&self, shader: &Shader, source: &str) {
+    if let Some(shader) = shader.0.id() {
+      self.record(Call::ShaderSource { shader, source: source.to_string() });
+    }
+  }
+
+  fn compile_shader(&self, shader: &Shader) {
+    if let Some(shader) = shader.0.id() { self.record(Call::CompileShader(shader)); }
+  }
+
+  fn get_shader_compile_status(&self, _shader: &Shader) -> bool { true }
+  fn get_shader_info_log(&self, _shader: &Shader) -> Option<String> { None }
+
+  fn create_program(&self) -> Option<Program> {
+    let program = self.inner.borrow_mut().allocate();
+    self.record(Call::CreateProgram(program));
+    Some(Program(Handle::Id(program)))
+  }
+
+  fn attach_shader(&self, program: &Program, shader: &Shader) {
+    if let (Some(program), Some(shader)) = (program.0.id(), shader.0.id()) {
+      self.record(Call::AttachShader { program, shader });
+    }
+  }
+
+  fn link_program(&self, program: &Program) {
+    if let Some(program) = program.0.id() { self.record(Call::LinkProgram(program)); }
+  }
+
+  fn get_program_link_status(&self, _program: &Program) -> bool { true }
+  fn get_program_info_log(&self, _program: &Program) -> Option<String> { None }
+
+  fn use_program(&self, program: Option<&Program>) {
+    let program = program.and_then(|p| p.0.id());
+    self.inner.borrow_mut().state.program = program;
+    self.record(Call::UseProgram(program));
+  }
+
+  fn get_attrib_location(&self, program: &Program, name: &str) -> i32 {
+    let Some(program) = program.0.id() else { return -1; };
+    let mut inner = self.inner.borrow_mut();
+    let next = inner.attrib_locations.keys().filter(|(p, _)| *p == program).count() as i32;
+    *inner.attrib_locations.entry((program, name.to_string())).or_insert(next)
+  }
+
+  fn get_uniform_location(&self, program: &Program, name: &str) -> Option<UniformLocation> {
+    let program = program.0.id()?;
+    let mut inner = self.inner.borrow_mut();
+    let key = (program, name.to_string());
+    let location = match inner.uniform_locations.get(&key) {
+      Some(location) => *location,
+      None => {
+        let location = inner.allocate();
+        inner.uniform_locations.insert(key.clone(), location);
+        inner.uniform_names.insert(location, key);
+        location
+      },
+    };
+    Some(UniformLocation(Handle::Id(location)))
+  }
+
+  fn create_buffer(&self) -> Option<Buffer> {
+    let buffer = self.inner.borrow_mut().allocate();
+    self.record(Call::CreateBuffer(buffer));
+    Some(Buffer(Handle::Id(buffer)))
+  }
+
+  fn bind_buffer(&self, target: u32, buffer: Option<&Buffer>) {
+    let buffer = buffer.and_then(|b| b.0.id());
+    match target {
+      GL::ARRAY_BUFFER => self.inner.borrow_mut().state.array_buffer = buffer,
+      GL::ELEMENT_ARRAY_BUFFER => self.inner.borrow_mut().state.element_array_buffer = buffer,
+      _ => {},
+    }
+    self.record(Call::BindBuffer { target, buffer });
+  }
+
+  fn buffer_data_with_f32_array(&self, target: u32, data: &[f32], usage: u32) {
+    let buffer = self.bound_buffer(target);
+    self.record(Call::BufferData { target, buffer, data: data.to_vec(), usage });
+  }
+
+  fn buffer_data_with_u16_array(&self, target: u32, data: &[u16], usage: u32) {
+    let buffer = self.bound_buffer(target);
+    self.record(Call::BufferDataU16 { target, buffer, data: data.to_vec(), usage });
+  }
+
+  fn buffer_data_with_u32_array(&self, target: u32, data: &[u32], usage: u32) {
+    let buffer = self.bound_buffer(target);
+    self.record(Call::BufferDataU32 { target, buffer, data: data.to_vec(), usage });
+  }
+
+  fn delete_buffer(&self, buffer: Option<&Buffer>) {
+    if let Some(buffer) = buffer.and_then(|b| b.0.id()) { self.record(Call::DeleteBuffer(buffer)); }
+  }
+
+  fn create_vertex_array(&self) -> Option<VertexArray> {
+    let vertex_array = self.inner.borrow_mut().allocate();
+    self.record(Call::CreateVertexArray(vertex_array));
+    Some(VertexArray(Handle::Id(vertex_array)))
+  }
+
+  fn bind_vertex_array(&self, vertex_array: Option<&VertexArray>) {
+    let vertex_array = vertex_array.and_then(|v| v.0.id());
+    self.inner.borrow_mut().state.vertex_array = vertex_array;
+    self.record(Call::BindVertexArray(vertex_array));
+  }
+
+  fn delete_vertex_array(&self, vertex_array: Option<&VertexArray>) {
+    if let Some(vertex_array) = vertex_array.and_then(|v| v.0.id()) { self.record(Call::DeleteVertexArray(vertex_array)); }
+  }
+
+  fn enable_vertex_attrib_array(&self, index: u32) {
+    self.record(Call::EnableVertexAttribArray(index));
+  }
+
+  fn vertex_attrib_pointer_with_i32(&self, index: u32, size: i32, data_type: u32, normalized: bool, stride: i32, offset: i32) {
+    self.record(Call::VertexAttribPointer { index, size, data_type, normalized, stride, offset });
+  }
+
+  fn create_texture(&self) -> Option<Texture> {
+    let texture = self.inner.borrow_mut().allocate();
+    self.record(Call::CreateTexture(texture));
+    Some(Texture(Handle::Id(texture)))
+  }
+
+  fn bind_texture(&self, target: u32, texture: Option<&Texture>) {
+    let texture = texture.and_then(|t| t.0.id());
+    if target == GL::TEXTURE_2D {
+      let state = &mut self.inner.borrow_mut().state;
+      let unit = state.active_texture.saturating_sub(GL::TEXTURE0);
+      match texture {
+        Some(texture) => state.textures.insert(unit, texture),
+        None => state.textures.remove(&unit),
+      };
+    }
+    self.record(Call::BindTexture { target, texture });
+  }
+
+  fn delete_texture(&self, texture: Option<&Texture>) {
+    if let Some(texture) = texture.and_then(|t| t.0.id()) { self.record(Call::DeleteTexture(texture)); }
+  }
+
+  fn create_framebuffer(&self) -> Option<Framebuffer> {
+    let framebuffer = self.inner.borrow_mut().allocate();
+    self.record(Call::CreateFramebuffer(framebuffer));
+    Some(Framebuffer(Handle::Id(framebuffer)))
+  }
+
+  fn bind_framebuffer(&self, target: u32, framebuffer: Option<&Framebuffer>) {
+    let framebuffer = framebuffer.and_then(|f| f.0.id());
+    {
+      let state = &mut self.inner.borrow_mut().state;
+      if target != GL::READ_FRAMEBUFFER { state.framebuffer = framebuffer; }
+      if target != GL::DRAW_FRAMEBUFFER { state.read_framebuffer = framebuffer; }
+    }
+    self.record(Call::BindFramebuffer { target, framebuffer });
+  }
+
+  fn delete_framebuffer(&self, framebuffer: Option<&Framebuffer>) {
+    if let Some(framebuffer) = framebuffer.and_then(|f| f.0.id()) { self.record(Call::DeleteFramebuffer(framebuffer)); }
+  }
+
+  fn framebuffer_texture_2d(&self, target: u32, attachment: u32, textarget: u32, texture: Option<&Texture>, level: i32) {
+    let texture = texture.and_then(|t| t.0.id());
+    self.record(Call::FramebufferTexture2D { target, attachment, textarget, texture, level });
+  }
+
+  fn check_framebuffer_status(&self, _target: u32) -> u32 { GL::FRAMEBUFFER_COMPLETE }
+
+  /// Records the call, leaving `data` unchanged
+  fn read_pixels_with_u32_array(
+    &self, x: i32, y: i32, width: i32, height: i32, format: u32, data_type: u32, _data: &mut [u32],
+  ) -> Result<()> {
+    self.record(Call::ReadPixels { x, y, width, height, format, data_type });
+    Ok(())
+  }
+
+  fn blit_framebuffer(
+    &self, src_x0: i32, src_y0: i32, src_x1: i32, src_y1: i32,
+    dst_x0: i32, dst_y0: i32, dst_x1: i32, dst_y1: i32, mask: u32, filter: u32,
+  ) {
+    self.record(Call::BlitFramebuffer { src: [src_x0, src_y0, src_x1, src_y1], dst: [dst_x0, dst_y0, dst_x1, dst_y1], mask, filter });
+  }
+
+  fn create_renderbuffer(&self) -> Option<Renderbuffer> {
+    let renderbuffer = self.inner.borrow_mut().allocate();
+    self.record(Call::CreateRenderbuffer(renderbuffer));
+    Some(Renderbuffer(Handle::Id(renderbuffer)))
+  }
+
+  fn bind_renderbuffer(&self, target: u32, renderbuffer: Option<&Renderbuffer>) {
+    self.record(Call::BindRenderbuffer { target, renderbuffer: renderbuffer.and_then(|r| r.0.id()) });
+  }
+
+  fn delete_renderbuffer(&self, renderbuffer: Option<&Renderbuffer>) {
+    if let Some(renderbuffer) = renderbuffer.and_then(|r| r.0.id()) { self.record(Call::DeleteRenderbuffer(renderbuffer)); }
+  }
+
+  fn renderbuffer_storage_multisample(&self, target: u32, samples: i32, internal_format: u32, width: i32, height: i32) {
+    self.record(Call::RenderbufferStorageMultisample { target, samples, internal_format, width, height });
+  }
+
+  fn framebuffer_renderbuffer(&self, target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: Option<&Renderbuffer>) {
+    let renderbuffer = renderbuffer.and_then(|r| r.0.id());
+    self.record(Call::FramebufferRenderbuffer { target, attachment, renderbuffer_target, renderbuffer });
+  }
+
+  fn active_texture(&self, unit: u32) {
+    self.inner.borrow_mut().state.active_texture = unit;
+    self.record(Call::ActiveTexture(unit));
+  }
+
+  fn pixel_storei(&self, pname: u32, param: i32) {
+    self.record(Call::PixelStorei { pname, param });
+  }
+
+  fn tex_parameteri(&self, target: u32, pname: u32, param: i32) {
+    self.record(Call::TexParameteri { target, pname, param });
+  }
+
+  fn tex_image_2d_with_u8_array(
+    &self, target: u32, level: i32, internal_format: i32, width: i32, height: i32,
+    format: u32, data_type: u32, data: Option<&[u8]>,
+  ) -> Result<()> {
+    let data = data.map(|d| d.to_vec());
+    self.record(Call::TexImage2D { target, level, internal_format, width, height, format, data_type, data });
+    Ok(())
+  }
+
+  fn tex_image_2d_with_f32_array(
+    &self, target: u32, level: i32, internal_format: i32, width: i32, height: i32,
+    format: u32, data: &[f32],
+  ) -> Result<()> {
+    self.record(Call::TexImage2DF32 { target, level, internal_format, width, height, format, data: data.to_vec() });
+    Ok(())
+  }
+
+  fn tex_image_2d_with_html_image_element(
+    &self, target: u32, level: i32, internal_format: i32, format: u32, data_type: u32,
+    _source: &web_sys::HtmlImageElement,
+  ) -> Result<()> {
+    self.record(Call::TexImage2DSource { target, level, internal_format, format, data_type });
+    Ok(())
+  }
+
+  fn tex_image_2d_with_image_bitmap(
+    &self, target: u32, level: i32, internal_format: i32, format: u32, data_type: u32,
+    _source: &web_sys::ImageBitmap,
+  ) -> Result<()> {
+    self.record(Call::TexImage2DSource { target, level, internal_format, format, data_type });
+    Ok(())
+  }
+
+  fn generate_mipmap(&self, target: u32) {
+    self.record(Call::GenerateMipmap(target));
+  }
+
+  fn uniform1i(&self, location: Option<&UniformLocation>, x: i32) {
+    self.set_uniform(location, UniformValue::Int(x));
+  }
+
+  fn uniform1f(&self, location: Option<&UniformLocation>, x: f32) {
+    self.set_uniform(location, UniformValue::Float(x));
+  }
+
+  fn uniform4f(&self, location: Option<&UniformLocation>, x: f32, y: f32, z: f32, w: f32) {
+    self.set_uniform(location, UniformValue::Vec4([x, y, z, w]));
+  }
+
+  fn uniform3fv_with_f32_array(&self, location: Option<&UniformLocation>, data: &[f32]) {
+    if let Ok(value) = data.try_into() { self.set_uniform(location, UniformValue::Vec3(value)); }
+  }
+
+  fn uniform_matrix4fv_with_f32_array(&self, location: Option<&UniformLocation>, _transpose: bool, data: &[f32]) {
+    if let Ok(value) = data.try_into() { self.set_uniform(location, UniformValue::Mat4(value)); }
+  }
+
+  fn draw_arrays(&self, mode: u32, first: i32, count: i32) {
+    self.record(Call::DrawArrays { mode, first, count });
+  }
+
+  fn draw_elements_with_i32(&self, mode: u32, count: i32, data_type: u32, offset: i32) {
+    self.record(Call::DrawElements { mode, count, data_type, offset });
+  }
+}
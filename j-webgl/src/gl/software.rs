@@ -0,0 +1,1378 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as GL;
+
+/// Built-in programs the software context knows how to execute
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+  /// `shaders::vertex::position_matrix` with `shaders::fragment::color`
+  Color,
+  /// `shaders::vertex::matrix_position_normal` with `shaders::fragment::color_light`
+  ColorLight,
+  /// `shaders::vertex::matrix_position_normal_texcoord` with `shaders::fragment::texture_light`
+  TextureLight,
+  /// `shaders::vertex::matrix_model_position_normal` with `shaders::fragment::phong`
+  Phong,
+  /// `shaders::vertex::matrix_model_position_normal` with `shaders::fragment::phong_shadow`
+  PhongShadow,
+  /// `shaders::vertex::matrix_model_position_normal_texcoord` with `shaders::fragment::pbr`
+  Pbr,
+  /// `shaders::vertex::position_matrix` with `shaders::fragment::depth_only`
+  Depth,
+  /// `shaders::vertex::position_matrix` with `shaders::fragment::object_id`
+  ObjectId,
+}
+
+impl Kind {
+  fn from_sources(sources: &[&str]) -> Option<Kind> {
+    let known = |vertex: Result<String>, fragment: Result<String>| -> bool {
+      match (vertex, fragment) {
+        (Ok(vertex), Ok(fragment)) => sources.len() == 2
+          && sources.contains(&vertex.as_str())
+          && sources.contains(&fragment.as_str()),
+        _ => false,
+      }
+    };
+    if known(shaders::vertex::position_matrix(), shaders::fragment::color()) {
+      Some(Kind::Color)
+    } else if known(shaders::vertex::matrix_position_normal(), shaders::fragment::color_light()) {
+      Some(Kind::ColorLight)
+    } else if known(shaders::vertex::matrix_position_normal_texcoord(), shaders::fragment::texture_light()) {
+      Some(Kind::TextureLight)
+    } else if known(shaders::vertex::matrix_model_position_normal(), shaders::fragment::phong()) {
+      Some(Kind::Phong)
+    } else if known(shaders::vertex::matrix_model_position_normal(), shaders::fragment::phong_shadow()) {
+      Some(Kind::PhongShadow)
+    } else if known(shaders::vertex::position_matrix(), shaders::fragment::depth_only()) {
+      Some(Kind::Depth)
+    } else if known(shaders::vertex::position_matrix(), shaders::fragment::object_id()) {
+      Some(Kind::ObjectId)
+    } else if known(shaders::vertex::matrix_model_position_normal_texcoord(), shaders::fragment::pbr()) {
+      Some(Kind::Pbr)
+    } else {
+      None
+    }
+  }
+
+  fn attributes(&self) -> &'static [&'static str] {
+    match self {
+      Kind::Color | Kind::Depth | Kind::ObjectId => &["a_position"],
+      Kind::ColorLight | Kind::Phong | Kind::PhongShadow => &["a_position", "a_normal"],
+      Kind::TextureLight | Kind::Pbr => &["a_position", "a_normal", "a_texcoord"],
+    }
+  }
+}
+
+#[derive(Default)]
+struct ProgramObject {
+  shaders: Vec<u32>,
+  kind: Option<Kind>,
+  info_log: Option<String>,
+  uniforms: HashMap<String, UniformValue>,
+}
+
+#[derive(Clone, Copy)]
+struct Pointer {
+  buffer: u32,
+  size: i32,
+  stride: i32,
+  offset: i32,
+}
+
+#[derive(Default)]
+struct VertexArrayObject {
+  enabled: BTreeSet<u32>,
+  pointers: HashMap<u32, Pointer>,
+  element_buffer: Option<u32>,
+}
+
+#[derive(Clone, Default)]
+struct TextureObject {
+  width: i32,
+  height: i32,
+  /// Level 0 texels, rows ordered from `t = 0`, depth textures holding the depth in the red channel
+  texels: Vec<[f32; 4]>,
+  /// Whether texels are 8-bit normalized values, clamped and quantized when drawn to
+  normalized: bool,
+  parameters: HashMap<u32, i32>,
+  /// Whether `generate_mipmap` was called since the last upload
+  mipmaps: bool,
+}
+
+impl TextureObject {
+  fn parameter(&self, pname: u32, default: u32) -> u32 {
+    self.parameters.get(&pname).map(|p| *p as u32).unwrap_or(default)
+  }
+
+  /// Sample level 0 at `uv` with the magnification filter
+  ///
+  /// Textures with a mipmapped minification filter but without mipmaps are
+  /// incomplete and sample as opaque black, as in WebGL.
+  fn sample(&self, uv: [f32; 2]) -> [f32; 4] {
+    let black = [0.0, 0.0, 0.0, 1.0];
+    if self.width <= 0 || self.height <= 0 { return black; }
+    let min_filter = self.parameter(GL::TEXTURE_MIN_FILTER, GL::NEAREST_MIPMAP_LINEAR);
+    if !self.mipmaps && ![GL::NEAREST, GL::LINEAR].contains(&min_filter) { return black; }
+
+    let wrap = |coordinate: i32, size: i32, mode: u32| -> i32 {
+      match mode {
+        GL::CLAMP_TO_EDGE => coordinate.clamp(0, size - 1),
+        GL::MIRRORED_REPEAT => {
+          let period = coordinate.rem_euclid(2 * size);
+          if period < size { period } else { 2 * size - 1 - period }
+        },
+        _ => coordinate.rem_euclid(size),
+      }
+    };
+    let (wrap_s, wrap_t) = (self.parameter(GL::TEXTURE_WRAP_S, GL::REPEAT), self.parameter(GL::TEXTURE_WRAP_T, GL::REPEAT));
+    let texel = |i: i32, j: i32| self.texels[(wrap(j, self.height, wrap_t) * self.width + wrap(i, self.width, wrap_s)) as usize];
+    let (x, y) = (uv[0] * self.width as f32, uv[1] * self.height as f32);
+    if self.parameter(GL::TEXTURE_MAG_FILTER, GL::LINEAR) == GL::NEAREST {
+      return texel(x.floor() as i32, y.floor() as i32);
+    }
+    let (x, y) = (x - 0.5, y - 0.5);
+    let (i, j) = (x.floor() as i32, y.floor() as i32);
+    let (fx, fy) = (x - x.floor(), y - y.floor());
+    let (a, b, c, d) = (texel(i, j), texel(i + 1, j), texel(i, j + 1), texel(i + 1, j + 1));
+    [0, 1, 2, 3].map(|k| {
+      (a[k] * (1.0 - fx) + b[k] * fx) * (1.0 - fy) + (c[k] * (1.0 - fx) + d[k] * fx) * fy
+    })
+  }
+}
+
+#[derive(Clone, Copy)]
+struct ClipVertex {
+  position: [f32; 4],
+  normal: [f32; 3],
+  texcoord: [f32; 2],
+  /// World space position, from `u_model`
+  world: [f32; 3],
+  /// World space direction of increasing `s` texture coordinate across the triangle
+  tangent: [f32; 3],
+}
+
+impl ClipVertex {
+  /// Weighted sum of the vertices of a triangle
+  fn blend(vertices: &[ClipVertex; 3], weights: [f32; 3]) -> ClipVertex {
+    let sum = |f: &dyn Fn(&ClipVertex) -> f32| -> f32 { (0..3).map(|i| weights[i] * f(&vertices[i])).sum() };
+    ClipVertex {
+      position: [0, 1, 2, 3].map(|k| sum(&|v| v.position[k])),
+      normal: [0, 1, 2].map(|k| sum(&|v| v.normal[k])),
+      texcoord: [0, 1].map(|k| sum(&|v| v.texcoord[k])),
+      world: [0, 1, 2].map(|k| sum(&|v| v.world[k])),
+      tangent: [0, 1, 2].map(|k| sum(&|v| v.tangent[k])),
+    }
+  }
+}
+
+/// Light of the `phong` and `pbr` programs, read from the `u_lights` uniforms
+struct Light {
+  kind: i32,
+  position: [f32; 3],
+  direction: [f32; 3],
+  color: [f32; 3],
+  attenuation: [f32; 3],
+  cos_outer: f32,
+  cos_inner: f32,
+}
+
+impl Light {
+  /// Direction towards the light and radiance reaching `position`, as `lightRadiance` in the shaders
+  fn illuminate(&self, position: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+    if self.kind == 0 {
+      return (self.direction.map(|v| -v), self.color);
+    }
+    let offset = sub(self.position, position);
+    let d = dot(offset, offset).sqrt();
+    // No direction for a light on the surface, which it does not light
+    if d < 1e-6 { return ([0.0; 3], [0.0; 3]); }
+    let to_light = offset.map(|v| v / d);
+    let attenuation = self.attenuation[0] + self.attenuation[1] * d + self.attenuation[2] * d * d;
+    let spot = if self.kind == 2 { smoothstep(self.cos_outer, self.cos_inner, -dot(to_light, self.direction)) } else { 1.0 };
+    (to_light, self.color.map(|v| v * spot / attenuation))
+  }
+}
+
+/// Light uniforms shared by the `phong` and `pbr` programs
+struct Lighting {
+  lights: Vec<Light>,
+  ambient: [f32; 3],
+  eye: [f32; 3],
+}
+
+/// Typed access to the uniforms of a program, unset uniforms reading as zero
+struct Uniforms<'a>(&'a HashMap<String, UniformValue>);
+
+impl Uniforms<'_> {
+  fn int(&self, name: &str) -> i32 {
+    match self.0.get(name) { Some(UniformValue::Int(v)) => *v, _ => 0 }
+  }
+
+  fn float(&self, name: &str) -> f32 {
+    match self.0.get(name) { Some(UniformValue::Float(v)) => *v, _ => 0.0 }
+  }
+
+  fn vec3(&self, name: &str) -> [f32; 3] {
+    match self.0.get(name) { Some(UniformValue::Vec3(v)) => *v, _ => [0.0; 3] }
+  }
+
+  fn vec4(&self, name: &str) -> [f32; 4] {
+    match self.0.get(name) { Some(UniformValue::Vec4(v)) => *v, _ => [0.0; 4] }
+  }
+
+  fn lighting(&self) -> Lighting {
+    let count = self.int("u_lightCount").clamp(0, shaders::fragment::MAX_LIGHTS as i32);
+    let lights = (0..count)
+      .map(|i| {
+        let name = |field: &str| format!("u_lights[{i}].{field}");
+        Light {
+          kind: self.int(&name("type")),
+          position: self.vec3(&name("position")),
+          direction: self.vec3(&name("direction")),
+          color: self.vec3(&name("color")),
+          attenuation: self.vec3(&name("attenuation")),
+          cos_outer: self.float(&name("cosOuter")),
+          cos_inner: self.float(&name("cosInner")),
+        }
+      })
+      .collect();
+    Lighting { lights, ambient: self.vec3("u_ambient"), eye: self.vec3("u_eyePosition") }
+  }
+}
+
+/// Shadow map uniforms of the `phong_shadow` program
+struct Shadow {
+  /// Index of the shadow casting light, negative for none
+  light: i32,
+  matrix: [f32; 16],
+  bias: f32,
+  pcf_radius: i32,
+  map: Option<Rc<TextureObject>>,
+}
+
+impl Shadow {
+  /// Fraction of the depth samples around `position` that see the light, as `shadow` in the shader
+  fn visibility(&self, position: [f32; 3]) -> f32 {
+    let p = transform(&self.matrix, &[position[0], position[1], position[2], 1.0]);
+    let coords = [0, 1, 2].map(|k| p[k] / p[3] * 0.5 + 0.5);
+    if coords.iter().any(|c| !(0.0..=1.0).contains(c)) { return 1.0; }
+    let (width, height) = self.map.as_ref().map(|m| (m.width.max(1), m.height.max(1))).unwrap_or((1, 1));
+    let radius = self.pcf_radius.max(0);
+    let (mut lit, mut count) = (0.0, 0.0);
+    for x in -radius..=radius {
+      for y in -radius..=radius {
+        let uv = [coords[0] + x as f32 / width as f32, coords[1] + y as f32 / height as f32];
+        let depth = self.map.as_ref().map(|m| m.sample(uv)[0]).unwrap_or(0.0);
+        if coords[2] - self.bias <= depth { lit += 1.0; }
+        count += 1.0;
+      }
+    }
+    lit / count
+  }
+}
+
+/// Uniforms of the `phong` and `phong_shadow` programs
+struct Phong {
+  lighting: Lighting,
+  specular: [f32; 3],
+  shininess: f32,
+  blinn: bool,
+  shadow: Option<Shadow>,
+}
+
+impl Phong {
+  fn from_uniforms(uniforms: &Uniforms) -> Phong {
+    Phong {
+      lighting: uniforms.lighting(),
+      specular: uniforms.vec3("u_specularColor"),
+      shininess: uniforms.float("u_shininess"),
+      blinn: uniforms.int("u_blinn") != 0,
+      shadow: None,
+    }
+  }
+
+  /// Color of a surface point, as in the `phong` shader
+  fn shade(&self, position: [f32; 3], normal: [f32; 3], color: [f32; 4]) -> [f32; 4] {
+    let normal = normalize(normal);
+    let to_eye = normalize(sub(self.lighting.eye, position));
+    let mut diffuse = self.lighting.ambient;
+    let mut specular = [0.0; 3];
+    for (i, light) in self.lighting.lights.iter().enumerate() {
+      let (to_light, radiance) = light.illuminate(position);
+      let visibility = match &self.shadow {
+        Some(shadow) if shadow.light == i as i32 => shadow.visibility(position),
+        _ => 1.0,
+      };
+      let radiance = radiance.map(|v| v * visibility);
+      let lambert = dot(normal, to_light).max(0.0);
+      if lambert > 0.0 {
+        let angle = if self.blinn {
+          dot(normal, normalize([0, 1, 2].map(|k| to_light[k] + to_eye[k])))
+        } else {
+          let reflected = [0, 1, 2].map(|k| 2.0 * dot(normal, to_light) * normal[k] - to_light[k]);
+          dot(reflected, to_eye)
+        };
+        let highlight = angle.max(0.0).powf(self.shininess);
+        for k in 0..3 {
+          diffuse[k] += lambert * radiance[k];
+          specular[k] += highlight * radiance[k];
+        }
+      }
+    }
+    [
+      color[0] * diffuse[0] + self.specular[0] * specular[0],
+      color[1] * diffuse[1] + self.specular[1] * specular[1],
+      color[2] * diffuse[2] + self.specular[2] * specular[2],
+      color[3],
+    ]
+  }
+}
+
+/// Uniforms and bound maps of the `pbr` program
+struct Pbr {
+  lighting: Lighting,
+  base_color: [f32; 4],
+  metallic: f32,
+  roughness: f32,
+  emissive: [f32; 3],
+  normal_scale: f32,
+  occlusion_strength: f32,
+  /// Base color, metallic-roughness, normal, occlusion and emissive maps
+  maps: [Option<Rc<TextureObject>>; 5],
+}
+
+impl Pbr {
+  /// Color of a surface point, as in the `pbr` shader, the tangent frame
+  /// being that of the rasterized triangle
+  fn shade(&self, vertex: &ClipVertex) -> [f32; 4] {
+    let sample = |map: usize| self.maps[map].as_ref().map(|t| t.sample(vertex.texcoord));
+    let to_linear = |v: f32| v.max(0.0).powf(2.2);
+
+    let mut base_color = self.base_color;
+    if let Some(texel) = sample(0) {
+      for k in 0..3 { base_color[k] *= to_linear(texel[k]); }
+      base_color[3] *= texel[3];
+    }
+    let (mut metallic, mut roughness) = (self.metallic, self.roughness);
+    if let Some(texel) = sample(1) {
+      roughness *= texel[1];
+      metallic *= texel[2];
+    }
+    let roughness = roughness.clamp(0.04, 1.0);
+    let metallic = metallic.clamp(0.0, 1.0);
+
+    let mut normal = normalize(vertex.normal);
+    if let Some(texel) = sample(2) {
+      let t = normalize(sub(vertex.tangent, normal.map(|v| v * dot(normal, vertex.tangent))));
+      let b = cross(normal, t);
+      let m = [texel[0] * 2.0 - 1.0, texel[1] * 2.0 - 1.0, texel[2] * 2.0 - 1.0];
+      let (x, y) = (m[0] * self.normal_scale, m[1] * self.normal_scale);
+      normal = normalize([0, 1, 2].map(|k| t[k] * x + b[k] * y + normal[k] * m[2]));
+    }
+    let to_eye = normalize(sub(self.lighting.eye, vertex.world));
+    let n_dot_v = dot(normal, to_eye).max(1e-4);
+
+    let f0 = [0, 1, 2].map(|k| 0.04 + (base_color[k] - 0.04) * metallic);
+    let alpha = roughness * roughness;
+    let k_geometry = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+
+    let mut color = [0.0; 3];
+    for light in self.lighting.lights.iter() {
+      let (to_light, radiance) = light.illuminate(vertex.world);
+      let n_dot_l = dot(normal, to_light);
+      if n_dot_l <= 0.0 { continue; }
+      let halfway = normalize([0, 1, 2].map(|k| to_light[k] + to_eye[k]));
+      let n_dot_h = dot(normal, halfway).max(0.0);
+      let v_dot_h = dot(to_eye, halfway).max(0.0);
+      let d = n_dot_h * n_dot_h * (alpha * alpha - 1.0) + 1.0;
+      let distribution = alpha * alpha / (std::f32::consts::PI * d * d);
+      let geometry = n_dot_l / (n_dot_l * (1.0 - k_geometry) + k_geometry)
+        * n_dot_v / (n_dot_v * (1.0 - k_geometry) + k_geometry);
+      for k in 0..3 {
+        let fresnel = f0[k] + (1.0 - f0[k]) * (1.0 - v_dot_h).powi(5);
+        let diffuse = (1.0 - fresnel) * (1.0 - metallic) * base_color[k] / std::f32::consts::PI;
+        let specular = distribution * geometry * fresnel / (4.0 * n_dot_l * n_dot_v);
+        color[k] += (diffuse + specular) * radiance[k] * n_dot_l;
+      }
+    }
+
+    let occlusion = sample(3).map(|texel| 1.0 + (texel[0] - 1.0) * self.occlusion_strength).unwrap_or(1.0);
+    let emissive = match sample(4) {
+      Some(texel) => [0, 1, 2].map(|k| self.emissive[k] * to_linear(texel[k])),
+      None => self.emissive,
+    };
+    let [red, green, blue] = [0, 1, 2].map(|k| {
+      (color[k] + self.lighting.ambient[k] * base_color[k] * occlusion + emissive[k]).max(0.0).powf(1.0 / 2.2)
+    });
+    [red, green, blue, base_color[3]]
+  }
+}
+
+#[derive(Default)]
+struct Inner {
+  width: i32,
+  height: i32,
+  /// Drawing buffer, 8-bit values stored as `f32`
+  color: Vec<[f32; 4]>,
+  depth: Vec<f32>,
+  next_id: u32,
+  viewport: [i32; 4],
+  clear_color: [f32; 4],
+  capabilities: BTreeSet<u32>,
+  program: Option<u32>,
+  vertex_array: u32,
+  array_buffer: Option<u32>,
+  shaders: HashMap<u32, String>,
+  programs: HashMap<u32, ProgramObject>,
+  /// Buffer contents as raw bytes, in native byte order
+  buffers: HashMap<u32, Vec<u8>>,
+  vertex_arrays: HashMap<u32, VertexArrayObject>,
+  uniform_locations: HashMap<(u32, String), u32>,
+  uniform_names: HashMap<u32, (u32, String)>,
+  textures: HashMap<u32, Rc<TextureObject>>,
+  /// Active texture unit index, and texture bound to `TEXTURE_2D` on each unit
+  active_texture: u32,
+  texture_units: HashMap<u32, u32>,
+  unpack_alignment: i32,
+  /// Textures and renderbuffers attached to each framebuffer, by attachment point
+  framebuffers: HashMap<u32, HashMap<u32, u32>>,
+  /// Framebuffer bound to `DRAW_FRAMEBUFFER`, `None` for the drawing buffer
+  framebuffer: Option<u32>,
+  /// Framebuffer bound to `READ_FRAMEBUFFER`, `None` for the drawing buffer
+  read_framebuffer: Option<u32>,
+  /// Renderbuffer bound to `RENDERBUFFER`, renderbuffers being stored with the textures
+  renderbuffer: Option<u32>,
+}
+
+/// Planes written by draws and clears, moved out of the drawing buffer or of
+/// the textures attached to the bound framebuffer for the duration of a call
+struct Target {
+  width: i32,
+  height: i32,
+  color: Option<Vec<[f32; 4]>>,
+  /// Whether color values are clamped and quantized to 8 bits
+  normalized: bool,
+  depth: Option<Vec<f32>>,
+}
+
+impl Target {
+  fn write_color(&mut self, index: usize, color: [f32; 4]) {
+    let normalized = self.normalized;
+    if let Some(pixel) = self.color.as_mut().and_then(|c| c.get_mut(index)) {
+      *pixel = if normalized { color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() / 255.0) } else { color };
+    }
+  }
+}
+
+/// Pure-Rust [`GlContext`] rasterizing the library built-in programs on the CPU
+///
+/// The context executes `position_matrix`/`color` and
+/// `matrix_position_normal`/`color_light` programs into an RGBA framebuffer,
+/// honouring `DEPTH_TEST` (with `LESS` comparison) and `CULL_FACE` (back faces,
+/// counter-clockwise front faces) as enabled by
+/// [`Initializer`](crate::structs::renderable::Initializer). Linking any other
+/// program fails. Only `TRIANGLES` draws are rasterized. The
+/// `matrix_position_normal_texcoord`/`texture_light` program samples `RGBA`,
+/// `RGB` and `RED` textures uploaded from bytes or floats, at level 0 with the
+/// wrap modes and magnification filter of the texture. The
+/// `matrix_model_position_normal`/`phong` program shades with the lights
+/// set on its `u_lights` uniforms, and the
+/// `matrix_model_position_normal_texcoord`/`pbr` program with its material
+/// maps, normal maps using the tangent frame of each triangle.
+///
+/// Draws and clears go to the textures attached to the bound framebuffer
+/// (`COLOR_ATTACHMENT0` and `DEPTH_ATTACHMENT`) if any, as for the depth
+/// passes of the `position_matrix`/`depth_only` program sampled by the
+/// `matrix_model_position_normal`/`phong_shadow` program. The
+/// `position_matrix`/`object_id` program writes identifiers into integer
+/// attachments, cleared with `clear_bufferuiv` and read back with `read_pixels`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rc::Rc;
+/// use j_webgl::{Renderer, gl::SoftwareContext, structs};
+///
+/// # fn example() -> j_webgl::Result<()> {
+/// let context = Rc::new(SoftwareContext::new(128, 128));
+/// let mut renderer = Renderer::from_context(context.clone());
+/// renderer.with_renderable("background".to_string(), Some(structs::renderable::Initializer::new()?))?;
+/// renderer.with_renderable("cube".to_string(), Some(structs::renderable::Cube::new()?))?;
+/// renderer.render(&structs::camera::Basic::new(128.0, 128.0)?)?;
+///
+/// let rgba = context.pixels();
+/// assert_eq!(rgba.len(), 128 * 128 * 4);
+/// # Ok(())
+/// # }
+/// # example().unwrap();
+/// ```
+pub struct SoftwareContext {
+  inner: RefCell<Inner>,
+}
+
+impl SoftwareContext {
+  /// Create a context with a framebuffer of the given size in pixels
+  pub fn new(width: i32, height: i32) -> Self {
+    let n = (width.max(0) * height.max(0)) as usize;
+    let mut inner = Inner {
+      width, height,
+      color: vec![[0.0, 0.0, 0.0, 0.0]; n],
+      depth: vec![1.0; n],
+      viewport: [0, 0, width, height],
+      unpack_alignment: 4,
+      ..Default::default()
+    };
+    inner.vertex_arrays.insert(0, VertexArrayObject::default());
+    SoftwareContext { inner: RefCell::new(inner) }
+  }
+
+  /// Framebuffer width in pixels
+  pub fn width(&self) -> i32 { self.inner.borrow().width }
+
+  /// Framebuffer height in pixels
+  pub fn height(&self) -> i32 { self.inner.borrow().height }
+
+  /// Returns the framebuffer as RGBA8 rows ordered from top to bottom,
+  /// ready to be encoded as an image
+  pub fn pixels(&self) -> Vec<u8> {
+    let inner = self.inner.borrow();
+    let row = inner.width.max(0) as usize * 4;
+    if row == 0 { return Vec::new(); }
+    inner.color.chunks(row / 4).rev().flatten().flatten().map(|c| (c * 255.0).round() as u8).collect()
+  }
+}
+
+impl Inner {
+  fn allocate(&mut self) -> u32 {
+    self.next_id += 1;
+    self.next_id
+  }
+
+  fn attribute(&self, vao: &VertexArrayObject, index: u32, vertex: i32) -> [f32; 4] {
+    let mut value = [0.0, 0.0, 0.0, 1.0];
+    if !vao.enabled.contains(&index) { return value; }
+    let Some(pointer) = vao.pointers.get(&index) else { return value; };
+    let Some(data) = self.buffers.get(&pointer.buffer) else { return value; };
+    let stride = if pointer.stride == 0 { pointer.size * 4 } else { pointer.stride };
+    let start = (pointer.offset + vertex * stride) as usize;
+    for (i, v) in value.iter_mut().take(pointer.size as usize).enumerate() {
+      if let Some(bytes) = data.get(start + 4 * i..start + 4 * i + 4) {
+        *v = f32::from_ne_bytes(bytes.try_into().unwrap_or_default());
+      }
+    }
+    value
+  }
+
+  /// Buffer bound to `target`, the element array buffer being part of the bound vertex array
+  fn bound_buffer(&self, target: u32) -> Option<u32> {
+    match target {
+      GL::ARRAY_BUFFER => self.array_buffer,
+      GL::ELEMENT_ARRAY_BUFFER => self.vertex_arrays.get(&self.vertex_array).and_then(|v| v.element_buffer),
+      _ => None,
+    }
+  }
+
+  fn buffer_data(&mut self, target: u32, bytes: Vec<u8>) {
+    if let Some(buffer) = self.bound_buffer(target) {
+      self.buffers.insert(buffer, bytes);
+    }
+  }
+
+  /// Read `count` indices of `data_type` at byte `offset` of the element array buffer
+  fn indices(&self, count: i32, data_type: u32, offset: i32) -> Vec<i32> {
+    let Some(data) = self.bound_buffer(GL::ELEMENT_ARRAY_BUFFER).and_then(|b| self.buffers.get(&b)) else { return Vec::new(); };
+    let size = match data_type {
+      GL::UNSIGNED_BYTE => 1,
+      GL::UNSIGNED_SHORT => 2,
+      GL::UNSIGNED_INT => 4,
+      _ => return Vec::new(),
+    };
+    data.get(offset.max(0) as usize..).unwrap_or_default()
+      .chunks_exact(size)
+      .take(count.max(0) as usize)
+      .map(|b| match b {
+        [b0] => *b0 as i32,
+        [b0, b1] => u16::from_ne_bytes([*b0, *b1]) as i32,
+        [b0, b1, b2, b3] => u32::from_ne_bytes([*b0, *b1, *b2, *b3]) as i32,
+        _ => 0,
+      })
+      .collect()
+  }
+
+  /// Texture bound to `TEXTURE_2D` on the texture unit index set on `uniform`
+  fn sampler(&self, program: &ProgramObject, uniform: &str) -> Option<Rc<TextureObject>> {
+    let unit = match program.uniforms.get(uniform) { Some(UniformValue::Int(unit)) => *unit as u32, _ => 0 };
+    self.texture_units.get(&unit).and_then(|t| self.textures.get(t)).cloned()
+  }
+
+  /// Upload level 0 of the texture bound to the active unit from rows of
+  /// `channels` values, each row starting on a multiple of `alignment` values
+  fn tex_image(
+    &mut self, width: i32, height: i32, channels: usize, alignment: usize, normalized: bool,
+    values: impl Fn(usize) -> Option<f32>,
+  ) {
+    let Some(texture) = self.texture_units.get(&self.active_texture).copied() else { return; };
+    let (width, height) = (width.max(0), height.max(0));
+    let row = (width as usize * channels).div_ceil(alignment) * alignment;
+    let texels = (0..height as usize).flat_map(|y| (0..width as usize).map(move |x| (y, x)))
+      .map(|(y, x)| {
+        let value = |k: usize| values(y * row + x * channels + k).unwrap_or_default();
+        match channels {
+          1 => [value(0), 0.0, 0.0, 1.0],
+          3 => [value(0), value(1), value(2), 1.0],
+          _ => [value(0), value(1), value(2), value(3)],
+        }
+      })
+      .collect();
+    let texture = Rc::make_mut(self.textures.entry(texture).or_default());
+    (texture.width, texture.height, texture.texels, texture.normalized, texture.mipmaps) = (width, height, texels, normalized, false);
+  }
+
+  /// Planes of a framebuffer, `None` if it is incomplete
+  fn take_target(&mut self, framebuffer: Option<u32>) -> Option<Target> {
+    let Some(framebuffer) = framebuffer else {
+      return Some(Target {
+        width: self.width, height: self.height,
+        color: Some(std::mem::take(&mut self.color)),
+        normalized: true,
+        depth: Some(std::mem::take(&mut self.depth)),
+      });
+    };
+    let (color, depth) = self.attachments(framebuffer)?;
+    let mut take = |texture: Option<u32>| {
+      texture.and_then(|t| self.textures.get_mut(&t)).map(|t| {
+        let t = Rc::make_mut(t);
+        (t.width, t.height, t.normalized, std::mem::take(&mut t.texels))
+      })
+    };
+    let (color, depth) = (take(color), take(depth));
+    let (width, height) = color.as_ref().or(depth.as_ref()).map(|(w, h, _, _)| (*w, *h))?;
+    Some(Target {
+      width, height,
+      normalized: color.as_ref().map(|c| c.2).unwrap_or(true),
+      color: color.map(|c| c.3),
+      depth: depth.map(|d| d.3.iter().map(|t| t[0]).collect()),
+    })
+  }
+
+  /// Move back the planes taken by [`Inner::take_target`]
+  fn restore_target(&mut self, framebuffer: Option<u32>, target: Target) {
+    let Some(framebuffer) = framebuffer else {
+      self.color = target.color.unwrap_or_default();
+      self.depth = target.depth.unwrap_or_default();
+      return;
+    };
+    let Some((color, depth)) = self.attachments(framebuffer) else { return; };
+    let mut restore = |texture: Option<u32>, texels: Option<Vec<[f32; 4]>>| {
+      if let (Some(texture), Some(texels)) = (texture.and_then(|t| self.textures.get_mut(&t)), texels) {
+        Rc::make_mut(texture).texels = texels;
+      }
+    };
+    restore(color, target.color);
+    restore(depth, target.depth.map(|d| d.into_iter().map(|z| [z, 0.0, 0.0, 1.0]).collect()));
+  }
+
+  /// Color and depth textures attached to a framebuffer, `None` if the framebuffer
+  /// has no attachment or attachments of different sizes
+  fn attachments(&self, framebuffer: u32) -> Option<(Option<u32>, Option<u32>)> {
+    let attachments = self.framebuffers.get(&framebuffer)?;
+    let color = attachments.get(&GL::COLOR_ATTACHMENT0).copied();
+    let depth = attachments.get(&GL::DEPTH_ATTACHMENT).or(attachments.get(&GL::DEPTH_STENCIL_ATTACHMENT)).copied();
+    let sizes: Vec<(i32, i32)> = [color, depth].iter().flatten()
+      .map(|t| self.textures.get(t).map(|t| (t.width, t.height)).unwrap_or_default())
+      .collect();
+    (!sizes.is_empty() && sizes.iter().all(|s| *s == sizes[0] && s.0 > 0 && s.1 > 0)).then_some((color, depth))
+  }
+
+  fn draw_triangles(&mut self, indices: &[i32]) {
+    let Some(program) = self.program.and_then(|p| self.programs.get(&p)) else { return; };
+    let Some(kind) = program.kind else { return; };
+    let Some(UniformValue::Mat4(matrix)) = program.uniforms.get("u_matrix").cloned() else { return; };
+    let color = match program.uniforms.get("u_color") {
+      Some(UniformValue::Vec4(c)) => *c,
+      _ => [0.0, 0.0, 0.0, 0.0],
+    };
+    let light = match program.uniforms.get("u_reverseLightDirection") {
+      Some(UniformValue::Vec3(l)) => *l,
+      _ => [0.0, 0.0, 0.0],
+    };
+    let normal_matrix = match program.uniforms.get("u_normalMatrix") {
+      Some(UniformValue::Mat4(m)) => *m,
+      _ => [0.0; 16],
+    };
+    let model = match program.uniforms.get("u_model") {
+      Some(UniformValue::Mat4(m)) => *m,
+      _ => [0.0; 16],
+    };
+    let texture = self.sampler(program, "u_texture");
+    let uniforms = Uniforms(&program.uniforms);
+    let phong = matches!(kind, Kind::Phong | Kind::PhongShadow).then(|| Phong {
+      shadow: (kind == Kind::PhongShadow).then(|| Shadow {
+        light: uniforms.int("u_shadowLight"),
+        matrix: match program.uniforms.get("u_shadowMatrix") { Some(UniformValue::Mat4(m)) => *m, _ => [0.0; 16] },
+        bias: uniforms.float("u_shadowBias"),
+        pcf_radius: uniforms.int("u_pcfRadius"),
+        map: self.sampler(program, "u_shadowMap"),
+      }),
+      ..Phong::from_uniforms(&uniforms)
+    });
+    let pbr = (kind == Kind::Pbr).then(|| {
+      let map = |sampler: &str, flag: &str| (uniforms.int(flag) != 0).then(|| self.sampler(program, sampler)).flatten();
+      Pbr {
+        lighting: uniforms.lighting(),
+        base_color: uniforms.vec4("u_baseColor"),
+        metallic: uniforms.float("u_metallic"),
+        roughness: uniforms.float("u_roughness"),
+        emissive: uniforms.vec3("u_emissive"),
+        normal_scale: uniforms.float("u_normalScale"),
+        occlusion_strength: uniforms.float("u_occlusionStrength"),
+        maps: [
+          map("u_baseColorTexture", "u_hasBaseColorTexture"),
+          map("u_metallicRoughnessTexture", "u_hasMetallicRoughnessTexture"),
+          map("u_normalTexture", "u_hasNormalTexture"),
+          map("u_occlusionTexture", "u_hasOcclusionTexture"),
+          map("u_emissiveTexture", "u_hasEmissiveTexture"),
+        ],
+      }
+    });
+    let object_id = uniforms.int("u_id") as f32;
+    let Some(vao) = self.vertex_arrays.get(&self.vertex_array) else { return; };
+
+    let vertices: Vec<ClipVertex> = indices.iter()
+      .map(|&i| {
+        let position = self.attribute(vao, 0, i);
+        let normal = if !matches!(kind, Kind::Color | Kind::Depth | Kind::ObjectId) { self.attribute(vao, 1, i) } else { [0.0; 4] };
+        let normal = transform(&normal_matrix, &[normal[0], normal[1], normal[2], 0.0]);
+        let texcoord = if matches!(kind, Kind::TextureLight | Kind::Pbr) { self.attribute(vao, 2, i) } else { [0.0; 4] };
+        let world = transform(&model, &position);
+        ClipVertex {
+          position: transform(&matrix, &position),
+          normal: [normal[0], normal[1], normal[2]],
+          texcoord: [texcoord[0], texcoord[1]],
+          world: [world[0], world[1], world[2]],
+          tangent: [0.0; 3],
+        }
+      })
+      .collect();
+
+    let shade = |vertex: &ClipVertex| -> [f32; 4] {
+      let lit = |color: [f32; 4]| {
+        let n = normalize(vertex.normal);
+        let l = n[0] * light[0] + n[1] * light[1] + n[2] * light[2];
+        [color[0] * l, color[1] * l, color[2] * l, color[3]]
+      };
+      match kind {
+        Kind::Color | Kind::Depth => color,
+        Kind::ObjectId => [object_id, 0.0, 0.0, 0.0],
+        Kind::ColorLight => lit(color),
+        Kind::TextureLight => {
+          let texel = texture.as_ref().map(|t| t.sample(vertex.texcoord)).unwrap_or([0.0, 0.0, 0.0, 1.0]);
+          lit([0, 1, 2, 3].map(|k| texel[k] * color[k]))
+        },
+        Kind::Phong | Kind::PhongShadow => phong.as_ref().map(|p| p.shade(vertex.world, vertex.normal, color)).unwrap_or(color),
+        Kind::Pbr => pbr.as_ref().map(|p| p.shade(vertex)).unwrap_or(color),
+      }
+    };
+
+    let Some(mut target) = self.take_target(self.framebuffer) else { return; };
+    for triangle in vertices.chunks_exact(3) {
+      let mut triangle: [ClipVertex; 3] = [triangle[0], triangle[1], triangle[2]];
+      let tangent = tangent(&triangle);
+      for vertex in triangle.iter_mut() { vertex.tangent = tangent; }
+      let polygon = clip_near(&triangle);
+      for i in 1..polygon.len().saturating_sub(1) {
+        self.rasterize(&mut target, [polygon[0], polygon[i], polygon[i + 1]], &shade);
+      }
+    }
+    self.restore_target(self.framebuffer, target);
+  }
+
+  fn rasterize(&self, target: &mut Target, triangle: [ClipVertex; 3], shade: &dyn Fn(&ClipVertex) -> [f32; 4]) {
+    let [vx, vy, vw, vh] = self.viewport;
+    let window = triangle.map(|v| {
+      let w = v.position[3];
+      [
+        (v.position[0] / w + 1.0) * 0.5 * vw as f32 + vx as f32,
+        (v.position[1] / w + 1.0) * 0.5 * vh as f32 + vy as f32,
+        (v.position[2] / w + 1.0) * 0.5,
+        1.0 / w,
+      ]
+    });
+    let [a, b, c] = window;
+    let area = edge(&a, &b, &c);
+    if area == 0.0 || !area.is_finite() { return; }
+    if self.capabilities.contains(&GL::CULL_FACE) && area < 0.0 { return; }
+    let depth_test = self.capabilities.contains(&GL::DEPTH_TEST) && target.depth.is_some();
+
+    let x_min = a[0].min(b[0]).min(c[0]).floor().max(vx.max(0) as f32) as i32;
+    let x_max = a[0].max(b[0]).max(c[0]).ceil().min((vx + vw).min(target.width) as f32) as i32;
+    let y_min = a[1].min(b[1]).min(c[1]).floor().max(vy.max(0) as f32) as i32;
+    let y_max = a[1].max(b[1]).max(c[1]).ceil().min((vy + vh).min(target.height) as f32) as i32;
+
+    for y in y_min..y_max {
+      for x in x_min..x_max {
+        let p = [x as f32 + 0.5, y as f32 + 0.5];
+        let b0 = edge(&b, &c, &p) / area;
+        let b1 = edge(&c, &a, &p) / area;
+        let b2 = edge(&a, &b, &p) / area;
+        if b0 < 0.0 || b1 < 0.0 || b2 < 0.0 { continue; }
+
+        let z = b0 * a[2] + b1 * b[2] + b2 * c[2];
+        if !(0.0..=1.0).contains(&z) { continue; }
+        let index = (y * target.width + x) as usize;
+        if let Some(depth) = target.depth.as_mut().filter(|_| depth_test) {
+          if z >= depth[index] { continue; }
+          depth[index] = z;
+        }
+        if target.color.is_none() { continue; }
+
+        // Perspective-correct interpolation of the varyings
+        let (p0, p1, p2) = (b0 * a[3], b1 * b[3], b2 * c[3]);
+        let sum = p0 + p1 + p2;
+        target.write_color(index, shade(&ClipVertex::blend(&triangle, [p0 / sum, p1 / sum, p2 / sum])));
+      }
+    }
+  }
+}
+
+/// Number of channels of the texture formats supported by the software context
+fn channels(format: u32) -> Option<usize> {
+  match format {
+    GL::RGBA | GL::RGBA_INTEGER => Some(4),
+    GL::RGB => Some(3),
+    GL::RED | GL::RED_INTEGER | GL::DEPTH_COMPONENT | GL::DEPTH_STENCIL => Some(1),
+    _ => None,
+  }
+}
+
+/// Multiply a column-major 4x4 matrix with a vector
+fn transform(m: &[f32; 16], v: &[f32; 4]) -> [f32; 4] {
+  [0, 1, 2, 3].map(|r| m[r] * v[0] + m[4 + r] * v[1] + m[8 + r] * v[2] + m[12 + r] * v[3])
+}
+
+/// World space direction of increasing `s` texture coordinate across a triangle
+fn tangent(triangle: &[ClipVertex; 3]) -> [f32; 3] {
+  let (e1, e2) = (sub(triangle[1].world, triangle[0].world), sub(triangle[2].world, triangle[0].world));
+  let (s1, t1) = (triangle[1].texcoord[0] - triangle[0].texcoord[0], triangle[1].texcoord[1] - triangle[0].texcoord[1]);
+  let (s2, t2) = (triangle[2].texcoord[0] - triangle[0].texcoord[0], triangle[2].texcoord[1] - triangle[0].texcoord[1]);
+  let determinant = s1 * t2 - s2 * t1;
+  if determinant == 0.0 { return [0.0; 3]; }
+  [0, 1, 2].map(|k| (t2 * e1[k] - t1 * e2[k]) / determinant)
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+  [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+  [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// GLSL `smoothstep`
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+  let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+  t * t * (3.0 - 2.0 * t)
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+  let n = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+  if n > 0.0 { [v[0] / n, v[1] / n, v[2] / n] } else { v }
+}
+
+/// Twice the signed area of triangle `a`, `b`, `p`, positive when counter-clockwise
+fn edge(a: &[f32], b: &[f32], p: &[f32]) -> f32 {
+  (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// Clip a triangle against the near plane (`z >= -w`), returning a convex polygon
+fn clip_near(triangle: &[ClipVertex]) -> Vec<ClipVertex> {
+  let distance = |v: &ClipVertex| v.position[2] + v.position[3];
+  let mut polygon = Vec::with_capacity(4);
+  for i in 0..triangle.len() {
+    let current = triangle[i];
+    let next = triangle[(i + 1) % triangle.len()];
+    let (dc, dn) = (distance(&current), distance(&next));
+    if dc >= 0.0 { polygon.push(current); }
+    if (dc >= 0.0) != (dn >= 0.0) {
+      let t = dc / (dc - dn);
+      polygon.push(ClipVertex::blend(&[current, next, next], [1.0 - t, t, 0.0]));
+    }
+  }
+  polygon
+}
+
+impl GlContext for SoftwareContext {
+  fn drawing_buffer_width(&self) -> i32 { self.width() }
+  fn drawing_buffer_height(&self) -> i32 { self.height() }
+
+  fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+    self.inner.borrow_mut().viewport = [x, y, width, height];
+  }
+
+  fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
+    self.inner.borrow_mut().clear_color = [red, green, blue, alpha];
+  }
+
+  fn clear(&self, mask: u32) {
+    let mut inner = self.inner.borrow_mut();
+    let framebuffer = inner.framebuffer;
+    let Some(mut target) = inner.take_target(framebuffer) else { return; };
+    if mask & GL::COLOR_BUFFER_BIT != 0 {
+      for index in 0..target.color.as_ref().map(|c| c.len()).unwrap_or(0) {
+        target.write_color(index, inner.clear_color);
+      }
+    }
+    if let Some(depth) = target.depth.as_mut().filter(|_| mask & GL::DEPTH_BUFFER_BIT != 0) {
+      depth.fill(1.0);
+    }
+    inner.restore_target(framebuffer, target);
+  }
+
+  fn clear_bufferuiv_with_u32_array(&self, buffer: u32, drawbuffer: i32, values: &[u32]) {
+    if buffer != GL::COLOR || drawbuffer != 0 { return; }
+    let mut inner = self.inner.borrow_mut();
+    let framebuffer = inner.framebuffer;
+    let Some(mut target) = inner.take_target(framebuffer) else { return; };
+    let value = [0, 1, 2, 3].map(|k| values.get(k).copied().unwrap_or_default() as f32);
+    if let Some(color) = target.color.as_mut() { color.fill(value); }
+    inner.restore_target(framebuffer, target);
+  }
+
+  fn enable(&self, capability: u32) { self.inner.borrow_mut().capabilities.insert(capability); }
+  fn disable(&self, capability: u32) { self.inner.borrow_mut().capabilities.remove(&capability); }
+
+  fn get_extension(&self, name: &str) -> bool {
+    name == "EXT_color_buffer_float"
+  }
+
+  fn create_shader(&self, _shader_type: u32) -> Option<Shader> {
+    let mut inner = self.inner.borrow_mut();
+    let shader = inner.allocate();
+    inner.shaders.insert(shader, String::new());
+    Some(Shader(Handle::Id(shader)))
+  }
+
+  fn shader_source(&self, shader: &Shader, source: &str) {
+    let Some(shader) = shader.0.id() else { return; };
+    if let Some(s) = self.inner.borrow_mut().shaders.get_mut(&shader) { *s = source.to_string(); }
+  }
+
+  fn compile_shader(&self, _shader: &Shader) {}
+
+  fn get_shader_compile_status(&self, shader: &Shader) -> bool {
+    shader.0.id().is_some_and(|id| self.inner.borrow().shaders.contains_key(&id))
+  }
+
+  fn get_shader_info_log(&self, _shader: &Shader) -> Option<String> { None }
+
+  fn create_program(&self) -> Option<Program> {
+    let mut inner = self.inner.borrow_mut();
+    let program = inner.allocate();
+    inner.programs.insert(program, ProgramObject::default());
+    Some(Program(Handle::Id(program)))
+  }
+
+  fn attach_shader(&self, program: &Program, shader: &Shader) {
+    let (Some(program), Some(shader)) = (program.0.id(), shader.0.id()) else { return; };
+    if let Some(p) = self.inner.borrow_mut().programs.get_mut(&program) { p.shaders.push(shader); }
+  }
+
+  fn link_program(&self, program: &Program) {
+    let Some(program) = program.0.id() else { return; };
+    let mut inner = self.inner.borrow_mut();
+    let Some(shaders) = inner.programs.get(&program).map(|p| p.shaders.clone()) else { return; };
+    let sources: Vec<String> = shaders.iter().filter_map(|s| inner.shaders.get(s).cloned()).collect();
+    let kind = Kind::from_sources(&sources.iter().map(String::as_str).collect::<Vec<_>>());
+    if let Some(p) = inner.programs.get_mut(&program) {
+      p.kind = kind;
+      p.info_log = match kind {
+        Some(_) => None,
+        None => Some("Program is not supported by the software context".to_string()),
+      };
+    }
+  }
+
+  fn get_program_link_status(&self, program: &Program) -> bool {
+    program.0.id()
+      .and_then(|id| self.inner.borrow().programs.get(&id).map(|p| p.kind.is_some()))
+      .unwrap_or(false)
+  }
+
+  fn get_program_info_log(&self, program: &Program) -> Option<String> {
+    program.0.id().and_then(|id| self.inner.borrow().programs.get(&id).and_then(|p| p.info_log.clone()))
+  }
+
+  fn use_program(&self, program: Option<&Program>) {
+    self.inner.borrow_mut().program = program.and_then(|p| p.0.id());
+  }
+
+  fn get_attrib_location(&self, program: &Program, name: &str) -> i32 {
+    program.0.id()
+      .and_then(|id| self.inner.borrow().programs.get(&id).and_then(|p| p.kind))
+      .and_then(|kind| kind.attributes().iter().position(|a| *a == name))
+      .map(|i| i as i32)
+      .unwrap_or(-1)
+  }
+
+  fn get_uniform_location(&self, program: &Program, name: &str) -> Option<UniformLocation> {
+    let program = program.0.id()?;
+    let mut inner = self.inner.borrow_mut();
+    let key = (program, name.to_string());
+    let location = match inner.uniform_locations.get(&key) {
+      Some(location) => *location,
+      None => {
+        let location = inner.allocate();
+        inner.uniform_locations.insert(key.clone(), location);
+        inner.uniform_names.insert(location, key);
+        location
+      },
+    };
+    Some(UniformLocation(Handle::Id(location)))
+  }
+
+  fn create_buffer(&self) -> Option<Buffer> {
+    let mut inner = self.inner.borrow_mut();
+    let buffer = inner.allocate();
+    inner.buffers.insert(buffer, Vec::new());
+    Some(Buffer(Handle::Id(buffer)))
+  }
+
+  fn bind_buffer(&self, target: u32, buffer: Option<&Buffer>) {
+    let mut inner = self.inner.borrow_mut();
+    let buffer = buffer.and_then(|b| b.0.id());
+    match target {
+      GL::ARRAY_BUFFER => inner.array_buffer = buffer,
+      GL::ELEMENT_ARRAY_BUFFER => {
+        let current = inner.vertex_array;
+        if let Some(vao) = inner.vertex_arrays.get_mut(&current) { vao.element_buffer = buffer; }
+      },
+      _ => {},
+    }
+  }
+
+  fn buffer_data_with_f32_array(&self, target: u32, data: &[f32], _usage: u32) {
+    self.inner.borrow_mut().buffer_data(target, data.iter().flat_map(|v| v.to_ne_bytes()).collect());
+  }
+
+  fn buffer_data_with_u16_array(&self, target: u32, data: &[u16], _usage: u32) {
+    self.inner.borrow_mut().buffer_data(target, data.iter().flat_map(|v| v.to_ne_bytes()).collect());
+  }
+
+  fn buffer_data_with_u32_array(&self, target: u32, data: &[u32], _usage: u32) {
+    self.inner.borrow_mut().buffer_data(target, data.iter().flat_map(|v| v.to_ne_bytes()).collect());
+  }
+
+  fn delete_buffer(&self, buffer: Option<&Buffer>) {
+    if let Some(buffer) = buffer.and_then(|b| b.0.id()) { self.inner.borrow_mut().buffers.remove(&buffer); }
+  }
+
+  fn create_vertex_array(&self) -> Option<VertexArray> {
+    let mut inner = self.inner.borrow_mut();
+    let vertex_array = inner.allocate();
+    inner.vertex_arrays.insert(vertex_array, VertexArrayObject::default());
+    Some(VertexArray(Handle::Id(vertex_array)))
+  }
+
+  fn bind_vertex_array(&self, vertex_array: Option<&VertexArray>) {
+    self.inner.borrow_mut().vertex_array = vertex_array.and_then(|v| v.0.id()).unwrap_or(0);
+  }
+
+  fn delete_vertex_array(&self, vertex_array: Option<&VertexArray>) {
+    let Some(vertex_array) = vertex_array.and_then(|v| v.0.id()) else { return; };
+    let mut inner = self.inner.borrow_mut();
+    inner.vertex_arrays.remove(&vertex_array);
+    if inner.vertex_array == vertex_array { inner.vertex_array = 0; }
+  }
+
+  fn enable_vertex_attrib_array(&self, index: u32) {
+    let mut inner = self.inner.borrow_mut();
+    let current = inner.vertex_array;
+    if let Some(vao) = inner.vertex_arrays.get_mut(&current) { vao.enabled.insert(index); }
+  }
+
+  fn vertex_attrib_pointer_with_i32(&self, index: u32, size: i32, data_type: u32, _normalized: bool, stride: i32, offset: i32) {
+    if data_type != GL::FLOAT { return; }
+    let mut inner = self.inner.borrow_mut();
+    let Some(buffer) = inner.array_buffer else { return; };
+    let current = inner.vertex_array;
+    if let Some(vao) = inner.vertex_arrays.get_mut(&current) {
+      vao.pointers.insert(index, Pointer { buffer, size, stride, offset });
+    }
+  }
+
+  fn create_texture(&self) -> Option<Texture> {
+    let mut inner = self.inner.borrow_mut();
+    let texture = inner.allocate();
+    inner.textures.insert(texture, Rc::default());
+    Some(Texture(Handle::Id(texture)))
+  }
+
+  fn bind_texture(&self, target: u32, texture: Option<&Texture>) {
+    if target != GL::TEXTURE_2D { return; }
+    let mut inner = self.inner.borrow_mut();
+    let unit = inner.active_texture;
+    match texture.and_then(|t| t.0.id()) {
+      Some(texture) => inner.texture_units.insert(unit, texture),
+      None => inner.texture_units.remove(&unit),
+    };
+  }
+
+  fn delete_texture(&self, texture: Option<&Texture>) {
+    let Some(texture) = texture.and_then(|t| t.0.id()) else { return; };
+    let mut inner = self.inner.borrow_mut();
+    inner.textures.remove(&texture);
+    inner.texture_units.retain(|_, t| *t != texture);
+  }
+
+  fn active_texture(&self, unit: u32) {
+    self.inner.borrow_mut().active_texture = unit.saturating_sub(GL::TEXTURE0);
+  }
+
+  fn pixel_storei(&self, pname: u32, param: i32) {
+    if pname == GL::UNPACK_ALIGNMENT { self.inner.borrow_mut().unpack_alignment = param; }
+  }
+
+  fn tex_parameteri(&self, target: u32, pname: u32, param: i32) {
+    if target != GL::TEXTURE_2D { return; }
+    let mut inner = self.inner.borrow_mut();
+    let Some(texture) = inner.texture_units.get(&inner.active_texture).copied() else { return; };
+    if let Some(texture) = inner.textures.get_mut(&texture) { Rc::make_mut(texture).parameters.insert(pname, param); }
+  }
+
+  fn tex_image_2d_with_u8_array(
+    &self, target: u32, level: i32, _internal_format: i32, width: i32, height: i32,
+    format: u32, data_type: u32, data: Option<&[u8]>,
+  ) -> Result<()> {
+    if target != GL::TEXTURE_2D || level != 0 { return Ok(()); }
+    let Some(channels) = channels(format) else { return Ok(()); };
+    let mut inner = self.inner.borrow_mut();
+    let alignment = inner.unpack_alignment.max(1) as usize;
+    let normalized = data_type == GL::UNSIGNED_BYTE;
+    match data {
+      // Allocation without pixels, as for framebuffer attachments
+      None => inner.tex_image(width, height, channels, alignment, normalized, |_| None),
+      Some(data) if normalized => {
+        inner.tex_image(width, height, channels, alignment, normalized, |i| data.get(i).map(|v| *v as f32 / 255.0));
+      },
+      Some(_) => {},
+    }
+    Ok(())
+  }
+
+  fn tex_image_2d_with_f32_array(
+    &self, target: u32, level: i32, _internal_format: i32, width: i32, height: i32,
+    format: u32, data: &[f32],
+  ) -> Result<()> {
+    if target != GL::TEXTURE_2D || level != 0 { return Ok(()); }
+    let Some(channels) = channels(format) else { return Ok(()); };
+    self.inner.borrow_mut().tex_image(width, height, channels, 1, false, |i| data.get(i).copied());
+    Ok(())
+  }
+
+  fn tex_image_2d_with_html_image_element(
+    &self, _target: u32, _level: i32, _internal_format: i32, _format: u32, _data_type: u32,
+    _source: &web_sys::HtmlImageElement,
+  ) -> Result<()> {
+    Err(Error::UnsupportedOperation("HtmlImageElement upload in the software context".to_string()))
+  }
+
+  fn tex_image_2d_with_image_bitmap(
+    &self, _target: u32, _level: i32, _internal_format: i32, _format: u32, _data_type: u32,
+    _source: &web_sys::ImageBitmap,
+  ) -> Result<()> {
+    Err(Error::UnsupportedOperation("ImageBitmap upload in the software context".to_string()))
+  }
+
+  fn generate_mipmap(&self, target: u32) {
+    if target != GL::TEXTURE_2D { return; }
+    let mut inner = self.inner.borrow_mut();
+    let Some(texture) = inner.texture_units.get(&inner.active_texture).copied() else { return; };
+    if let Some(texture) = inner.textures.get_mut(&texture) { Rc::make_mut(texture).mipmaps = true; }
+  }
+
+  fn create_framebuffer(&self) -> Option<Framebuffer> {
+    let mut inner = self.inner.borrow_mut();
+    let framebuffer = inner.allocate();
+    inner.framebuffers.insert(framebuffer, HashMap::new());
+    Some(Framebuffer(Handle::Id(framebuffer)))
+  }
+
+  fn bind_framebuffer(&self, target: u32, framebuffer: Option<&Framebuffer>) {
+    let framebuffer = framebuffer.and_then(|f| f.0.id());
+    let mut inner = self.inner.borrow_mut();
+    if target == GL::FRAMEBUFFER || target == GL::DRAW_FRAMEBUFFER { inner.framebuffer = framebuffer; }
+    if target == GL::FRAMEBUFFER || target == GL::READ_FRAMEBUFFER { inner.read_framebuffer = framebuffer; }
+  }
+
+  fn delete_framebuffer(&self, framebuffer: Option<&Framebuffer>) {
+    let Some(framebuffer) = framebuffer.and_then(|f| f.0.id()) else { return; };
+    let mut inner = self.inner.borrow_mut();
+    inner.framebuffers.remove(&framebuffer);
+    if inner.framebuffer == Some(framebuffer) { inner.framebuffer = None; }
+    if inner.read_framebuffer == Some(framebuffer) { inner.read_framebuffer = None; }
+  }
+
+  fn framebuffer_texture_2d(&self, target: u32, attachment: u32, textarget: u32, texture: Option<&Texture>, level: i32) {
+    if textarget != GL::TEXTURE_2D || level != 0 { return; }
+    let mut inner = self.inner.borrow_mut();
+    let framebuffer = if target == GL::READ_FRAMEBUFFER { inner.read_framebuffer } else { inner.framebuffer };
+    let Some(attachments) = framebuffer.and_then(|f| inner.framebuffers.get_mut(&f)) else { return; };
+    match texture.and_then(|t| t.0.id()) {
+      Some(texture) => attachments.insert(attachment, texture),
+      None => attachments.remove(&attachment),
+    };
+  }
+
+  fn check_framebuffer_status(&self, target: u32) -> u32 {
+    let inner = self.inner.borrow();
+    match if target == GL::READ_FRAMEBUFFER { inner.read_framebuffer } else { inner.framebuffer } {
+      None => GL::FRAMEBUFFER_COMPLETE,
+      Some(framebuffer) if inner.attachments(framebuffer).is_some() => GL::FRAMEBUFFER_COMPLETE,
+      Some(framebuffer) if inner.framebuffers.get(&framebuffer).is_some_and(|a| a.is_empty()) => GL::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT,
+      Some(_) => GL::FRAMEBUFFER_INCOMPLETE_DIMENSIONS,
+    }
+  }
+
+  /// Integer values are stored as `f32`, exact up to 2<sup>24</sup>
+  fn read_pixels_with_u32_array(
+    &self, x: i32, y: i32, width: i32, height: i32, format: u32, data_type: u32, data: &mut [u32],
+  ) -> Result<()> {
+    let Some(channels) = channels(format).filter(|_| data_type == GL::UNSIGNED_INT) else { return Ok(()); };
+    let mut inner = self.inner.borrow_mut();
+    let read = inner.read_framebuffer;
+    let Some(source) = inner.take_target(read) else { return Ok(()); };
+    if let Some(color) = source.color.as_ref() {
+      let pixels = (y..y + height).flat_map(|y| (x..x + width).map(move |x| (x, y)));
+      for ((x, y), values) in pixels.zip(data.chunks_exact_mut(channels)) {
+        if x < 0 || y < 0 || x >= source.width || y >= source.height { continue; }
+        let texel = color[(y * source.width + x) as usize];
+        for (k, value) in values.iter_mut().enumerate() { *value = texel[k] as u32; }
+      }
+    }
+    inner.restore_target(read, source);
+    Ok(())
+  }
+
+  /// Nearest texel copy, whatever the filter: multisampled renderbuffers hold a single sample
+  fn blit_framebuffer(
+    &self, src_x0: i32, src_y0: i32, src_x1: i32, src_y1: i32,
+    dst_x0: i32, dst_y0: i32, dst_x1: i32, dst_y1: i32, mask: u32, _filter: u32,
+  ) {
+    let mut inner = self.inner.borrow_mut();
+    let (read, draw) = (inner.read_framebuffer, inner.framebuffer);
+    if read == draw { return; }
+    let Some(source) = inner.take_target(read) else { return; };
+    let Some(mut target) = inner.take_target(draw) else {
+      inner.restore_target(read, source);
+      return;
+    };
+    let map = |d: i32, d0: i32, d1: i32, s0: i32, s1: i32| -> i32 {
+      (s0 as f32 + (d as f32 + 0.5 - d0 as f32) / (d1 - d0) as f32 * (s1 - s0) as f32).floor() as i32
+    };
+    for y in dst_y0.min(dst_y1).max(0)..dst_y0.max(dst_y1).min(target.height) {
+      for x in dst_x0.min(dst_x1).max(0)..dst_x0.max(dst_x1).min(target.width) {
+        let (sx, sy) = (map(x, dst_x0, dst_x1, src_x0, src_x1), map(y, dst_y0, dst_y1, src_y0, src_y1));
+        if sx < 0 || sy < 0 || sx >= source.width || sy >= source.height { continue; }
+        let (from, to) = ((sy * source.width + sx) as usize, (y * target.width + x) as usize);
+        let color = source.color.as_ref().filter(|_| mask & GL::COLOR_BUFFER_BIT != 0).and_then(|c| c.get(from));
+        if let Some(color) = color { target.write_color(to, *color); }
+        let depth = source.depth.as_ref().filter(|_| mask & GL::DEPTH_BUFFER_BIT != 0).and_then(|d| d.get(from));
+        if let (Some(from), Some(to)) = (depth, target.depth.as_mut().and_then(|d| d.get_mut(to))) {
+          *to = *from;
+        }
+      }
+    }
+    inner.restore_target(read, source);
+    inner.restore_target(draw, target);
+  }
+
+  fn create_renderbuffer(&self) -> Option<Renderbuffer> {
+    let mut inner = self.inner.borrow_mut();
+    let renderbuffer = inner.allocate();
+    inner.textures.insert(renderbuffer, Rc::default());
+    Some(Renderbuffer(Handle::Id(renderbuffer)))
+  }
+
+  fn bind_renderbuffer(&self, target: u32, renderbuffer: Option<&Renderbuffer>) {
+    if target == GL::RENDERBUFFER { self.inner.borrow_mut().renderbuffer = renderbuffer.and_then(|r| r.0.id()); }
+  }
+
+  fn delete_renderbuffer(&self, renderbuffer: Option<&Renderbuffer>) {
+    let Some(renderbuffer) = renderbuffer.and_then(|r| r.0.id()) else { return; };
+    let mut inner = self.inner.borrow_mut();
+    inner.textures.remove(&renderbuffer);
+    if inner.renderbuffer == Some(renderbuffer) { inner.renderbuffer = None; }
+  }
+
+  fn renderbuffer_storage_multisample(&self, target: u32, _samples: i32, internal_format: u32, width: i32, height: i32) {
+    if target != GL::RENDERBUFFER { return; }
+    let mut inner = self.inner.borrow_mut();
+    let Some(renderbuffer) = inner.renderbuffer.and_then(|r| inner.textures.get_mut(&r)) else { return; };
+    let renderbuffer = Rc::make_mut(renderbuffer);
+    (renderbuffer.width, renderbuffer.height) = (width.max(0), height.max(0));
+    renderbuffer.texels = vec![[0.0, 0.0, 0.0, 0.0]; (width.max(0) * height.max(0)) as usize];
+    renderbuffer.normalized = ![GL::RGBA16F, GL::RGBA32F, GL::R32F, GL::R32UI].contains(&internal_format);
+  }
+
+  fn framebuffer_renderbuffer(&self, target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: Option<&Renderbuffer>) {
+    if renderbuffer_target != GL::RENDERBUFFER { return; }
+    let mut inner = self.inner.borrow_mut();
+    let framebuffer = if target == GL::READ_FRAMEBUFFER { inner.read_framebuffer } else { inner.framebuffer };
+    let Some(attachments) = framebuffer.and_then(|f| inner.framebuffers.get_mut(&f)) else { return; };
+    match renderbuffer.and_then(|r| r.0.id()) {
+      Some(renderbuffer) => attachments.insert(attachment, renderbuffer),
+      None => attachments.remove(&attachment),
+    };
+  }
+
+  fn uniform1i(&self, location: Option<&UniformLocation>, x: i32) {
+    self.set_uniform(location, UniformValue::Int(x));
+  }
+
+  fn uniform1f(&self, location: Option<&UniformLocation>, x: f32) {
+    self.set_uniform(location, UniformValue::Float(x));
+  }
+
+  fn uniform4f(&self, location: Option<&UniformLocation>, x: f32, y: f32, z: f32, w: f32) {
+    self.set_uniform(location, UniformValue::Vec4([x, y, z, w]));
+  }
+
+  fn uniform3fv_with_f32_array(&self, location: Option<&UniformLocation>, data: &[f32]) {
+    if let Ok(value) = data.try_into() { self.set_uniform(location, UniformValue::Vec3(value)); }
+  }
+
+  fn uniform_matrix4fv_with_f32_array(&self, location: Option<&UniformLocation>, _transpose: bool, data: &[f32]) {
+    if let Ok(value) = data.try_into() { self.set_uniform(location, UniformValue::Mat4(value)); }
+  }
+
+  fn draw_arrays(&self, mode: u32, first: i32, count: i32) {
+    if mode == GL::TRIANGLES {
+      self.inner.borrow_mut().draw_triangles(&(first..first + count).collect::<Vec<_>>());
+    }
+  }
+
+  fn draw_elements_with_i32(&self, mode: u32, count: i32, data_type: u32, offset: i32) {
+    if mode == GL::TRIANGLES {
+      let mut inner = self.inner.borrow_mut();
+      let indices = inner.indices(count, data_type, offset);
+      inner.draw_triangles(&indices);
+    }
+  }
+}
+
+impl SoftwareContext {
+  fn set_uniform(&self, location: Option<&UniformLocation>, value: UniformValue) {
+    let mut inner = self.inner.borrow_mut();
+    let Some((program, name)) = location
+      .and_then(|l| l.0.id())
+      .and_then(|id| inner.uniform_names.get(&id).cloned()) else { return; };
+    if let Some(p) = inner.programs.get_mut(&program) { p.uniforms.insert(name, value); }
+  }
+}
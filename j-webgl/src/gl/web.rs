@@ -0,0 +1,315 @@
+use super::*;
+
+use wasm_bindgen::JsCast;
+use web_sys::WebGl2RenderingContext;
+
+fn handle<T: Into<wasm_bindgen::JsValue>>(value: T) -> Handle {
+  Handle::Js(value.into())
+}
+
+/// Recover the WebGL object behind a handle. Handles allocated by another
+/// context type resolve to `None`, which WebGL treats as unbinding.
+fn js<T: JsCast>(handle: &Handle) -> Option<&T> {
+  match handle {
+    Handle::Js(value) => Some(value.unchecked_ref::<T>()),
+    Handle::Id(_) => None,
+  }
+}
+
+impl GlContext for WebGl2RenderingContext {
+  fn drawing_buffer_width(&self) -> i32 { WebGl2RenderingContext::drawing_buffer_width(self) }
+  fn drawing_buffer_height(&self) -> i32 { WebGl2RenderingContext::drawing_buffer_height(self) }
+
+  fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+    WebGl2RenderingContext::viewport(self, x, y, width, height);
+  }
+
+  fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
+    WebGl2RenderingContext::clear_color(self, red, green, blue, alpha);
+  }
+
+  fn clear(&self, mask: u32) { WebGl2RenderingContext::clear(self, mask); }
+
+  fn clear_bufferuiv_with_u32_array(&self, buffer: u32, drawbuffer: i32, values: &[u32]) {
+    WebGl2RenderingContext::clear_bufferuiv_with_u32_array(self, buffer, drawbuffer, values);
+  }
+
+  fn enable(&self, capability: u32) { WebGl2RenderingContext::enable(self, capability); }
+  fn disable(&self, capability: u32) { WebGl2RenderingContext::disable(self, capability); }
+
+  fn get_extension(&self, name: &str) -> bool {
+    matches!(WebGl2RenderingContext::get_extension(self, name), Ok(Some(_)))
+  }
+
+  fn create_shader(&self, shader_type: u32) -> Option<Shader> {
+    WebGl2RenderingContext::create_shader(self, shader_type).map(|s| Shader(handle(s)))
+  }
+
+  fn shader_source(&self, shader: &Shader, source: &str) {
+    if let Some(shader) = js(&shader.0) {
+      WebGl2RenderingContext::shader_source(self, shader, source);
+    }
+  }
+
+  fn compile_shader(&self, shader: &Shader) {
+    if let Some(shader) = js(&shader.0) {
+      WebGl2RenderingContext::compile_shader(self, shader);
+    }
+  }
+
+  fn get_shader_compile_status(&self, shader: &Shader) -> bool {
+    js(&shader.0)
+      .and_then(|shader| {
+        WebGl2RenderingContext::get_shader_parameter(self, shader, WebGl2RenderingContext::COMPILE_STATUS).as_bool()
+      })
+      .unwrap_or(false)
+  }
+
+  fn get_shader_info_log(&self, shader: &Shader) -> Option<String> {
+    js(&shader.0).and_then(|shader| WebGl2RenderingContext::get_shader_info_log(self, shader))
+  }
+
+  fn create_program(&self) -> Option<Program> {
+    WebGl2RenderingContext::create_program(self).map(|p| Program(handle(p)))
+  }
+
+  fn attach_shader(&self, program: &Program, shader: &Shader) {
+    if let (Some(program), Some(shader)) = (js(&program.0), js(&shader.0)) {
+      WebGl2RenderingContext::attach_shader(self, program, shader);
+    }
+  }
+
+  fn link_program(&self, program: &Program) {
+    if let Some(program) = js(&program.0) {
+      WebGl2RenderingContext::link_program(self, program);
+    }
+  }
+
+  fn get_program_link_status(&self, program: &Program) -> bool {
+    js(&program.0)
+      .and_then(|program| {
+        WebGl2RenderingContext::get_program_parameter(self, program, WebGl2RenderingContext::LINK_STATUS).as_bool()
+      })
+      .unwrap_or(false)
+  }
+
+  fn get_program_info_log(&self, program: &Program) -> Option<String> {
+    js(&program.0).and_then(|program| WebGl2RenderingContext::get_program_info_log(self, program))
+  }
+
+  fn use_program(&self, program: Option<&Program>) {
+    WebGl2RenderingContext::use_program(self, program.and_then(|p| js(&p.0)));
+  }
+
+  fn get_attrib_location(&self, program: &Program, name: &str) -> i32 {
+    js(&program.0)
+      .map(|program| WebGl2RenderingContext::get_attrib_location(self, program, name))
+      .unwrap_or(-1)
+  }
+
+  fn get_uniform_location(&self, program: &Program, name: &str) -> Option<UniformLocation> {
+    js(&program.0)
+      .and_then(|program| WebGl2RenderingContext::get_uniform_location(self, program, name))
+      .map(|l| UniformLocation(handle(l)))
+  }
+
+  fn create_buffer(&self) -> Option<Buffer> {
+    WebGl2RenderingContext::create_buffer(self).map(|b| Buffer(handle(b)))
+  }
+
+  fn bind_buffer(&self, target: u32, buffer: Option<&Buffer>) {
+    WebGl2RenderingContext::bind_buffer(self, target, buffer.and_then(|b| js(&b.0)));
+  }
+
+  fn buffer_data_with_f32_array(&self, target: u32, data: &[f32], usage: u32) {
+    unsafe {
+      let array = js_sys::Float32Array::view(data);
+      WebGl2RenderingContext::buffer_data_with_array_buffer_view(self, target, &array, usage);
+    }
+  }
+
+  fn buffer_data_with_u16_array(&self, target: u32, data: &[u16], usage: u32) {
+    unsafe {
+      let array = js_sys::Uint16Array::view(data);
+      WebGl2RenderingContext::buffer_data_with_array_buffer_view(self, target, &array, usage);
+    }
+  }
+
+  fn buffer_data_with_u32_array(&self, target: u32, data: &[u32], usage: u32) {
+    unsafe {
+      let array = js_sys::Uint32Array::view(data);
+      WebGl2RenderingContext::buffer_data_with_array_buffer_view(self, target, &array, usage);
+    }
+  }
+
+  fn delete_buffer(&self, buffer: Option<&Buffer>) {
+    WebGl2RenderingContext::delete_buffer(self, buffer.and_then(|b| js(&b.0)));
+  }
+
+  fn create_vertex_array(&self) -> Option<VertexArray> {
+    WebGl2RenderingContext::create_vertex_array(self).map(|v| VertexArray(handle(v)))
+  }
+
+  fn bind_vertex_array(&self, vertex_array: Option<&VertexArray>) {
+    WebGl2RenderingContext::bind_vertex_array(self, vertex_array.and_then(|v| js(&v.0)));
+  }
+
+  fn delete_vertex_array(&self, vertex_array: Option<&VertexArray>) {
+    WebGl2RenderingContext::delete_vertex_array(self, vertex_array.and_then(|v| js(&v.0)));
+  }
+
+  fn enable_vertex_attrib_array(&self, index: u32) {
+    WebGl2RenderingContext::enable_vertex_attrib_array(self, index);
+  }
+
+  fn vertex_attrib_pointer_with_i32(&self, index: u32, size: i32, data_type: u32, normalized: bool, stride: i32, offset: i32) {
+    WebGl2RenderingContext::vertex_attrib_pointer_with_i32(self, index, size, data_type, normalized, stride, offset);
+  }
+
+  fn create_texture(&self) -> Option<Texture> {
+    WebGl2RenderingContext::create_texture(self).map(|t| Texture(handle(t)))
+  }
+
+  fn bind_texture(&self, target: u32, texture: Option<&Texture>) {
+    WebGl2RenderingContext::bind_texture(self, target, texture.and_then(|t| js(&t.0)));
+  }
+
+  fn delete_texture(&self, texture: Option<&Texture>) {
+    WebGl2RenderingContext::delete_texture(self, texture.and_then(|t| js(&t.0)));
+  }
+
+  fn active_texture(&self, unit: u32) { WebGl2RenderingContext::active_texture(self, unit); }
+
+  fn create_framebuffer(&self) -> Option<Framebuffer> {
+    WebGl2RenderingContext::create_framebuffer(self).map(|f| Framebuffer(handle(f)))
+  }
+
+  fn bind_framebuffer(&self, target: u32, framebuffer: Option<&Framebuffer>) {
+    WebGl2RenderingContext::bind_framebuffer(self, target, framebuffer.and_then(|f| js(&f.0)));
+  }
+
+  fn delete_framebuffer(&self, framebuffer: Option<&Framebuffer>) {
+    WebGl2RenderingContext::delete_framebuffer(self, framebuffer.and_then(|f| js(&f.0)));
+  }
+
+  fn framebuffer_texture_2d(&self, target: u32, attachment: u32, textarget: u32, texture: Option<&Texture>, level: i32) {
+    WebGl2RenderingContext::framebuffer_texture_2d(self, target, attachment, textarget, texture.and_then(|t| js(&t.0)), level);
+  }
+
+  fn check_framebuffer_status(&self, target: u32) -> u32 {
+    WebGl2RenderingContext::check_framebuffer_status(self, target)
+  }
+
+  fn read_pixels_with_u32_array(
+    &self, x: i32, y: i32, width: i32, height: i32, format: u32, data_type: u32, data: &mut [u32],
+  ) -> Result<()> {
+    let array = js_sys::Uint32Array::new_with_length(data.len().try_into()?);
+    WebGl2RenderingContext::read_pixels_with_opt_array_buffer_view(self, x, y, width, height, format, data_type, Some(&array))?;
+    array.copy_to(data);
+    Ok(())
+  }
+
+  fn blit_framebuffer(
+    &self, src_x0: i32, src_y0: i32, src_x1: i32, src_y1: i32,
+    dst_x0: i32, dst_y0: i32, dst_x1: i32, dst_y1: i32, mask: u32, filter: u32,
+  ) {
+    WebGl2RenderingContext::blit_framebuffer(self, src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1, dst_y1, mask, filter);
+  }
+
+  fn create_renderbuffer(&self) -> Option<Renderbuffer> {
+    WebGl2RenderingContext::create_renderbuffer(self).map(|r| Renderbuffer(handle(r)))
+  }
+
+  fn bind_renderbuffer(&self, target: u32, renderbuffer: Option<&Renderbuffer>) {
+    WebGl2RenderingContext::bind_renderbuffer(self, target, renderbuffer.and_then(|r| js(&r.0)));
+  }
+
+  fn delete_renderbuffer(&self, renderbuffer: Option<&Renderbuffer>) {
+    WebGl2RenderingContext::delete_renderbuffer(self, renderbuffer.and_then(|r| js(&r.0)));
+  }
+
+  fn renderbuffer_storage_multisample(&self, target: u32, samples: i32, internal_format: u32, width: i32, height: i32) {
+    WebGl2RenderingContext::renderbuffer_storage_multisample(self, target, samples, internal_format, width, height);
+  }
+
+  fn framebuffer_renderbuffer(&self, target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: Option<&Renderbuffer>) {
+    WebGl2RenderingContext::framebuffer_renderbuffer(self, target, attachment, renderbuffer_target, renderbuffer.and_then(|r| js(&r.0)));
+  }
+
+  fn pixel_storei(&self, pname: u32, param: i32) { WebGl2RenderingContext::pixel_storei(self, pname, param); }
+
+  fn tex_parameteri(&self, target: u32, pname: u32, param: i32) {
+    WebGl2RenderingContext::tex_parameteri(self, target, pname, param);
+  }
+
+  fn tex_image_2d_with_u8_array(
+    &self, target: u32, level: i32, internal_format: i32, width: i32, height: i32,
+    format: u32, data_type: u32, data: Option<&[u8]>,
+  ) -> Result<()> {
+    WebGl2RenderingContext::tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+      self, target, level, internal_format, width, height, 0, format, data_type, data,
+    )?;
+    Ok(())
+  }
+
+  fn tex_image_2d_with_f32_array(
+    &self, target: u32, level: i32, internal_format: i32, width: i32, height: i32,
+    format: u32, data: &[f32],
+  ) -> Result<()> {
+    let array = js_sys::Float32Array::from(data);
+    WebGl2RenderingContext::tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+      self, target, level, internal_format, width, height, 0, format, WebGl2RenderingContext::FLOAT, Some(&array),
+    )?;
+    Ok(())
+  }
+
+  fn tex_image_2d_with_html_image_element(
+    &self, target: u32, level: i32, internal_format: i32, format: u32, data_type: u32,
+    source: &web_sys::HtmlImageElement,
+  ) -> Result<()> {
+    WebGl2RenderingContext::tex_image_2d_with_u32_and_u32_and_html_image_element(
+      self, target, level, internal_format, format, data_type, source,
+    )?;
+    Ok(())
+  }
+
+  fn tex_image_2d_with_image_bitmap(
+    &self, target: u32, level: i32, internal_format: i32, format: u32, data_type: u32,
+    source: &web_sys::ImageBitmap,
+  ) -> Result<()> {
+    WebGl2RenderingContext::tex_image_2d_with_u32_and_u32_and_image_bitmap(
+      self, target, level, internal_format, format, data_type, source,
+    )?;
+    Ok(())
+  }
+
+  fn generate_mipmap(&self, target: u32) { WebGl2RenderingContext::generate_mipmap(self, target); }
+
+  fn uniform1i(&self, location: Option<&UniformLocation>, x: i32) {
+    WebGl2RenderingContext::uniform1i(self, location.and_then(|l| js(&l.0)), x);
+  }
+
+  fn uniform1f(&self, location: Option<&UniformLocation>, x: f32) {
+    WebGl2RenderingContext::uniform1f(self, location.and_then(|l| js(&l.0)), x);
+  }
+
+  fn uniform4f(&self, location: Option<&UniformLocation>, x: f32, y: f32, z: f32, w: f32) {
+    WebGl2RenderingContext::uniform4f(self, location.and_then(|l| js(&l.0)), x, y, z, w);
+  }
+
+  fn uniform3fv_with_f32_array(&self, location: Option<&UniformLocation>, data: &[f32]) {
+    WebGl2RenderingContext::uniform3fv_with_f32_array(self, location.and_then(|l| js(&l.0)), data);
+  }
+
+  fn uniform_matrix4fv_with_f32_array(&self, location: Option<&UniformLocation>, transpose: bool, data: &[f32]) {
+    WebGl2RenderingContext::uniform_matrix4fv_with_f32_array(self, location.and_then(|l| js(&l.0)), transpose, data);
+  }
+
+  fn draw_arrays(&self, mode: u32, first: i32, count: i32) {
+    WebGl2RenderingContext::draw_arrays(self, mode, first, count);
+  }
+
+  fn draw_elements_with_i32(&self, mode: u32, count: i32, data_type: u32, offset: i32) {
+    WebGl2RenderingContext::draw_elements_with_i32(self, mode, count, data_type, offset);
+  }
+}
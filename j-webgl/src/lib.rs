@@ -15,7 +15,8 @@
 //! - **Traits**: `Renderable` and `Camera` traits for flexible rendering architecture
 //! - **Shader Utilities**: Helper functions for compiling and linking shader programs
 //! - **Reusable Components**: Pre-built shaders and structs for common use cases
-//! - **Algebra Types**: 3D math types (`Point3`, `Vector3`, `Matrix4`) for graphics operations
+//! - **Algebra Types**: 3D math types (`Point3`, `Vector3`, `Matrix4`, `Quaternion`) for graphics operations,
+//!   and ray casting against boxes, spheres, planes and triangle meshes
 //! - **Macro Support**: `make_renderer!` macro for generating specialized renderer classes
 //!
 //! ## Quick Start
@@ -28,7 +29,9 @@
 //!
 //! - **`Renderable`**: Implement this trait for any object that can be rendered
 //! - **`Camera`**: Implement this trait for camera/view matrices
+//! - **`FrameContext`**: Camera matrices, eye position, viewport and lights handed to each `Renderable`
 //! - **`Renderer`**: Manages the WebGL context and orchestrates rendering of `Renderable` objects
+//! - **`scene::Scene`**: Hierarchy of transformed nodes, rendered as a single `Renderable`
 //!
 //! ## Examples
 //!
@@ -38,11 +41,28 @@
 /// Macro to generate specialist renderer class
 ///
 /// This macro helps create specialized renderer implementations with custom
-/// rendering logic. See the `j-webgl-macro-make-renderer` crate for details.p
+/// rendering logic. See the `j-webgl-macro-make-renderer` crate for details.
 pub use j_webgl_macro_make_renderer::make_renderer;
 
 mod error;
-mod algebra;
+
+/// 3D math types for graphics operations
+///
+/// This module provides [`Point3`](algebra::Point3), [`Vector3`](algebra::Vector3),
+/// the column-major [`Matrix4`](algebra::Matrix4) and [`Quaternion`](algebra::Quaternion)
+/// used to build camera and model transforms, and the [`Ray`](algebra::Ray)
+/// intersection tests with boxes, spheres, planes, triangles and the
+/// [`Bvh`](algebra::Bvh) of a mesh used to pick and measure on the CPU, and
+/// the view [`Frustum`](algebra::Frustum) used to cull objects by their bounding volumes.
+pub mod algebra;
+
+/// Abstraction over the WebGL 2.0 calls made by the library
+///
+/// The [`GlContext`](gl::GlContext) trait is implemented for
+/// `web_sys::WebGl2RenderingContext` and for two in-memory contexts so that
+/// rendering code can be tested without a browser: [`RecordingContext`](gl::RecordingContext)
+/// logs calls and [`SoftwareContext`](gl::SoftwareContext) rasterizes on the CPU.
+pub mod gl;
 
 /// Library of utility functions for WebGL operations
 ///
@@ -58,14 +78,84 @@ pub mod shaders;
 
 pub mod programs;
 
+/// Indexed triangle meshes
+///
+/// A [`Mesh`](mesh::Mesh) holds vertex attributes and triangle indices in CPU
+/// memory, and [`MeshBuffers`](mesh::MeshBuffers) uploads it to vertex and
+/// element array buffers to draw it with `draw_elements`.
+pub mod mesh;
+
+/// Procedural meshes for common primitives
+///
+/// Generators return a [`Mesh`](mesh::Mesh) with unit normals, texture
+/// coordinates and counter-clockwise front faces, which
+/// [`ShadedMesh`](structs::renderable::ShadedMesh) renders directly.
+pub mod geometry;
+
+/// Textures sampled by shader programs
+///
+/// A [`Texture2D`](texture::Texture2D) uploads pixels from byte or float
+/// slices, from an [`Image`](texture::Image) decoded from PNG, or from a
+/// browser image, and applies the wrap and filter settings of a
+/// [`Sampler`](texture::Sampler).
+pub mod texture;
+
 mod traits;
 pub use traits::{Renderable, Camera};
 
+mod frame_context;
+pub use frame_context::FrameContext;
+
 pub use error::{Error, Result};
 
 mod renderer;
 pub use renderer::{Renderer};
 
+/// Offscreen rendering
+///
+/// A [`RenderTarget`](target::RenderTarget) wraps a framebuffer with color
+/// and depth textures, optionally drawn to through multisampled
+/// renderbuffers. The [`Renderer`] renders into targets with
+/// [`render_into`](Renderer::render_into), and resizes the targets registered
+/// with [`with_render_target`](Renderer::with_render_target) with the canvas.
+pub mod target;
+
+/// Post-processing of rendered images
+///
+/// A [`Chain`](post::Chain) runs full-screen [`Effect`](post::Effect)s,
+/// such as [`Fxaa`](post::Fxaa), [`ToneMapping`](post::ToneMapping) or
+/// [`Bloom`](post::Bloom), reading and writing named textures. Effects added
+/// with [`Renderer::with_effect`] apply to the scene rendered by the [`Renderer`].
+pub mod post;
+
+/// Shadow mapping for directional lights
+///
+/// The [`Renderer`] renders the depth of the scene as seen from a light into
+/// a [`ShadowMap`](shadow::ShadowMap) with [`Renderable::render_depth`], then
+/// hands the map to lit renderables through [`FrameContext::shadow`]. See
+/// [`ShadowSettings`](shadow::ShadowSettings).
+pub mod shadow;
+
+/// Scene graph of nodes with hierarchical transforms
+///
+/// A [`Scene`](scene::Scene) holds nodes with a local [`Transform`](scene::Transform),
+/// children, a visibility flag and attached renderables. World transforms are
+/// propagated from parents to children without any GPU access, and the scene
+/// renders as a single [`Renderable`].
+pub mod scene;
+
+/// Readers for 3D model file formats
+///
+/// Parsers work on byte slices, independently of how the file was fetched.
+/// [`parse_obj`](loaders::parse_obj) reads Wavefront OBJ geometry into
+/// [`Mesh`](mesh::Mesh)es and [`parse_mtl`](loaders::parse_mtl) reads the
+/// materials they refer to. [`parse_stl`](loaders::parse_stl) and
+/// [`write_stl`](loaders::write_stl) read and write ASCII or binary STL.
+/// [`parse_ply`](loaders::parse_ply) reads PLY meshes and point clouds.
+/// [`parse_gltf`](loaders::parse_gltf) reads glTF 2.0 scenes, which map onto
+/// a [`Scene`](scene::Scene) or onto renderables of a [`Renderer`].
+pub mod loaders;
+
 /// Re-usable struct implementing library traits
 ///
 /// This module contains concrete implementations of the library's traits,
@@ -0,0 +1,588 @@
+use super::*;
+
+use std::collections::HashMap;
+use base64::Engine;
+use serde::Deserialize;
+use mesh::{Mesh, Indices};
+
+/// First bytes of a binary glTF file, `glTF` read as a little endian `u32`
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+const GLB_BIN_CHUNK: u32 = 0x004E_4942;
+
+/// Model read from a glTF 2.0 file
+///
+/// Nodes, meshes and materials keep their glTF indices, so that
+/// [`GltfNode::children`], [`GltfNode::mesh`] and [`GltfPrimitive::material`]
+/// index into the vectors of the model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfModel {
+  pub nodes: Vec<GltfNode>,
+  /// Nodes of the default scene, or nodes without parent when the file has no scene
+  pub roots: Vec<usize>,
+  pub meshes: Vec<GltfMesh>,
+  pub materials: Vec<GltfMaterial>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfNode {
+  pub name: String,
+  /// Transform relative to the parent node
+  pub transform: scene::Transform,
+  pub mesh: Option<usize>,
+  pub children: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMesh {
+  pub name: String,
+  pub primitives: Vec<GltfPrimitive>,
+}
+
+/// Triangles of a mesh drawn with a single material
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfPrimitive {
+  pub mesh: Mesh,
+  pub material: Option<usize>,
+}
+
+/// Metallic-roughness material
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+  pub name: String,
+  /// Linear RGBA base color factor
+  pub base_color: [f32; 4],
+  pub metallic: f32,
+  pub roughness: f32,
+  /// Index of the glTF texture holding the base color, if any
+  pub base_color_texture: Option<usize>,
+}
+
+impl Default for GltfMaterial {
+  fn default() -> Self {
+    GltfMaterial { name: String::new(), base_color: [1.0; 4], metallic: 1.0, roughness: 1.0, base_color_texture: None }
+  }
+}
+
+impl GltfModel {
+  /// Build a scene with the node hierarchy of the model, each primitive
+  /// rendered in the base color of its material
+  pub fn to_scene(&self) -> Result<scene::Scene> {
+    let mut scene = scene::Scene::new();
+    let mut stack: Vec<(usize, Option<scene::NodeId>)> = self.roots.iter().rev().map(|i| (*i, None)).collect();
+    while let Some((index, parent)) = stack.pop() {
+      let node = &self.nodes[index];
+      let id = scene.add_node(parent, &node.name)?;
+      scene.set_transform(id, node.transform)?;
+      for renderable in self.renderables(node)? {
+        scene.add_renderable(id, renderable)?;
+      }
+      stack.extend(node.children.iter().rev().map(|child| (*child, Some(id))));
+    }
+    Ok(scene)
+  }
+
+  /// Register each primitive as a renderable of `renderer`, with the world
+  /// transform of its node, and return the renderable ids
+  ///
+  /// Ids are node names, followed by `/` and the primitive index for meshes
+  /// with several primitives, and by `#` and the node index when the name is
+  /// already taken.
+  pub fn add_to_renderer(&self, renderer: &mut Renderer) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    for (index, world) in self.world_transforms() {
+      let node = &self.nodes[index];
+      let renderables = self.renderables(node)?;
+      let count = renderables.len();
+      for (primitive, renderable) in renderables.into_iter().enumerate() {
+        let mut id = if count > 1 { format!("{}/{primitive}", node.name) } else { node.name.clone() };
+        if ids.contains(&id) { id = format!("{id}#{index}"); }
+        renderer.with_renderable(id.clone(), Some(renderable))?;
+        renderer.set_transform(&id, world)?;
+        ids.push(id);
+      }
+    }
+    Ok(ids)
+  }
+
+  /// World transform of each node reachable from the roots, in depth-first order
+  pub fn world_transforms(&self) -> Vec<(usize, algebra::Matrix4)> {
+    let mut transforms = Vec::new();
+    let mut stack: Vec<(usize, algebra::Matrix4)> = self.roots.iter().rev()
+      .map(|i| (*i, algebra::Matrix4::identity()))
+      .collect();
+    while let Some((index, parent)) = stack.pop() {
+      let world = &parent * &self.nodes[index].transform.matrix();
+      stack.extend(self.nodes[index].children.iter().rev().map(|child| (*child, world)));
+      transforms.push((index, world));
+    }
+    transforms
+  }
+
+  fn renderables(&self, node: &GltfNode) -> Result<Vec<structs::renderable::ShadedMesh>> {
+    let Some(mesh) = node.mesh.map(|i| &self.meshes[i]) else { return Ok(Vec::new()); };
+    Ok(mesh.primitives.iter().map(|primitive| {
+      let mut renderable = structs::renderable::ShadedMesh::new(primitive.mesh.clone());
+      let material = primitive.material.map(|i| &self.materials[i]).cloned().unwrap_or_default();
+      let [red, green, blue, alpha] = material.base_color;
+      renderable.set_color(red, green, blue, alpha);
+      renderable
+    }).collect())
+  }
+}
+
+/// Parse a glTF 2.0 file, either JSON `.gltf` or binary `.glb`
+///
+/// Buffers must be embedded, as base64 data URIs or as the binary chunk of a
+/// GLB file. Primitives are read with their indices and their `POSITION`,
+/// `NORMAL`, `TEXCOORD_0` and `COLOR_0` attributes; normals are computed for
+/// primitives without. Triangle strips and fans are converted to triangle
+/// lists.
+///
+/// # Errors
+///
+/// Returns [`Error::GltfParse`] for malformed files or references to missing
+/// elements, and [`Error::UnsupportedOperation`] for external buffers, sparse
+/// accessors and point or line primitives.
+pub fn parse_gltf(data: &[u8]) -> Result<GltfModel> {
+  let (json, binary) = match data.get(..4).map(|m| u32::from_le_bytes(m.try_into().unwrap_or_default())) {
+    Some(GLB_MAGIC) => split_glb(data)?,
+    _ => (data, None),
+  };
+  let document: Document = serde_json::from_slice(json).map_err(|e| Error::GltfParse(e.to_string()))?;
+  if !document.asset.version.starts_with("2.") {
+    return Err(Error::GltfParse(format!("unsupported version {}", document.asset.version)));
+  }
+  let reader = Reader::new(&document, binary)?;
+
+  let meshes = document.meshes.iter().enumerate().map(|(i, mesh)| {
+    let primitives = mesh.primitives.iter()
+      .map(|primitive| reader.primitive(primitive))
+      .collect::<Result<Vec<_>>>()?;
+    Ok(GltfMesh { name: mesh.name.clone().unwrap_or_else(|| format!("mesh{i}")), primitives })
+  }).collect::<Result<Vec<_>>>()?;
+
+  let materials = document.materials.iter().map(|material| {
+    let pbr = &material.pbr_metallic_roughness;
+    GltfMaterial {
+      name: material.name.clone().unwrap_or_default(),
+      base_color: pbr.base_color_factor,
+      metallic: pbr.metallic_factor,
+      roughness: pbr.roughness_factor,
+      base_color_texture: pbr.base_color_texture.as_ref().map(|t| t.index),
+    }
+  }).collect::<Vec<_>>();
+
+  let nodes = document.nodes.iter().enumerate().map(|(i, node)| {
+    let mut transform = match node.matrix {
+      Some(matrix) => scene::Transform::from_matrix(&algebra::Matrix4::from(matrix)),
+      None => scene::Transform::identity(),
+    };
+    if let Some([x, y, z]) = node.translation { transform = transform.with_translation(x, y, z); }
+    if let Some([x, y, z, w]) = node.rotation { transform = transform.with_rotation(algebra::Quaternion::new(x, y, z, w)); }
+    if let Some([x, y, z]) = node.scale { transform = transform.with_scale(x, y, z); }
+    GltfNode {
+      name: node.name.clone().unwrap_or_else(|| format!("node{i}")),
+      transform,
+      mesh: node.mesh,
+      children: node.children.clone(),
+    }
+  }).collect::<Vec<_>>();
+
+  let roots = match document.scenes.get(document.scene.unwrap_or(0)) {
+    Some(scene) => scene.nodes.clone(),
+    None => (0..nodes.len()).filter(|i| !nodes.iter().any(|n| n.children.contains(i))).collect(),
+  };
+  let model = GltfModel { nodes, roots, meshes, materials };
+  check_references(&model)?;
+  Ok(model)
+}
+
+/// Split a GLB file into its JSON chunk and optional binary chunk
+fn split_glb(data: &[u8]) -> Result<(&[u8], Option<&[u8]>)> {
+  let word = |offset: usize| -> Result<u32> {
+    let bytes = data.get(offset..offset + 4).ok_or_else(|| Error::GltfParse(format!("GLB truncated at byte {offset}")))?;
+    Ok(u32::from_le_bytes(bytes.try_into()?))
+  };
+  if word(4)? != 2 { return Err(Error::GltfParse(format!("unsupported GLB version {}", word(4)?))); }
+  let length = (word(8)? as usize).min(data.len());
+  let (mut json, mut binary) = (None, None);
+  let mut offset = 12;
+  while offset + 8 <= length {
+    let (chunk_length, chunk_type) = (word(offset)? as usize, word(offset + 4)?);
+    let exceeds = || Error::GltfParse(format!("GLB chunk at byte {offset} exceeds the file"));
+    let end = offset.checked_add(8).and_then(|start| start.checked_add(chunk_length)).ok_or_else(exceeds)?;
+    let chunk = data.get(offset + 8..end).ok_or_else(exceeds)?;
+    match chunk_type {
+      GLB_JSON_CHUNK if json.is_none() => json = Some(chunk),
+      GLB_BIN_CHUNK if binary.is_none() => binary = Some(chunk),
+      _ => {},
+    }
+    offset = end;
+  }
+  Ok((json.ok_or_else(|| Error::GltfParse("GLB without JSON chunk".to_string()))?, binary))
+}
+
+/// Check that node and material indices are in range and that nodes form a forest
+fn check_references(model: &GltfModel) -> Result<()> {
+  let mut parents = vec![None; model.nodes.len()];
+  for (index, node) in model.nodes.iter().enumerate() {
+    if node.mesh.is_some_and(|mesh| mesh >= model.meshes.len()) {
+      return Err(Error::GltfParse(format!("node {index} references missing mesh")));
+    }
+    for child in node.children.iter() {
+      let parent = parents.get_mut(*child).ok_or_else(|| Error::GltfParse(format!("node {index} references missing child {child}")))?;
+      if parent.replace(index).is_some() {
+        return Err(Error::GltfParse(format!("node {child} has several parents")));
+      }
+    }
+  }
+  for root in model.roots.iter() {
+    if parents.get(*root).is_none_or(|p| p.is_some()) {
+      return Err(Error::GltfParse(format!("scene root {root} is missing or has a parent")));
+    }
+  }
+  // Walk up from each node to detect cycles
+  for start in 0..parents.len() {
+    let (mut node, mut steps) = (start, 0);
+    while let Some(parent) = parents[node] {
+      (node, steps) = (parent, steps + 1);
+      if steps > parents.len() { return Err(Error::GltfParse(format!("node {start} is part of a cycle"))); }
+    }
+  }
+  let materials = model.meshes.iter().flat_map(|m| m.primitives.iter()).filter_map(|p| p.material);
+  if let Some(material) = materials.into_iter().find(|m| *m >= model.materials.len()) {
+    return Err(Error::GltfParse(format!("primitive references missing material {material}")));
+  }
+  Ok(())
+}
+
+/// Accessor data resolved against the decoded buffers
+struct Reader<'a> {
+  document: &'a Document,
+  buffers: Vec<std::borrow::Cow<'a, [u8]>>,
+}
+
+impl<'a> Reader<'a> {
+  fn new(document: &'a Document, binary: Option<&'a [u8]>) -> Result<Self> {
+    let buffers = document.buffers.iter().enumerate().map(|(i, buffer)| {
+      let data: std::borrow::Cow<[u8]> = match buffer.uri.as_deref() {
+        None if i == 0 => binary.ok_or_else(|| Error::GltfParse("buffer 0 has no uri and there is no GLB binary chunk".to_string()))?.into(),
+        None => return Err(Error::GltfParse(format!("buffer {i} has no uri"))),
+        Some(uri) => {
+          let (_, encoded) = uri.strip_prefix("data:").and_then(|u| u.split_once(";base64,"))
+            .ok_or_else(|| Error::UnsupportedOperation(format!("external buffer '{uri}'")))?;
+          base64::engine::general_purpose::STANDARD.decode(encoded)
+            .map_err(|e| Error::GltfParse(format!("buffer {i}: {e}")))?
+            .into()
+        },
+      };
+      if data.len() < buffer.byte_length {
+        return Err(Error::GltfParse(format!("buffer {i} holds {} bytes, {} declared", data.len(), buffer.byte_length)));
+      }
+      Ok(data)
+    }).collect::<Result<Vec<_>>>()?;
+    Ok(Reader { document, buffers })
+  }
+
+  fn primitive(&self, primitive: &PrimitiveDef) -> Result<GltfPrimitive> {
+    let attribute = |name: &str| primitive.attributes.get(name).copied();
+    let position = attribute("POSITION").ok_or_else(|| Error::GltfParse("primitive without POSITION".to_string()))?;
+    let (positions, _) = self.read(position, &[3])?;
+    let vertex_count = positions.len() / 3;
+    let mut indices: Vec<u32> = match primitive.indices {
+      Some(accessor) => self.read_indices(accessor)?,
+      None => (0..vertex_count as u32).collect(),
+    };
+    if let Some(index) = indices.iter().find(|i| **i as usize >= vertex_count) {
+      return Err(Error::GltfParse(format!("index {index} out of range of {vertex_count} vertices")));
+    }
+    indices = match primitive.mode {
+      4 => indices,
+      // Strip: every other triangle is flipped to keep the winding
+      5 => (0..indices.len().saturating_sub(2))
+        .flat_map(|i| if i % 2 == 0 { [indices[i], indices[i + 1], indices[i + 2]] } else { [indices[i + 1], indices[i], indices[i + 2]] })
+        .collect(),
+      6 => (1..indices.len().saturating_sub(1)).flat_map(|i| [indices[0], indices[i], indices[i + 1]]).collect(),
+      mode => return Err(Error::UnsupportedOperation(format!("primitive mode {mode}"))),
+    };
+
+    let mut mesh = Mesh::new(positions)?;
+    if let Some(accessor) = attribute("TEXCOORD_0") { mesh = mesh.with_uvs(self.read(accessor, &[2])?.0)?; }
+    if let Some(accessor) = attribute("COLOR_0") {
+      let colors = match self.read(accessor, &[3, 4])? {
+        (colors, 3) => colors.chunks_exact(3).flat_map(|c| [c[0], c[1], c[2], 1.0]).collect(),
+        (colors, _) => colors,
+      };
+      mesh = mesh.with_colors(colors)?;
+    }
+    mesh = mesh.with_indices(Indices::compact(indices))?;
+    mesh = match attribute("NORMAL") {
+      Some(accessor) => mesh.with_normals(self.read(accessor, &[3])?.0)?,
+      None => mesh.with_computed_normals(),
+    };
+    Ok(GltfPrimitive { mesh, material: primitive.material })
+  }
+
+  /// Read an accessor as floats, integers flagged as normalized being mapped to
+  /// `[0, 1]` or `[-1, 1]`, and return its values and number of components,
+  /// which must be one of `components`
+  fn read(&self, index: usize, components: &[usize]) -> Result<(Vec<f32>, usize)> {
+    let normalized = self.document.accessors.get(index).is_some_and(|a| a.normalized);
+    self.read_with(index, components, |component_type, bytes| {
+      let (value, scale) = match component_type {
+        5120 => (bytes[0] as i8 as f64, 127.0),
+        5121 => (bytes[0] as f64, 255.0),
+        5122 => (i16::from_le_bytes(bytes.try_into()?) as f64, 32767.0),
+        5123 => (u16::from_le_bytes(bytes.try_into()?) as f64, 65535.0),
+        5125 => (u32::from_le_bytes(bytes.try_into()?) as f64, 1.0),
+        _ => (f32::from_le_bytes(bytes.try_into()?) as f64, 1.0),
+      };
+      Ok(if normalized { (value / scale).max(-1.0) } else { value } as f32)
+    })
+  }
+
+  /// Read a `SCALAR` accessor of unsigned integers as exact `u32` values
+  fn read_indices(&self, index: usize) -> Result<Vec<u32>> {
+    let (values, _) = self.read_with(index, &[1], |component_type, bytes| match component_type {
+      5121 => Ok(bytes[0] as u32),
+      5123 => Ok(u16::from_le_bytes(bytes.try_into()?) as u32),
+      5125 => Ok(u32::from_le_bytes(bytes.try_into()?)),
+      other => Err(Error::GltfParse(format!("accessor {index}: component type {other} cannot hold indices"))),
+    })?;
+    Ok(values)
+  }
+
+  /// Read the components of an accessor, converting each from its component
+  /// type and little endian bytes with `convert`
+  fn read_with<T: Clone + Default>(
+    &self, index: usize, components: &[usize], convert: impl Fn(u32, &[u8]) -> Result<T>,
+  ) -> Result<(Vec<T>, usize)> {
+    let error = |message: String| Error::GltfParse(format!("accessor {index}: {message}"));
+    let accessor = self.document.accessors.get(index).ok_or_else(|| error("missing".to_string()))?;
+    if accessor.sparse.is_some() { return Err(Error::UnsupportedOperation(format!("sparse accessor {index}"))); }
+    let size = match accessor.r#type.as_str() {
+      "SCALAR" => 1, "VEC2" => 2, "VEC3" => 3, "VEC4" => 4,
+      other => return Err(error(format!("unsupported type {other}"))),
+    };
+    if !components.contains(&size) { return Err(error(format!("{} components, expected {:?}", size, components))); }
+    let width = match accessor.component_type {
+      5120 | 5121 => 1, 5122 | 5123 => 2, 5125 | 5126 => 4,
+      other => return Err(error(format!("unsupported component type {other}"))),
+    };
+    let length = accessor.count.checked_mul(size).ok_or_else(|| error(format!("{} elements overflow", accessor.count)))?;
+    let Some(view_index) = accessor.buffer_view else {
+      // Zeros, which cannot describe more data than the buffers of the file hold
+      let available: usize = self.buffers.iter().map(|b| b.len()).sum();
+      if length.checked_mul(width).is_none_or(|bytes| bytes > available) {
+        return Err(error(format!("{} elements without buffer view exceed the buffers", accessor.count)));
+      }
+      return Ok((vec![T::default(); length], size));
+    };
+    let view = self.document.buffer_views.get(view_index).ok_or_else(|| error(format!("missing buffer view {view_index}")))?;
+    let buffer = self.buffers.get(view.buffer).ok_or_else(|| error(format!("missing buffer {}", view.buffer)))?;
+    let data = view.byte_offset.checked_add(view.byte_length)
+      .and_then(|end| buffer.get(view.byte_offset..end))
+      .ok_or_else(|| error(format!("buffer view {view_index} exceeds its buffer")))?;
+    let stride = view.byte_stride.unwrap_or(width * size);
+
+    // Bytes spanned by the elements, checked before allocating the values
+    let span = match accessor.count {
+      0 => Some(0),
+      count => (count - 1).checked_mul(stride)
+        .and_then(|s| s.checked_add(size * width))
+        .and_then(|s| s.checked_add(accessor.byte_offset)),
+    };
+    if span.is_none_or(|span| span > data.len()) {
+      return Err(error(format!("{} elements exceed buffer view {view_index}", accessor.count)));
+    }
+
+    let mut values = Vec::with_capacity(length);
+    for element in 0..accessor.count {
+      for component in 0..size {
+        let offset = accessor.byte_offset + element * stride + component * width;
+        values.push(convert(accessor.component_type, &data[offset..offset + width])?);
+      }
+    }
+    Ok((values, size))
+  }
+}
+
+// Subset of the glTF JSON schema read by the loader
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+  asset: AssetDef,
+  scene: Option<usize>,
+  #[serde(default)] scenes: Vec<SceneDef>,
+  #[serde(default)] nodes: Vec<NodeDef>,
+  #[serde(default)] meshes: Vec<MeshDef>,
+  #[serde(default)] materials: Vec<MaterialDef>,
+  #[serde(default)] accessors: Vec<AccessorDef>,
+  #[serde(default)] buffer_views: Vec<BufferViewDef>,
+  #[serde(default)] buffers: Vec<BufferDef>,
+}
+
+#[derive(Deserialize)]
+struct AssetDef { version: String }
+
+#[derive(Deserialize)]
+struct SceneDef { #[serde(default)] nodes: Vec<usize> }
+
+#[derive(Deserialize)]
+struct NodeDef {
+  name: Option<String>,
+  #[serde(default)] children: Vec<usize>,
+  mesh: Option<usize>,
+  matrix: Option<[f32; 16]>,
+  translation: Option<[f32; 3]>,
+  rotation: Option<[f32; 4]>,
+  scale: Option<[f32; 3]>,
+}
+
+#[derive(Deserialize)]
+struct MeshDef {
+  name: Option<String>,
+  primitives: Vec<PrimitiveDef>,
+}
+
+#[derive(Deserialize)]
+struct PrimitiveDef {
+  attributes: HashMap<String, usize>,
+  indices: Option<usize>,
+  material: Option<usize>,
+  #[serde(default = "triangles")] mode: u32,
+}
+
+fn triangles() -> u32 { 4 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MaterialDef {
+  name: Option<String>,
+  #[serde(default)] pbr_metallic_roughness: PbrDef,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct PbrDef {
+  base_color_factor: [f32; 4],
+  metallic_factor: f32,
+  roughness_factor: f32,
+  base_color_texture: Option<TextureInfoDef>,
+}
+
+impl Default for PbrDef {
+  fn default() -> Self {
+    PbrDef { base_color_factor: [1.0; 4], metallic_factor: 1.0, roughness_factor: 1.0, base_color_texture: None }
+  }
+}
+
+#[derive(Deserialize)]
+struct TextureInfoDef { index: usize }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccessorDef {
+  buffer_view: Option<usize>,
+  #[serde(default)] byte_offset: usize,
+  component_type: u32,
+  #[serde(default)] normalized: bool,
+  count: usize,
+  r#type: String,
+  sparse: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferViewDef {
+  buffer: usize,
+  #[serde(default)] byte_offset: usize,
+  byte_length: usize,
+  byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferDef {
+  uri: Option<String>,
+  byte_length: usize,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Document with one triangle whose buffer holds `positions` then `indices`
+  fn triangle(extra: &str) -> String {
+    let mut buffer = Vec::new();
+    for v in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] { buffer.extend(v.to_le_bytes()); }
+    for i in [0u16, 1, 2, 0] { buffer.extend(i.to_le_bytes()); }
+    format!(r#"{{
+      "asset": {{ "version": "2.0" }},
+      "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }}] }}],
+      "accessors": [
+        {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }},
+        {{ "bufferView": 0, "byteOffset": 36, "componentType": 5123, "count": 3, "type": "SCALAR" }}
+      ],
+      "bufferViews": [{{ "buffer": 0, "byteLength": 44 }}],
+      "buffers": [{{ "byteLength": 44, "uri": "data:application/octet-stream;base64,{}" }}]
+      {extra}
+    }}"#, base64::engine::general_purpose::STANDARD.encode(&buffer))
+  }
+
+  #[test]
+  fn reads_embedded_buffer_and_computes_missing_normals() {
+    let model = parse_gltf(triangle(r#", "nodes": [{ "mesh": 0 }]"#).as_bytes()).unwrap();
+    assert_eq!(model.roots, vec![0]);
+    assert_eq!(model.nodes[0].name, "node0");
+    let mesh = &model.meshes[0].primitives[0].mesh;
+    assert_eq!(mesh.indices(), Some(&Indices::U16(vec![0, 1, 2])));
+    assert_eq!(mesh.normals(), Some(&[0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0][..]));
+  }
+
+  #[test]
+  fn rejects_invalid_hierarchies() {
+    let cycle = r#", "nodes": [{ "children": [1] }, { "children": [0] }], "scenes": [{ "nodes": [] }]"#;
+    assert!(matches!(parse_gltf(triangle(cycle).as_bytes()), Err(Error::GltfParse(m)) if m.contains("cycle")));
+    let shared = r#", "nodes": [{ "children": [2] }, { "children": [2] }, {}]"#;
+    assert!(matches!(parse_gltf(triangle(shared).as_bytes()), Err(Error::GltfParse(m)) if m.contains("several parents")));
+    let mesh = r#", "nodes": [{ "mesh": 3 }]"#;
+    assert!(matches!(parse_gltf(triangle(mesh).as_bytes()), Err(Error::GltfParse(_))));
+  }
+
+  #[test]
+  fn reads_indices_exactly_and_rejects_oversized_accessors() {
+    // 2^24 + 1 is not representable as f32
+    let mut buffer = Vec::new();
+    for v in [0.0f32; 9] { buffer.extend(v.to_le_bytes()); }
+    buffer.extend(16_777_217u32.to_le_bytes());
+    let document = |count: &str, view: &str| format!(r#"{{
+      "asset": {{ "version": "2.0" }},
+      "accessors": [{{ {view} "byteOffset": 36, "componentType": 5125, "count": {count}, "type": "SCALAR" }}],
+      "bufferViews": [{{ "buffer": 0, "byteLength": 40 }}],
+      "buffers": [{{ "byteLength": 40, "uri": "data:application/octet-stream;base64,{}" }}]
+    }}"#, base64::engine::general_purpose::STANDARD.encode(&buffer));
+    let parse = |count: &str, view: &str| {
+      let document: Document = serde_json::from_str(&document(count, view)).unwrap();
+      Reader::new(&document, None).and_then(|reader| reader.read_indices(0))
+    };
+    assert_eq!(parse("1", r#""bufferView": 0,"#).unwrap(), vec![16_777_217]);
+    assert!(matches!(parse("2", r#""bufferView": 0,"#), Err(Error::GltfParse(m)) if m.contains("exceed")));
+    assert!(matches!(parse("4611686018427387904", r#""bufferView": 0,"#), Err(Error::GltfParse(m)) if m.contains("exceed")));
+    assert!(matches!(parse("1000000000", ""), Err(Error::GltfParse(m)) if m.contains("exceed")));
+    assert_eq!(parse("10", "").unwrap(), vec![0; 10]);
+  }
+
+  #[test]
+  fn rejects_glb_chunks_past_the_end() {
+    let glb = |chunk_length: u32| -> Vec<u8> {
+      [GLB_MAGIC, 2, 24, chunk_length, GLB_JSON_CHUNK, 0].iter().flat_map(|w| w.to_le_bytes()).collect()
+    };
+    assert!(matches!(parse_gltf(&glb(u32::MAX)), Err(Error::GltfParse(m)) if m.contains("exceeds")));
+    assert!(matches!(parse_gltf(&glb(u32::MAX - 7)), Err(Error::GltfParse(m)) if m.contains("exceeds")));
+    // A JSON chunk holding the 4 bytes `0 0 0 0` is reached but is not valid JSON
+    assert!(matches!(parse_gltf(&glb(4)), Err(Error::GltfParse(m)) if !m.contains("exceeds")));
+  }
+}
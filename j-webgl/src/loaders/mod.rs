@@ -0,0 +1,19 @@
+use super::*;
+
+mod obj; pub use obj::{parse_obj, ObjModel, ObjGroup};
+mod mtl; pub use mtl::{parse_mtl, MtlMaterial};
+mod stl; pub use stl::{parse_stl, write_stl, StlModel, StlFormat};
+mod ply; pub use ply::{parse_ply, PlyModel, PlyFormat, PlyElement, PlyProperty, PlyValues, PlyType};
+mod gltf; pub use gltf::{parse_gltf, GltfModel, GltfNode, GltfMesh, GltfPrimitive, GltfMaterial};
+
+/// Split `data` into lines numbered from 1, without comments and surrounding blanks
+fn lines(data: &[u8]) -> impl Iterator<Item = (usize, String)> + '_ {
+  data.split(|b| *b == b'\n')
+    .enumerate()
+    .map(|(i, line)| {
+      let line = String::from_utf8_lossy(line);
+      let line = line.split('#').next().unwrap_or_default().trim().to_string();
+      (i + 1, line)
+    })
+    .filter(|(_, line)| !line.is_empty())
+}
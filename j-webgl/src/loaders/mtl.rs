@@ -0,0 +1,108 @@
+use super::*;
+
+/// Material read from a Wavefront MTL library
+#[derive(Debug, Clone, PartialEq)]
+pub struct MtlMaterial {
+  pub name: String,
+  /// Ambient color, `Ka`
+  pub ambient: [f32; 3],
+  /// Diffuse color, `Kd`
+  pub diffuse: [f32; 3],
+  /// Specular color, `Ks`
+  pub specular: [f32; 3],
+  /// Specular exponent, `Ns`
+  pub shininess: f32,
+  /// Opacity, `d` or `1 - Tr`
+  pub opacity: f32,
+  /// Diffuse texture file, `map_Kd`, relative to the library
+  pub diffuse_map: Option<String>,
+}
+
+impl MtlMaterial {
+  pub fn new(name: &str) -> Self {
+    MtlMaterial {
+      name: name.to_string(),
+      ambient: [0.0; 3],
+      diffuse: [0.8; 3],
+      specular: [0.0; 3],
+      shininess: 0.0,
+      opacity: 1.0,
+      diffuse_map: None,
+    }
+  }
+}
+
+/// Parse a Wavefront MTL material library
+///
+/// Statements other than `newmtl`, `Ka`, `Kd`, `Ks`, `Ns`, `d`, `Tr` and
+/// `map_Kd` are ignored. Texture map options are skipped, the file name being
+/// the last token of the statement.
+///
+/// # Errors
+///
+/// Returns [`Error::MtlParse`] with the line number of a malformed statement,
+/// or of a property given before any `newmtl`.
+pub fn parse_mtl(data: &[u8]) -> Result<Vec<MtlMaterial>> {
+  let mut materials: Vec<MtlMaterial> = Vec::new();
+  for (line, text) in lines(data) {
+    let error = |message: String| Error::MtlParse { line, message };
+    let mut tokens = text.split_whitespace();
+    let keyword = tokens.next().unwrap_or_default();
+    let arguments: Vec<&str> = tokens.collect();
+
+    if keyword == "newmtl" {
+      let name = arguments.join(" ");
+      if name.is_empty() { return Err(error("newmtl without a name".to_string())); }
+      materials.push(MtlMaterial::new(&name));
+      continue;
+    }
+    if !["Ka", "Kd", "Ks", "Ns", "d", "Tr", "map_Kd"].contains(&keyword) { continue; }
+    let material = materials.last_mut()
+      .ok_or_else(|| error(format!("{keyword} before any newmtl")))?;
+    let number = |s: &str| -> Result<f32> {
+      s.parse().map_err(|_| error(format!("invalid number '{s}' in {keyword}")))
+    };
+    let color = || -> Result<[f32; 3]> {
+      match arguments.as_slice() {
+        // A single value sets the three components
+        [v] => Ok([number(v)?; 3]),
+        [r, g, b, ..] => Ok([number(r)?, number(g)?, number(b)?]),
+        _ => Err(error(format!("{keyword} expects 1 or 3 values"))),
+      }
+    };
+    let scalar = || -> Result<f32> {
+      arguments.first().ok_or_else(|| error(format!("{keyword} expects a value"))).and_then(|s| number(s))
+    };
+    match keyword {
+      "Ka" => material.ambient = color()?,
+      "Kd" => material.diffuse = color()?,
+      "Ks" => material.specular = color()?,
+      "Ns" => material.shininess = scalar()?,
+      "d" => material.opacity = scalar()?,
+      "Tr" => material.opacity = 1.0 - scalar()?,
+      _ => {
+        let file = arguments.last().ok_or_else(|| error("map_Kd expects a file name".to_string()))?;
+        material.diffuse_map = Some(file.to_string());
+      },
+    }
+  }
+  Ok(materials)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reports_line_of_malformed_statement() {
+    let data = b"newmtl red\nKd 1 0 0\n\nKs 1 x 1\n";
+    match parse_mtl(data) {
+      Err(Error::MtlParse { line, message }) => {
+        assert_eq!(line, 4);
+        assert!(message.contains("'x'"), "{message}");
+      },
+      other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(parse_mtl(b"# library\nKd 1 1 1\n"), Err(Error::MtlParse { line: 2, .. })));
+  }
+}
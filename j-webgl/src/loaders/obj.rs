@@ -0,0 +1,276 @@
+use super::*;
+
+use std::collections::HashMap;
+use mesh::{Mesh, Indices};
+
+/// Model read from a Wavefront OBJ file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjModel {
+  /// Meshes, one per group and material in order of appearance
+  pub groups: Vec<ObjGroup>,
+  /// Material libraries referenced by `mtllib` statements
+  pub material_libraries: Vec<String>,
+}
+
+/// Faces of an OBJ model sharing a group name and a material
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjGroup {
+  /// Name of the enclosing `g` or `o` statement, `default` before any
+  pub name: String,
+  /// Material set by `usemtl`, if any
+  pub material: Option<String>,
+  pub mesh: Mesh,
+}
+
+impl ObjModel {
+  /// Build a scene with a root node per group, each rendering its mesh in the
+  /// diffuse color of its material, looked up by name in `materials`
+  pub fn to_scene(&self, materials: &[MtlMaterial]) -> Result<scene::Scene> {
+    let mut scene = scene::Scene::new();
+    for group in self.groups.iter() {
+      let node = scene.add_node(None, &group.name)?;
+      let mut renderable = structs::renderable::ShadedMesh::new(group.mesh.clone());
+      let material = group.material.as_ref()
+        .and_then(|name| materials.iter().find(|m| &m.name == name));
+      if let Some(material) = material {
+        let [red, green, blue] = material.diffuse;
+        renderable.set_color(red, green, blue, material.opacity);
+      }
+      scene.add_renderable(node, renderable)?;
+    }
+    Ok(scene)
+  }
+}
+
+/// Group being read, with the mesh vertex of each distinct `v/vt/vn` triplet
+#[derive(Default)]
+struct GroupBuilder {
+  name: String,
+  material: Option<String>,
+  vertices: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+  positions: Vec<f32>,
+  colors: Vec<f32>,
+  uvs: Vec<f32>,
+  normals: Vec<f32>,
+  indices: Vec<u32>,
+  /// Whether every vertex has a texture coordinate, respectively a normal
+  all_uvs: bool,
+  all_normals: bool,
+}
+
+impl GroupBuilder {
+  fn new(name: &str, material: Option<String>) -> Self {
+    GroupBuilder { name: name.to_string(), material, all_uvs: true, all_normals: true, ..Default::default() }
+  }
+
+  fn build(self) -> Result<ObjGroup> {
+    let mut mesh = Mesh::new(self.positions)?;
+    if self.colors.iter().any(|c| *c != 1.0) { mesh = mesh.with_colors(self.colors)?; }
+    if self.all_uvs { mesh = mesh.with_uvs(self.uvs)?; }
+    mesh = mesh.with_indices(Indices::compact(self.indices))?;
+    mesh = match self.all_normals {
+      true => mesh.with_normals(self.normals)?,
+      false => mesh.with_computed_normals(),
+    };
+    Ok(ObjGroup { name: self.name, material: self.material, mesh })
+  }
+}
+
+/// Parse a Wavefront OBJ file
+///
+/// Vertices (`v`, with optional RGB colors), texture coordinates (`vt`),
+/// normals (`vn`) and faces (`f`) are read, with indices counted from 1 or,
+/// when negative, backwards from the last element read. Polygons are
+/// triangulated by ear clipping, so that concave faces are supported. A new
+/// group starts on each `g`, `o` or `usemtl` statement. Texture coordinates
+/// are flipped vertically to have `v = 0` at the top of images. Groups
+/// missing some normals get normals computed from their faces. Other
+/// statements are ignored.
+///
+/// # Errors
+///
+/// Returns [`Error::ObjParse`] with the line number of a malformed statement
+/// or of a face referencing a missing element.
+pub fn parse_obj(data: &[u8]) -> Result<ObjModel> {
+  let mut positions: Vec<[f32; 3]> = Vec::new();
+  let mut colors: Vec<[f32; 3]> = Vec::new();
+  let mut uvs: Vec<[f32; 2]> = Vec::new();
+  let mut normals: Vec<[f32; 3]> = Vec::new();
+  let mut material_libraries = Vec::new();
+  let mut groups = Vec::new();
+  let mut group = GroupBuilder::new("default", None);
+
+  for (line, text) in lines(data) {
+    let error = |message: String| Error::ObjParse { line, message };
+    let mut tokens = text.split_whitespace();
+    let keyword = tokens.next().unwrap_or_default();
+    let arguments: Vec<&str> = tokens.collect();
+    let numbers = |minimum: usize| -> Result<Vec<f32>> {
+      if arguments.len() < minimum {
+        return Err(error(format!("{keyword} expects at least {minimum} values, got {}", arguments.len())));
+      }
+      arguments.iter()
+        .map(|s| s.parse::<f32>().map_err(|_| error(format!("invalid number '{s}' in {keyword}"))))
+        .collect()
+    };
+
+    match keyword {
+      "v" => {
+        let v = numbers(3)?;
+        positions.push([v[0], v[1], v[2]]);
+        colors.push(if v.len() >= 6 { [v[3], v[4], v[5]] } else { [1.0; 3] });
+      },
+      "vt" => {
+        let v = numbers(1)?;
+        uvs.push([v[0], 1.0 - v.get(1).copied().unwrap_or(0.0)]);
+      },
+      "vn" => {
+        let v = numbers(3)?;
+        normals.push([v[0], v[1], v[2]]);
+      },
+      "f" => {
+        if arguments.len() < 3 {
+          return Err(error(format!("face needs at least 3 vertices, got {}", arguments.len())));
+        }
+        let mut face = Vec::with_capacity(arguments.len());
+        for vertex in arguments.iter() {
+          let mut parts = vertex.split('/');
+          let resolve = |part: Option<&str>, count: usize, name: &str| -> Result<Option<usize>> {
+            let Some(part) = part.filter(|p| !p.is_empty()) else { return Ok(None); };
+            let index: i64 = part.parse().map_err(|_| error(format!("invalid {name} index '{part}'")))?;
+            let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+            if index == 0 || resolved < 0 || resolved >= count as i64 {
+              return Err(error(format!("{name} index {index} out of range ({count} defined)")));
+            }
+            Ok(Some(resolved as usize))
+          };
+          let position = resolve(parts.next(), positions.len(), "vertex")?
+            .ok_or_else(|| error(format!("face vertex '{vertex}' has no position index")))?;
+          let uv = resolve(parts.next(), uvs.len(), "texture coordinate")?;
+          let normal = resolve(parts.next(), normals.len(), "normal")?;
+          let key = (position, uv, normal);
+          let index = match group.vertices.get(&key) {
+            Some(index) => *index,
+            None => {
+              let index = (group.positions.len() / 3) as u32;
+              group.positions.extend(positions[position]);
+              group.colors.extend(colors[position]);
+              group.colors.push(1.0);
+              group.uvs.extend(uv.map(|i| uvs[i]).unwrap_or_default());
+              group.normals.extend(normal.map(|i| normals[i]).unwrap_or_default());
+              group.all_uvs &= uv.is_some();
+              group.all_normals &= normal.is_some();
+              group.vertices.insert(key, index);
+              index
+            },
+          };
+          face.push((index, positions[position]));
+        }
+        let points: Vec<[f32; 3]> = face.iter().map(|(_, p)| *p).collect();
+        for triangle in triangulate(&points) {
+          group.indices.extend(triangle.map(|i| face[i].0));
+        }
+      },
+      "g" | "o" | "usemtl" => {
+        let name = arguments.join(" ");
+        let (name, material) = match keyword {
+          "usemtl" => (group.name.clone(), Some(name)),
+          _ => (if name.is_empty() { "default".to_string() } else { name }, group.material.clone()),
+        };
+        let previous = std::mem::replace(&mut group, GroupBuilder::new(&name, material));
+        if !previous.indices.is_empty() { groups.push(previous.build()?); }
+      },
+      "mtllib" => material_libraries.extend(arguments.iter().map(|s| s.to_string())),
+      _ => {},
+    }
+  }
+  if !group.indices.is_empty() { groups.push(group.build()?); }
+
+  Ok(ObjModel { groups, material_libraries })
+}
+
+/// Triangulate a simple polygon by ear clipping, keeping its winding
+pub(super) fn triangulate(points: &[[f32; 3]]) -> Vec<[usize; 3]> {
+  let n = points.len();
+  let fan = |remaining: &[usize]| -> Vec<[usize; 3]> {
+    (1..remaining.len() - 1).map(|i| [remaining[0], remaining[i], remaining[i + 1]]).collect()
+  };
+  if n == 3 { return vec![[0, 1, 2]]; }
+
+  // Project on the plane of the dominant axis of the Newell normal
+  let mut normal = [0.0f32; 3];
+  for i in 0..n {
+    let (p, q) = (points[i], points[(i + 1) % n]);
+    normal[0] += (p[1] - q[1]) * (p[2] + q[2]);
+    normal[1] += (p[2] - q[2]) * (p[0] + q[0]);
+    normal[2] += (p[0] - q[0]) * (p[1] + q[1]);
+  }
+  let axis = (0..3).max_by(|a, b| normal[*a].abs().total_cmp(&normal[*b].abs())).unwrap_or(2);
+  if normal[axis] == 0.0 { return fan(&(0..n).collect::<Vec<_>>()); }
+  let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+  let sign = normal[axis].signum();
+  let projected: Vec<[f32; 2]> = points.iter().map(|p| [p[u], p[v] * sign]).collect();
+  let cross = |a: usize, b: usize, c: usize| {
+    let (a, b, c) = (projected[a], projected[b], projected[c]);
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+  };
+
+  let mut remaining: Vec<usize> = (0..n).collect();
+  let mut triangles = Vec::with_capacity(n - 2);
+  while remaining.len() > 3 {
+    let m = remaining.len();
+    let ear = (0..m).find(|&i| {
+      let (a, b, c) = (remaining[(i + m - 1) % m], remaining[i], remaining[(i + 1) % m]);
+      cross(a, b, c) > 0.0 && remaining.iter()
+        .filter(|&&p| p != a && p != b && p != c)
+        .all(|&p| cross(a, b, p) < 0.0 || cross(b, c, p) < 0.0 || cross(c, a, p) < 0.0)
+    });
+    let Some(i) = ear else {
+      // Degenerate polygon, fall back to a fan
+      triangles.extend(fan(&remaining));
+      return triangles;
+    };
+    triangles.push([remaining[(i + m - 1) % m], remaining[i], remaining[(i + 1) % m]]);
+    remaining.remove(i);
+  }
+  triangles.push([remaining[0], remaining[1], remaining[2]]);
+  triangles
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn triangulates_concave_polygon_with_its_winding() {
+    // L shape in the xy plane, counter-clockwise seen from +z
+    let points = [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [2.0, 1.0, 0.0], [1.0, 1.0, 0.0], [1.0, 2.0, 0.0], [0.0, 2.0, 0.0]];
+    let triangles = triangulate(&points);
+    assert_eq!(triangles.len(), 4);
+    let mut area = 0.0;
+    for [a, b, c] in triangles {
+      let (a, b, c) = (points[a], points[b], points[c]);
+      let z = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+      assert!(z > 0.0);
+      area += 0.5 * z;
+    }
+    assert!((area - 3.0).abs() < 1e-6);
+  }
+
+  #[test]
+  fn resolves_negative_indices_and_reports_errors() {
+    let model = parse_obj(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n").unwrap();
+    assert_eq!(model.groups[0].mesh.indices(), Some(&Indices::U16(vec![0, 1, 2])));
+
+    match parse_obj(b"v 0 0 0\nv 1 0 0\n# comment\nf 1 2 3\n") {
+      Err(Error::ObjParse { line, message }) => {
+        assert_eq!(line, 4);
+        assert!(message.contains("vertex index 3"), "{message}");
+      },
+      other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(parse_obj(b"v 0 0\n"), Err(Error::ObjParse { line: 1, .. })));
+    assert!(matches!(parse_obj(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2\n"), Err(Error::ObjParse { line: 4, .. })));
+    assert!(matches!(parse_obj(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1/1 2 3\n"), Err(Error::ObjParse { line: 4, .. })));
+  }
+}
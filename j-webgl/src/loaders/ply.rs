@@ -0,0 +1,362 @@
+use super::*;
+
+use mesh::{Mesh, Indices};
+
+/// Model read from a PLY file: its elements with all their properties
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlyModel {
+  pub format: PlyFormat,
+  pub comments: Vec<String>,
+  pub elements: Vec<PlyElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+  Ascii,
+  BinaryLittleEndian,
+  BinaryBigEndian,
+}
+
+/// Element of a PLY file, such as `vertex` or `face`, with `count` values per property
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlyElement {
+  pub name: String,
+  pub count: usize,
+  pub properties: Vec<PlyProperty>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlyProperty {
+  pub name: String,
+  /// Type of the values, or of the list items for list properties
+  pub data_type: PlyType,
+  pub values: PlyValues,
+}
+
+/// Values of a property, widened to `f64` which holds all PLY types exactly
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlyValues {
+  Scalar(Vec<f64>),
+  List(Vec<Vec<f64>>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyType {
+  Int8,
+  UInt8,
+  Int16,
+  UInt16,
+  Int32,
+  UInt32,
+  Float32,
+  Float64,
+}
+
+impl PlyType {
+  fn parse(name: &str) -> Option<Self> {
+    Some(match name {
+      "char" | "int8" => PlyType::Int8,
+      "uchar" | "uint8" => PlyType::UInt8,
+      "short" | "int16" => PlyType::Int16,
+      "ushort" | "uint16" => PlyType::UInt16,
+      "int" | "int32" => PlyType::Int32,
+      "uint" | "uint32" => PlyType::UInt32,
+      "float" | "float32" => PlyType::Float32,
+      "double" | "float64" => PlyType::Float64,
+      _ => return None,
+    })
+  }
+
+  /// Number of bytes of a binary value
+  pub fn size(&self) -> usize {
+    match self {
+      PlyType::Int8 | PlyType::UInt8 => 1,
+      PlyType::Int16 | PlyType::UInt16 => 2,
+      PlyType::Int32 | PlyType::UInt32 | PlyType::Float32 => 4,
+      PlyType::Float64 => 8,
+    }
+  }
+
+  /// Largest value of unsigned integer types, which map colors to `[0, 1]`
+  fn color_scale(&self) -> f64 {
+    match self {
+      PlyType::UInt8 => 255.0,
+      PlyType::UInt16 => 65535.0,
+      PlyType::UInt32 => u32::MAX as f64,
+      _ => 1.0,
+    }
+  }
+
+  fn read(&self, bytes: &[u8], format: PlyFormat) -> f64 {
+    macro_rules! read {
+      ($t:ty) => {{
+        let bytes = bytes.try_into().unwrap_or_default();
+        (if format == PlyFormat::BinaryBigEndian { <$t>::from_be_bytes(bytes) } else { <$t>::from_le_bytes(bytes) }) as f64
+      }};
+    }
+    match self {
+      PlyType::Int8 => bytes[0] as i8 as f64,
+      PlyType::UInt8 => bytes[0] as f64,
+      PlyType::Int16 => read!(i16),
+      PlyType::UInt16 => read!(u16),
+      PlyType::Int32 => read!(i32),
+      PlyType::UInt32 => read!(u32),
+      PlyType::Float32 => read!(f32),
+      PlyType::Float64 => read!(f64),
+    }
+  }
+}
+
+impl PlyProperty {
+  pub fn scalars(&self) -> Option<&[f64]> {
+    match &self.values { PlyValues::Scalar(values) => Some(values), PlyValues::List(_) => None }
+  }
+
+  pub fn lists(&self) -> Option<&[Vec<f64>]> {
+    match &self.values { PlyValues::List(values) => Some(values), PlyValues::Scalar(_) => None }
+  }
+}
+
+impl PlyElement {
+  pub fn property(&self, name: &str) -> Option<&PlyProperty> {
+    self.properties.iter().find(|p| p.name == name)
+  }
+
+  /// Interleave the scalar properties found first among each list of `candidates`,
+  /// divided by `scale(property)`
+  fn interleave(&self, candidates: &[&[&str]], scale: impl Fn(&PlyProperty) -> f64) -> Option<Vec<f32>> {
+    let columns = candidates.iter()
+      .map(|names| names.iter().find_map(|name| self.property(name)))
+      .map(|property| property.and_then(|p| Some((p.scalars()?, scale(p)))))
+      .collect::<Option<Vec<_>>>()?;
+    Some((0..self.count).flat_map(|i| columns.iter().map(move |(values, scale)| (values[i] / scale) as f32)).collect())
+  }
+}
+
+impl PlyModel {
+  pub fn element(&self, name: &str) -> Option<&PlyElement> {
+    self.elements.iter().find(|e| e.name == name)
+  }
+
+  /// Build a mesh from the `vertex` element and, when present, the `face` element
+  ///
+  /// Vertices need `x`, `y` and `z`; `nx`, `ny`, `nz` normals, `red`, `green`,
+  /// `blue` and optional `alpha` colors, and `s`, `t` or `u`, `v` texture
+  /// coordinates are read when present. Integer colors are mapped to
+  /// `[0, 1]`. Faces, read from `vertex_indices` or `vertex_index`, are
+  /// triangulated; without faces the mesh is a point cloud without indices.
+  pub fn to_mesh(&self) -> Result<Mesh> {
+    let vertex = self.element("vertex").ok_or_else(|| Error::PlyParse("no vertex element".to_string()))?;
+    let positions = vertex.interleave(&[&["x"], &["y"], &["z"]], |_| 1.0)
+      .ok_or_else(|| Error::PlyParse("vertex element without x, y and z".to_string()))?;
+    let mut mesh = Mesh::new(positions)?;
+    if let Some(normals) = vertex.interleave(&[&["nx"], &["ny"], &["nz"]], |_| 1.0) {
+      mesh = mesh.with_normals(normals)?;
+    }
+    let color = |p: &PlyProperty| p.data_type.color_scale();
+    let rgb: [&[&str]; 3] = [&["red", "diffuse_red"], &["green", "diffuse_green"], &["blue", "diffuse_blue"]];
+    if let Some(colors) = vertex.interleave(&[rgb[0], rgb[1], rgb[2], &["alpha"]], color) {
+      mesh = mesh.with_colors(colors)?;
+    } else if let Some(colors) = vertex.interleave(&rgb, color) {
+      mesh = mesh.with_colors(colors.chunks_exact(3).flat_map(|c| [c[0], c[1], c[2], 1.0]).collect())?;
+    }
+    if let Some(uvs) = vertex.interleave(&[&["s", "u", "texture_u"], &["t", "v", "texture_v"]], |_| 1.0) {
+      mesh = mesh.with_uvs(uvs)?;
+    }
+
+    let faces = self.element("face")
+      .and_then(|face| face.property("vertex_indices").or_else(|| face.property("vertex_index")))
+      .and_then(|property| property.lists());
+    if let Some(faces) = faces {
+      let mut indices = Vec::new();
+      for face in faces {
+        if let Some(i) = face.iter().find(|i| **i < 0.0 || i.fract() != 0.0) {
+          return Err(Error::PlyParse(format!("face index {i} is not a vertex index")));
+        }
+        let face: Vec<usize> = face.iter().map(|i| *i as usize).collect();
+        if let Some(i) = face.iter().find(|i| **i >= vertex.count) {
+          return Err(Error::PlyParse(format!("face index {i} out of range of {} vertices", vertex.count)));
+        }
+        if face.len() < 3 { continue; }
+        let points: Vec<[f32; 3]> = face.iter().map(|i| mesh.position(*i)).collect();
+        for triangle in obj::triangulate(&points) {
+          indices.extend(triangle.map(|i| face[i] as u32));
+        }
+      }
+      mesh = mesh.with_indices(Indices::compact(indices))?;
+    }
+    Ok(mesh)
+  }
+}
+
+/// Parse a PLY file in ASCII, binary little endian or binary big endian format
+///
+/// All elements and properties declared in the header are read, including
+/// list properties.
+///
+/// # Errors
+///
+/// Returns [`Error::PlyParse`] for malformed headers, with the line number,
+/// and for bodies not matching their header.
+pub fn parse_ply(data: &[u8]) -> Result<PlyModel> {
+  // Header lines, up to and including `end_header`
+  let mut offset = 0;
+  let mut header = Vec::new();
+  loop {
+    let end = data[offset..].iter().position(|b| *b == b'\n')
+      .ok_or_else(|| Error::PlyParse("header without end_header".to_string()))?;
+    let line = String::from_utf8_lossy(&data[offset..offset + end]).trim().to_string();
+    offset += end + 1;
+    let done = line == "end_header";
+    header.push(line);
+    if done { break; }
+  }
+
+  let error = |line: usize, message: String| Error::PlyParse(format!("line {}: {message}", line + 1));
+  if header[0] != "ply" { return Err(error(0, "missing 'ply' magic number".to_string())); }
+  let mut format = None;
+  let mut comments = Vec::new();
+  let mut elements: Vec<PlyElement> = Vec::new();
+  // Type of the length of list properties, for each property of each element
+  let mut count_types: Vec<Vec<Option<PlyType>>> = Vec::new();
+  for (line, text) in header.iter().enumerate().skip(1) {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    match tokens.as_slice() {
+      ["format", name, _version] => format = Some(match *name {
+        "ascii" => PlyFormat::Ascii,
+        "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+        "binary_big_endian" => PlyFormat::BinaryBigEndian,
+        other => return Err(error(line, format!("unknown format '{other}'"))),
+      }),
+      ["comment", ..] | ["obj_info", ..] => comments.push(text.split_once(' ').map(|(_, c)| c.to_string()).unwrap_or_default()),
+      ["element", name, count] => {
+        elements.push(PlyElement {
+          name: name.to_string(),
+          count: count.parse().map_err(|_| error(line, format!("invalid element count '{count}'")))?,
+          properties: Vec::new(),
+        });
+        count_types.push(Vec::new());
+      },
+      ["property", rest @ ..] => {
+        let (Some(element), Some(count_types)) = (elements.last_mut(), count_types.last_mut()) else {
+          return Err(error(line, "property before any element".to_string()));
+        };
+        let data_type = |name: &str| PlyType::parse(name).ok_or_else(|| error(line, format!("unknown type '{name}'")));
+        let (name, data_type, values, count_type) = match rest {
+          ["list", count_type, item_type, name] => (name, data_type(item_type)?, PlyValues::List(Vec::new()), Some(data_type(count_type)?)),
+          [item_type, name] => (name, data_type(item_type)?, PlyValues::Scalar(Vec::new()), None),
+          _ => return Err(error(line, format!("malformed property '{text}'"))),
+        };
+        element.properties.push(PlyProperty { name: name.to_string(), data_type, values });
+        count_types.push(count_type);
+      },
+      ["end_header"] => {},
+      _ => return Err(error(line, format!("unexpected '{text}'"))),
+    }
+  }
+  let format = format.ok_or_else(|| Error::PlyParse("header without format".to_string()))?;
+
+  let mut body = Body { data, offset, format, tokens: None };
+  for (element, count_types) in elements.iter_mut().zip(count_types) {
+    for row in 0..element.count {
+      for (property, count_type) in element.properties.iter_mut().zip(count_types.iter()) {
+        let context = || format!("{} {} of {}", element.name, row, property.name);
+        match &mut property.values {
+          PlyValues::Scalar(values) => values.push(body.value(property.data_type, &context)?),
+          PlyValues::List(lists) => {
+            let count = body.value(count_type.unwrap_or(PlyType::UInt8), &context)?;
+            if !(count >= 0.0 && count.fract() == 0.0) {
+              return Err(Error::PlyParse(format!("{}: list count {count} is not a count", context())));
+            }
+            let count = count as usize;
+            let list = (0..count).map(|_| body.value(property.data_type, &context)).collect::<Result<Vec<_>>>()?;
+            lists.push(list);
+          },
+        }
+      }
+    }
+  }
+  Ok(PlyModel { format, comments, elements })
+}
+
+/// Reader of the values following the header
+struct Body<'a> {
+  data: &'a [u8],
+  offset: usize,
+  format: PlyFormat,
+  /// Whitespace separated values of ASCII bodies
+  tokens: Option<std::str::SplitAsciiWhitespace<'a>>,
+}
+
+impl Body<'_> {
+  fn value(&mut self, data_type: PlyType, context: &dyn Fn() -> String) -> Result<f64> {
+    let truncated = || Error::PlyParse(format!("{}: unexpected end of data", context()));
+    if self.format == PlyFormat::Ascii {
+      if self.tokens.is_none() {
+        let data = self.data;
+        let text = std::str::from_utf8(&data[self.offset..])
+          .map_err(|e| Error::PlyParse(format!("ASCII body is not valid text: {e}")))?;
+        self.tokens = Some(text.split_ascii_whitespace());
+      }
+      let tokens = self.tokens.as_mut().unwrap();
+      let token = tokens.next().ok_or_else(truncated)?;
+      return token.parse().map_err(|_| Error::PlyParse(format!("{}: invalid number '{token}'", context())));
+    }
+    let bytes = self.data.get(self.offset..self.offset + data_type.size()).ok_or_else(truncated)?;
+    self.offset += data_type.size();
+    Ok(data_type.read(bytes, self.format))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reads_big_endian_lists_and_scalars() {
+    let mut data = b"ply\nformat binary_big_endian 1.0\nelement vertex 3\nproperty short x\nproperty float y\nproperty double z\n\
+      element face 1\nproperty list uchar int vertex_indices\nend_header\n".to_vec();
+    for (x, y, z) in [(0i16, 0.0f32, 0.0f64), (-2, 0.0, 0.0), (0, 1.5, 0.0)] {
+      data.extend(x.to_be_bytes());
+      data.extend(y.to_be_bytes());
+      data.extend(z.to_be_bytes());
+    }
+    data.push(3);
+    for i in [0i32, 2, 1] { data.extend(i.to_be_bytes()); }
+
+    let model = parse_ply(&data).unwrap();
+    assert_eq!(model.format, PlyFormat::BinaryBigEndian);
+    assert_eq!(model.element("vertex").unwrap().property("x").unwrap().scalars(), Some(&[0.0, -2.0, 0.0][..]));
+    assert_eq!(model.element("face").unwrap().property("vertex_indices").unwrap().lists(), Some(&[vec![0.0, 2.0, 1.0]][..]));
+    let mesh = model.to_mesh().unwrap();
+    assert_eq!(mesh.position(2), [0.0, 1.5, 0.0]);
+    assert_eq!(mesh.indices(), Some(&Indices::U16(vec![0, 2, 1])));
+
+    data.pop();
+    assert!(matches!(parse_ply(&data), Err(Error::PlyParse(m)) if m.contains("end of data")));
+  }
+
+  #[test]
+  fn reports_header_errors_with_line() {
+    let data = b"ply\nformat ascii 1.0\nelement vertex 1\nproperty half x\nend_header\n0\n";
+    assert!(matches!(parse_ply(data), Err(Error::PlyParse(m)) if m.starts_with("line 4:")));
+    assert!(matches!(parse_ply(b"obj\nend_header\n"), Err(Error::PlyParse(m)) if m.starts_with("line 1:")));
+  }
+
+  #[test]
+  fn rejects_invalid_face_indices_and_body_text() {
+    let header = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+      element face 1\nproperty list uchar int vertex_indices\nend_header\n0 0 0\n1 0 0\n0 1 0\n";
+    let mesh = |face: &str| parse_ply(format!("{header}{face}\n").as_bytes()).and_then(|ply| ply.to_mesh());
+    assert_eq!(mesh("3 0 1 2").unwrap().triangle_count(), 1);
+    assert!(matches!(mesh("3 0 -1 2"), Err(Error::PlyParse(m)) if m.contains("-1")));
+    assert!(matches!(mesh("3 0 1.5 2"), Err(Error::PlyParse(m)) if m.contains("1.5")));
+    assert!(matches!(mesh("-3 0 1 2"), Err(Error::PlyParse(m)) if m.contains("list count -3")));
+    assert!(matches!(mesh("3.7 0 1 2"), Err(Error::PlyParse(m)) if m.contains("list count 3.7")));
+    assert!(matches!(mesh("inf 0 1 2"), Err(Error::PlyParse(m)) if m.contains("list count inf")));
+
+    let mut data = header.as_bytes().to_vec();
+    data.extend(b"3 0 1 \xff\n");
+    assert!(matches!(parse_ply(&data), Err(Error::PlyParse(m)) if m.contains("not valid text")));
+  }
+}
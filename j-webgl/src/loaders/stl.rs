@@ -0,0 +1,232 @@
+use super::*;
+
+use std::collections::HashMap;
+use mesh::{Mesh, Indices};
+
+/// Length of the binary header, followed by the `u32` triangle count
+const HEADER_LENGTH: usize = 80;
+/// Length of a binary facet: normal, three vertices and an attribute byte count
+const FACET_LENGTH: usize = 50;
+
+/// Triangles read from an STL file
+///
+/// Each triangle contributes three vertices to `positions` and its facet
+/// normal, repeated for each vertex, to `normals`, matching the layout
+/// expected by [`TrianglesShaded::with_position`](programs::TrianglesShaded::with_position)
+/// and [`TrianglesShaded::with_normals`](programs::TrianglesShaded::with_normals).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StlModel {
+  /// Name of the ASCII `solid`, empty for binary files
+  pub name: String,
+  pub positions: Vec<f32>,
+  pub normals: Vec<f32>,
+}
+
+/// Encoding of a written STL file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StlFormat {
+  Ascii,
+  Binary,
+}
+
+impl StlModel {
+  pub fn triangle_count(&self) -> usize { self.positions.len() / 9 }
+
+  /// Non-indexed mesh with flat facet normals
+  pub fn to_mesh(&self) -> Result<Mesh> {
+    Mesh::new(self.positions.clone())?.with_normals(self.normals.clone())
+  }
+
+  /// Indexed mesh sharing vertices at identical positions, with smooth normals
+  /// averaged over the adjacent facets
+  pub fn welded(&self) -> Result<Mesh> {
+    let mut vertices: HashMap<[u32; 3], u32> = HashMap::new();
+    let mut positions = Vec::new();
+    let mut indices = Vec::with_capacity(self.positions.len() / 3);
+    for p in self.positions.chunks_exact(3) {
+      // Adding 0.0 turns -0.0 into 0.0 so that both weld together
+      let key = [p[0], p[1], p[2]].map(|v| (v + 0.0).to_bits());
+      let index = *vertices.entry(key).or_insert_with(|| {
+        positions.extend_from_slice(p);
+        (positions.len() / 3 - 1) as u32
+      });
+      indices.push(index);
+    }
+    Ok(Mesh::new(positions)?.with_indices(Indices::compact(indices))?.with_computed_normals())
+  }
+
+  /// Append a triangle, computing its normal from the vertices when zero
+  fn push(&mut self, normal: [f32; 3], vertices: &[f32]) {
+    let normal = if normal == [0.0; 3] { facet_normal(vertices) } else { normal };
+    self.positions.extend_from_slice(&vertices[..9]);
+    for _ in 0..3 { self.normals.extend(normal); }
+  }
+}
+
+/// Parse an STL file, detecting whether it is ASCII or binary
+///
+/// Files whose size matches the triangle count of a binary header are read as
+/// binary, even when the header starts with `solid` as some exporters write.
+/// Facet normals that are missing (zero) are computed from the vertices,
+/// assuming counter-clockwise winding.
+///
+/// # Errors
+///
+/// Returns [`Error::StlParse`] on a truncated binary file, or on a malformed
+/// ASCII statement with its line number.
+pub fn parse_stl(data: &[u8]) -> Result<StlModel> {
+  if data.len() >= HEADER_LENGTH + 4 {
+    let count = u32::from_le_bytes(data[HEADER_LENGTH..HEADER_LENGTH + 4].try_into()?) as usize;
+    let length = count.checked_mul(FACET_LENGTH).and_then(|n| n.checked_add(HEADER_LENGTH + 4));
+    if length == Some(data.len()) { return parse_binary(data); }
+  }
+  let start = data.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(data.len());
+  if data[start..].starts_with(b"solid") { parse_ascii(data) } else { parse_binary(data) }
+}
+
+fn parse_binary(data: &[u8]) -> Result<StlModel> {
+  if data.len() < HEADER_LENGTH + 4 {
+    return Err(Error::StlParse(format!("binary file of {} bytes is shorter than its header", data.len())));
+  }
+  let count = u32::from_le_bytes(data[HEADER_LENGTH..HEADER_LENGTH + 4].try_into()?) as usize;
+  let facets = &data[HEADER_LENGTH + 4..];
+  if facets.len() / FACET_LENGTH < count {
+    return Err(Error::StlParse(format!(
+      "binary file declares {count} triangles but holds {} bytes of facets", facets.len(),
+    )));
+  }
+  let mut model = StlModel { name: String::new(), positions: Vec::with_capacity(9 * count), normals: Vec::with_capacity(9 * count) };
+  for facet in facets.chunks_exact(FACET_LENGTH).take(count) {
+    let mut values = [0.0f32; 12];
+    for (i, value) in values.iter_mut().enumerate() {
+      *value = f32::from_le_bytes(facet[4 * i..4 * i + 4].try_into()?);
+    }
+    model.push([values[0], values[1], values[2]], &values[3..]);
+  }
+  Ok(model)
+}
+
+fn parse_ascii(data: &[u8]) -> Result<StlModel> {
+  let text = String::from_utf8_lossy(data);
+  let mut model = StlModel { name: String::new(), positions: Vec::new(), normals: Vec::new() };
+  let mut normal = [0.0f32; 3];
+  let mut vertices: Vec<f32> = Vec::with_capacity(9);
+  let mut in_facet = false;
+  for (i, line) in text.lines().enumerate() {
+    let error = |message: String| Error::StlParse(format!("line {}: {message}", i + 1));
+    let mut tokens = line.split_whitespace();
+    let Some(keyword) = tokens.next() else { continue; };
+    let numbers = |tokens: std::str::SplitWhitespace| -> Result<[f32; 3]> {
+      let values = tokens
+        .map(|s| s.parse::<f32>().map_err(|_| error(format!("invalid number '{s}'"))))
+        .collect::<Result<Vec<f32>>>()?;
+      values.try_into().map_err(|v: Vec<f32>| error(format!("expected 3 values, got {}", v.len())))
+    };
+    match keyword {
+      "solid" => model.name = tokens.collect::<Vec<_>>().join(" "),
+      "facet" => {
+        if tokens.next() != Some("normal") { return Err(error("expected 'facet normal'".to_string())); }
+        normal = numbers(tokens)?;
+        vertices.clear();
+        in_facet = true;
+      },
+      "vertex" => {
+        if !in_facet { return Err(error("vertex outside of a facet".to_string())); }
+        if vertices.len() == 9 { return Err(error("facet with more than 3 vertices".to_string())); }
+        vertices.extend(numbers(tokens)?);
+      },
+      "endfacet" => {
+        if !in_facet || vertices.len() != 9 {
+          return Err(error(format!("facet with {} vertices", vertices.len() / 3)));
+        }
+        model.push(normal, &vertices);
+        in_facet = false;
+      },
+      "outer" | "endloop" | "endsolid" => {},
+      _ => return Err(error(format!("unexpected '{keyword}'"))),
+    }
+  }
+  if in_facet { return Err(Error::StlParse("unterminated facet".to_string())); }
+  Ok(model)
+}
+
+/// Unit normal of the counter-clockwise triangle with the given 9 coordinates
+fn facet_normal(v: &[f32]) -> [f32; 3] {
+  let (u, w) = ([v[3] - v[0], v[4] - v[1], v[5] - v[2]], [v[6] - v[0], v[7] - v[1], v[8] - v[2]]);
+  let n = [u[1] * w[2] - u[2] * w[1], u[2] * w[0] - u[0] * w[2], u[0] * w[1] - u[1] * w[0]];
+  let norm = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+  if norm > 0.0 { n.map(|v| v / norm) } else { n }
+}
+
+/// Write the triangles of a mesh as an STL file, with normals computed from
+/// the counter-clockwise winding of each triangle
+///
+/// # Errors
+///
+/// Returns an error when the triangle count exceeds the `u32` of the binary format.
+pub fn write_stl(mesh: &Mesh, name: &str, format: StlFormat) -> Result<Vec<u8>> {
+  let triangles = mesh.triangles().map(|triangle| {
+    let mut vertices = [0.0f32; 9];
+    for (k, i) in triangle.into_iter().enumerate() {
+      vertices[3 * k..3 * k + 3].copy_from_slice(&mesh.position(i));
+    }
+    (facet_normal(&vertices), vertices)
+  });
+
+  match format {
+    StlFormat::Ascii => {
+      let mut text = format!("solid {name}\n");
+      for (normal, vertices) in triangles {
+        text += &format!("  facet normal {:e} {:e} {:e}\n    outer loop\n", normal[0], normal[1], normal[2]);
+        for v in vertices.chunks_exact(3) {
+          text += &format!("      vertex {:e} {:e} {:e}\n", v[0], v[1], v[2]);
+        }
+        text += "    endloop\n  endfacet\n";
+      }
+      text += &format!("endsolid {name}\n");
+      Ok(text.into_bytes())
+    },
+    StlFormat::Binary => {
+      let count: u32 = mesh.triangle_count().try_into()?;
+      let mut data = Vec::with_capacity(HEADER_LENGTH + 4 + count as usize * FACET_LENGTH);
+      // The header must not start with `solid`, which readers take for ASCII
+      let mut header = format!("binary STL {name}").into_bytes();
+      header.resize(HEADER_LENGTH, 0);
+      data.extend(header);
+      data.extend(count.to_le_bytes());
+      for (normal, vertices) in triangles {
+        for v in normal.iter().chain(vertices.iter()) { data.extend(v.to_le_bytes()); }
+        data.extend(0u16.to_le_bytes());
+      }
+      Ok(data)
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn detects_binary_file_starting_with_solid() {
+    let mesh = Mesh::new(vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]).unwrap();
+    let mut data = write_stl(&mesh, "", StlFormat::Binary).unwrap();
+    data[..5].copy_from_slice(b"solid");
+    let model = parse_stl(&data).unwrap();
+    assert_eq!(model.triangle_count(), 1);
+    assert_eq!(model.normals, [0.0, 0.0, 1.0].repeat(3));
+  }
+
+  #[test]
+  fn reports_malformed_files() {
+    // Binary header declaring 2 triangles followed by a partial facet
+    let mut data = vec![0u8; 84 + 60];
+    data[80] = 2;
+    assert!(matches!(parse_stl(&data), Err(Error::StlParse(_))));
+    let data = b"solid bad\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0\n";
+    match parse_stl(data) {
+      Err(Error::StlParse(message)) => assert!(message.starts_with("line 5:"), "{message}"),
+      other => panic!("unexpected {:?}", other),
+    }
+  }
+}
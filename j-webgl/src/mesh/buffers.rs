@@ -0,0 +1,121 @@
+use super::*;
+
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as GL;
+use gl::{GlContext, Program, VertexArray, Buffer};
+
+/// Program attribute names the mesh attributes are bound to, with their number of components
+const ATTRIBUTES: [(&str, i32); 4] = [("a_position", 3), ("a_normal", 3), ("a_texcoord", 2), ("a_color", 4)];
+
+/// [`Mesh`] uploaded to GPU buffers, ready to be drawn with a program
+///
+/// Each vertex attribute goes into its own `ARRAY_BUFFER`, bound in a vertex
+/// array object to the `a_position`, `a_normal`, `a_texcoord` and `a_color`
+/// attributes of the program. Attributes absent from the mesh or unused by
+/// the program are skipped. Indices go into an `ELEMENT_ARRAY_BUFFER` and are
+/// drawn with `draw_elements`. Buffers are deleted when dropped.
+pub struct MeshBuffers {
+  context: Rc<dyn GlContext>,
+  vertex_array: VertexArray,
+  buffers: Vec<Buffer>,
+  index_buffer: Option<Buffer>,
+  index_type: Option<u32>,
+  count: i32,
+}
+
+impl MeshBuffers {
+  pub fn new(context: &Rc<dyn GlContext>, program: &Program, mesh: &Mesh) -> Result<Self> {
+    let vertex_array = context.create_vertex_array().ok_or("Unable to create vertex array")?;
+    let mut mesh_buffers = MeshBuffers {
+      context: context.clone(),
+      vertex_array,
+      buffers: Vec::new(),
+      index_buffer: None,
+      index_type: None,
+      count: mesh.element_count().try_into()?,
+    };
+    context.bind_vertex_array(Some(&mesh_buffers.vertex_array));
+
+    let values = [Some(mesh.positions()), mesh.normals(), mesh.uvs(), mesh.colors()];
+    for ((name, size), values) in ATTRIBUTES.iter().zip(values) {
+      let location = context.get_attrib_location(program, name);
+      let Some(values) = values.filter(|_| location >= 0) else { continue; };
+      let buffer = context.create_buffer().ok_or("Unable to create buffer")?;
+      context.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
+      context.buffer_data_with_f32_array(GL::ARRAY_BUFFER, values, GL::STATIC_DRAW);
+      context.enable_vertex_attrib_array(location as u32);
+      context.vertex_attrib_pointer_with_i32(location as u32, *size, GL::FLOAT, false, 0, 0);
+      mesh_buffers.buffers.push(buffer);
+    }
+
+    if let Some(indices) = mesh.indices() {
+      let buffer = context.create_buffer().ok_or("Unable to create buffer")?;
+      context.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&buffer));
+      match indices {
+        Indices::U16(data) => context.buffer_data_with_u16_array(GL::ELEMENT_ARRAY_BUFFER, data, GL::STATIC_DRAW),
+        Indices::U32(data) => context.buffer_data_with_u32_array(GL::ELEMENT_ARRAY_BUFFER, data, GL::STATIC_DRAW),
+      }
+      mesh_buffers.index_buffer = Some(buffer);
+      mesh_buffers.index_type = Some(indices.data_type());
+    }
+
+    context.bind_vertex_array(None);
+    Ok(mesh_buffers)
+  }
+
+  /// Number of vertices drawn
+  pub fn count(&self) -> i32 { self.count }
+
+  /// Bind the vertex array and draw the mesh triangles with the program in use
+  pub fn draw(&self) -> Result<()> {
+    self.draw_mode(GL::TRIANGLES)
+  }
+
+  /// Bind the vertex array and draw the mesh with the given primitive `mode`
+  pub fn draw_mode(&self, mode: u32) -> Result<()> {
+    let context = &self.context;
+    context.bind_vertex_array(Some(&self.vertex_array));
+    match self.index_type {
+      Some(index_type) => context.draw_elements_with_i32(mode, self.count, index_type, 0),
+      None => context.draw_arrays(mode, 0, self.count),
+    }
+    Ok(())
+  }
+}
+
+impl Drop for MeshBuffers {
+  fn drop(&mut self) {
+    self.context.delete_vertex_array(Some(&self.vertex_array));
+    for buffer in self.buffers.iter().chain(self.index_buffer.iter()) {
+      self.context.delete_buffer(Some(buffer));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use gl::{RecordingContext, Call};
+
+  #[test]
+  fn uploads_element_array_buffer_and_draws_elements() {
+    let recording = Rc::new(RecordingContext::new(64, 64));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let program = context.create_program().unwrap();
+    let mesh = Mesh::new(vec![0.0; 12]).unwrap()
+      .with_uvs(vec![0.0; 8]).unwrap()
+      .with_indices(Indices::compact(vec![0, 1, 2, 2, 3, 0])).unwrap();
+
+    let buffers = MeshBuffers::new(&context, &program, &mesh).unwrap();
+    buffers.draw().unwrap();
+
+    let calls = recording.calls();
+    assert!(calls.iter().any(|c| matches!(c, Call::BufferDataU16 { target: GL::ELEMENT_ARRAY_BUFFER, data, .. } if data == &[0, 1, 2, 2, 3, 0])));
+    // Positions and texture coordinates, which the recording context binds to any name
+    assert_eq!(calls.iter().filter(|c| matches!(c, Call::BufferData { target: GL::ARRAY_BUFFER, .. })).count(), 2);
+    assert_eq!(calls.last(), Some(&Call::DrawElements { mode: GL::TRIANGLES, count: 6, data_type: GL::UNSIGNED_SHORT, offset: 0 }));
+
+    drop(buffers);
+    assert_eq!(recording.calls().iter().filter(|c| matches!(c, Call::DeleteBuffer(_))).count(), 3);
+  }
+}
@@ -0,0 +1,54 @@
+use web_sys::WebGl2RenderingContext as GL;
+
+/// Triangle vertex indices, stored as 16-bit values when the mesh is small enough
+#[derive(Debug, Clone, PartialEq)]
+pub enum Indices {
+  U16(Vec<u16>),
+  U32(Vec<u32>),
+}
+
+impl Indices {
+  /// Store `indices` with the smallest integer type holding them
+  pub fn compact(indices: Vec<u32>) -> Self {
+    match indices.iter().all(|i| *i <= u16::MAX as u32) {
+      true => Indices::U16(indices.into_iter().map(|i| i as u16).collect()),
+      false => Indices::U32(indices),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    match self {
+      Indices::U16(v) => v.len(),
+      Indices::U32(v) => v.len(),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+  pub fn get(&self, i: usize) -> Option<u32> {
+    match self {
+      Indices::U16(v) => v.get(i).map(|i| *i as u32),
+      Indices::U32(v) => v.get(i).copied(),
+    }
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+    (0..self.len()).filter_map(|i| self.get(i))
+  }
+
+  /// GL type of the indices, `UNSIGNED_SHORT` or `UNSIGNED_INT`
+  pub fn data_type(&self) -> u32 {
+    match self {
+      Indices::U16(_) => GL::UNSIGNED_SHORT,
+      Indices::U32(_) => GL::UNSIGNED_INT,
+    }
+  }
+}
+
+impl From<Vec<u16>> for Indices {
+  fn from(indices: Vec<u16>) -> Self { Indices::U16(indices) }
+}
+
+impl From<Vec<u32>> for Indices {
+  fn from(indices: Vec<u32>) -> Self { Indices::U32(indices) }
+}
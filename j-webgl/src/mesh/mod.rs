@@ -0,0 +1,199 @@
+use super::*;
+
+mod indices; pub use indices::Indices;
+mod buffers; pub use buffers::MeshBuffers;
+
+/// Indexed triangle mesh held in CPU memory
+///
+/// Vertex attributes are stored as flat arrays: 3 values per vertex for
+/// positions and normals, 2 for texture coordinates and 4 (RGBA) for colors.
+/// Optional attributes are empty when absent. Triangles are given by
+/// [`Indices`] into the vertices, or by consecutive vertex triplets when the
+/// mesh has no indices. Use [`MeshBuffers`] to upload a mesh to the GPU.
+///
+/// ```rust
+/// use j_webgl::mesh::Mesh;
+///
+/// let quad = Mesh::new(vec![0.0, 0.0, 0.0,  1.0, 0.0, 0.0,  1.0, 1.0, 0.0,  0.0, 1.0, 0.0]).unwrap()
+///   .with_indices(vec![0u16, 1, 2,  2, 3, 0]).unwrap()
+///   .with_computed_normals();
+/// assert_eq!(quad.vertex_count(), 4);
+/// assert_eq!(quad.triangle_count(), 2);
+/// assert_eq!(&quad.normals().unwrap()[0..3], &[0.0, 0.0, 1.0]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+  positions: Vec<f32>,
+  normals: Vec<f32>,
+  uvs: Vec<f32>,
+  colors: Vec<f32>,
+  indices: Option<Indices>,
+}
+
+impl Mesh {
+  /// Create a mesh from vertex positions, 3 values per vertex
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the number of values is not a multiple of 3.
+  pub fn new(positions: Vec<f32>) -> Result<Self> {
+    if !positions.len().is_multiple_of(3) {
+      return Err(Error::InvalidMesh(format!("{} position values is not a multiple of 3", positions.len())));
+    }
+    Ok(Mesh { positions, ..Default::default() })
+  }
+
+  /// Set vertex normals, 3 values per vertex
+  pub fn with_normals(mut self, normals: Vec<f32>) -> Result<Self> {
+    self.check_attribute("normal", normals.len(), 3)?;
+    self.normals = normals;
+    Ok(self)
+  }
+
+  /// Set vertex texture coordinates, 2 values per vertex
+  pub fn with_uvs(mut self, uvs: Vec<f32>) -> Result<Self> {
+    self.check_attribute("uv", uvs.len(), 2)?;
+    self.uvs = uvs;
+    Ok(self)
+  }
+
+  /// Set vertex RGBA colors, 4 values per vertex
+  pub fn with_colors(mut self, colors: Vec<f32>) -> Result<Self> {
+    self.check_attribute("color", colors.len(), 4)?;
+    self.colors = colors;
+    Ok(self)
+  }
+
+  /// Set triangle indices
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the number of indices is not a multiple of 3 or if
+  /// an index is out of the vertex range.
+  pub fn with_indices<I: Into<Indices>>(mut self, indices: I) -> Result<Self> {
+    let indices = indices.into();
+    if !indices.len().is_multiple_of(3) {
+      return Err(Error::InvalidMesh(format!("{} indices is not a multiple of 3", indices.len())));
+    }
+    if let Some(index) = indices.iter().find(|i| *i as usize >= self.vertex_count()) {
+      return Err(Error::InvalidMesh(format!("Index {} is out of range for {} vertices", index, self.vertex_count())));
+    }
+    self.indices = Some(indices);
+    Ok(self)
+  }
+
+  /// Replace the normals by area-weighted averages of the adjacent triangle normals
+  pub fn with_computed_normals(mut self) -> Self {
+    let mut normals = vec![0.0f32; self.positions.len()];
+    for triangle in self.triangles() {
+      let [a, b, c] = triangle.map(|i| self.position(i));
+      let (u, v) = ([0, 1, 2].map(|k| b[k] - a[k]), [0, 1, 2].map(|k| c[k] - a[k]));
+      let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+      for i in triangle {
+        for k in 0..3 { normals[3 * i + k] += n[k]; }
+      }
+    }
+    for n in normals.chunks_exact_mut(3) {
+      let norm = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+      if norm > 0.0 { n.iter_mut().for_each(|v| *v /= norm); }
+    }
+    self.normals = normals;
+    self
+  }
+
+  pub fn positions(&self) -> &[f32] { &self.positions }
+  pub fn normals(&self) -> Option<&[f32]> { Some(self.normals.as_slice()).filter(|v| !v.is_empty()) }
+  pub fn uvs(&self) -> Option<&[f32]> { Some(self.uvs.as_slice()).filter(|v| !v.is_empty()) }
+  pub fn colors(&self) -> Option<&[f32]> { Some(self.colors.as_slice()).filter(|v| !v.is_empty()) }
+  pub fn indices(&self) -> Option<&Indices> { self.indices.as_ref() }
+
+  pub fn vertex_count(&self) -> usize { self.positions.len() / 3 }
+
+  /// Number of vertices drawn: the number of indices, or of vertices for a non-indexed mesh
+  pub fn element_count(&self) -> usize {
+    self.indices.as_ref().map(|i| i.len()).unwrap_or(self.vertex_count())
+  }
+
+  pub fn triangle_count(&self) -> usize { self.element_count() / 3 }
+
+  /// Returns the position of vertex `i`
+  pub fn position(&self, i: usize) -> [f32; 3] {
+    [self.positions[3 * i], self.positions[3 * i + 1], self.positions[3 * i + 2]]
+  }
+
+  /// Returns the box bounding the vertices, `None` for a mesh without vertices
+  pub fn bounds(&self) -> Option<algebra::Aabb> {
+    let points: Vec<_> = self.positions.chunks_exact(3).map(|p| algebra::Point3::new(p[0], p[1], p[2])).collect();
+    algebra::Aabb::from_points(&points)
+  }
+
+  /// Build a bounding volume hierarchy over the triangles, to intersect rays with the mesh
+  pub fn bvh(&self) -> algebra::Bvh {
+    let point = |i: usize| { let [x, y, z] = self.position(i); algebra::Point3::new(x, y, z) };
+    algebra::Bvh::new(self.triangles().map(|t| t.map(point)).collect())
+  }
+
+  /// Iterate over triangles as vertex index triplets
+  pub fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+    let indices: Box<dyn Iterator<Item = usize>> = match self.indices.as_ref() {
+      Some(indices) => Box::new(indices.iter().map(|i| i as usize)),
+      None => Box::new(0..self.vertex_count()),
+    };
+    let mut indices = indices.fuse();
+    std::iter::from_fn(move || Some([indices.next()?, indices.next()?, indices.next()?]))
+  }
+
+  fn check_attribute(&self, name: &str, len: usize, size: usize) -> Result<()> {
+    if len != self.vertex_count() * size {
+      return Err(Error::InvalidMesh(format!(
+        "{} {} values do not match {} vertices with {} values each", len, name, self.vertex_count(), size,
+      )));
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn validates_attribute_lengths_and_indices() {
+    assert!(Mesh::new(vec![0.0; 4]).is_err());
+    let mesh = Mesh::new(vec![0.0; 9]).unwrap();
+    assert!(mesh.clone().with_normals(vec![0.0; 6]).is_err());
+    assert!(mesh.clone().with_uvs(vec![0.0; 6]).is_ok());
+    assert!(mesh.clone().with_colors(vec![0.0; 9]).is_err());
+    assert!(mesh.clone().with_indices(vec![0u32, 1]).is_err());
+    assert!(mesh.clone().with_indices(vec![0u32, 1, 3]).is_err());
+    assert_eq!(mesh.with_indices(vec![0u32, 1, 2, 2, 1, 0]).unwrap().triangle_count(), 2);
+  }
+
+  #[test]
+  fn computed_normals_are_averaged_over_shared_vertices() {
+    // Two triangles folded along the x axis: one in the xy plane, one in the xz plane
+    let mesh = Mesh::new(vec![0.0, 0.0, 0.0,  1.0, 0.0, 0.0,  0.0, 1.0, 0.0,  0.0, 0.0, -1.0]).unwrap()
+      .with_indices(vec![0u16, 1, 2,  0, 1, 3]).unwrap()
+      .with_computed_normals();
+    let normals = mesh.normals().unwrap();
+    let s = std::f32::consts::FRAC_1_SQRT_2;
+    for (a, b) in normals[0..3].iter().zip([0.0, s, s]) { assert!((a - b).abs() < 1e-6); }
+    assert_eq!(&normals[6..9], &[0.0, 0.0, 1.0]);
+    assert_eq!(&normals[9..12], &[0.0, 1.0, 0.0]);
+  }
+
+  #[test]
+  fn camera_ray_hits_mesh_surface() {
+    let camera = crate::structs::camera::Basic::new(640.0, 480.0).unwrap();
+    let ray = traits::Camera::ray(&camera, 320.0, 240.0).unwrap();
+    let sphere = crate::geometry::icosphere(1.0, 3).unwrap();
+    let hit = sphere.bvh().intersect(&ray).unwrap();
+    // The ray through the center of the screen hits the sphere around the origin facing the eye
+    let eye = traits::Camera::eye_position(&camera).unwrap();
+    let expected = (&eye - &algebra::Point3::origin()).norm() - 1.0;
+    assert!((&hit.point - &ray.at(hit.distance)).norm() < 1e-6);
+    assert!(((&ray.origin() - &eye).norm() + hit.distance - expected).abs() < 2e-2);
+    assert!(hit.normal.dot(&ray.direction()) < 0.0);
+    assert!(traits::Camera::ray(&camera, 0.0, 0.0).map(|r| sphere.bvh().intersect(&r)).unwrap().is_none());
+  }
+}
@@ -0,0 +1,380 @@
+use super::*;
+
+use gl::UniformValue;
+use programs::FullScreen;
+
+/// Compile the program of `source` on first use, bind `textures` to
+/// successive texture units, set `uniforms` and draw a full-screen triangle
+fn draw<S: FnOnce() -> Result<String>>(
+  program: &RefCell<Option<FullScreen>>,
+  source: S,
+  context: &Rc<dyn GlContext>,
+  textures: &[(&str, &texture::Texture2D)],
+  uniforms: &[(&str, UniformValue)],
+) -> Result<()> {
+  let mut program = program.borrow_mut();
+  if program.is_none() {
+    *program = Some(FullScreen::new(context, &source()?)?);
+  }
+  let program = program.as_mut().unwrap();
+  for (unit, (name, texture)) in textures.iter().enumerate() {
+    program.with_texture(name, unit.try_into()?, texture)?;
+  }
+  for (name, value) in uniforms {
+    program.with_uniform(name, value)?;
+  }
+  program.draw()
+}
+
+/// First input of a pass, read by single input effects
+fn input<'a>(pass: &'a EffectPass) -> Result<&'a texture::Texture2D> {
+  pass.input(0).ok_or_else(|| "Effect has no input texture".into())
+}
+
+/// Effect drawing a user-supplied fragment shader
+///
+/// The shader reads the texture coordinates of the fragment from
+/// `in vec2 v_uv` and writes `out vec4 outColor`. Its inputs are bound to
+/// samplers `u_input0`, `u_input1`, ... in the order of
+/// [`with_inputs`](ShaderEffect::with_inputs), by default the scene color only.
+///
+/// # Example
+///
+/// ```rust
+/// use j_webgl::{post::{self, ShaderEffect}, gl::UniformValue};
+///
+/// let mut sepia = ShaderEffect::new(r#"#version 300 es
+///   precision highp float;
+///   uniform sampler2D u_input0;
+///   uniform float u_amount;
+///   in vec2 v_uv;
+///   out vec4 outColor;
+///   void main() {
+///     vec4 color = texture(u_input0, v_uv);
+///     vec3 sepia = vec3(dot(color.rgb, vec3(0.393, 0.769, 0.189)),
+///                       dot(color.rgb, vec3(0.349, 0.686, 0.168)),
+///                       dot(color.rgb, vec3(0.272, 0.534, 0.131)));
+///     outColor = vec4(mix(color.rgb, sepia, u_amount), color.a);
+///   }
+/// "#);
+/// sepia.set_uniform("u_amount", UniformValue::Float(0.8));
+/// let mut chain = post::Chain::new();
+/// chain.with_effect("sepia".to_string(), Some(sepia));
+/// ```
+pub struct ShaderEffect {
+  source: String,
+  inputs: Vec<String>,
+  output: String,
+  uniforms: Vec<(String, UniformValue)>,
+  program: RefCell<Option<FullScreen>>,
+}
+
+impl ShaderEffect {
+  pub fn new(fragment_shader: &str) -> Self {
+    ShaderEffect {
+      source: fragment_shader.to_string(),
+      inputs: vec![COLOR.to_string()],
+      output: COLOR.to_string(),
+      uniforms: Vec::new(),
+      program: RefCell::new(None),
+    }
+  }
+
+  /// Set the names of the textures bound to `u_input0`, `u_input1`, ...
+  pub fn with_inputs(mut self, inputs: Vec<String>) -> Self {
+    self.inputs = inputs;
+    self
+  }
+
+  /// Set the name of the texture written
+  pub fn with_output(mut self, output: String) -> Self {
+    self.output = output;
+    self
+  }
+
+  /// Set the value of uniform `name`, applied on every draw
+  pub fn set_uniform(&mut self, name: &str, value: UniformValue) {
+    match self.uniforms.iter_mut().find(|(n, _)| n == name) {
+      Some((_, v)) => *v = value,
+      None => self.uniforms.push((name.to_string(), value)),
+    }
+  }
+}
+
+impl Effect for ShaderEffect {
+  fn inputs(&self) -> Vec<&str> { self.inputs.iter().map(String::as_str).collect() }
+
+  fn output(&self) -> &str { &self.output }
+
+  fn apply(&self, pass: &EffectPass) -> Result<()> {
+    let names: Vec<String> = (0..self.inputs.len()).map(|i| format!("u_input{i}")).collect();
+    let textures = names.iter()
+      .enumerate()
+      .map(|(i, name)| pass.input(i).map(|t| (name.as_str(), t)).ok_or("Missing effect input".into()))
+      .collect::<Result<Vec<_>>>()?;
+    let uniforms: Vec<(&str, UniformValue)> = self.uniforms.iter().map(|(n, v)| (n.as_str(), v.clone())).collect();
+    draw(&self.program, || Ok(self.source.clone()), pass.context(), &textures, &uniforms)
+  }
+}
+
+/// Fast approximate anti-aliasing, smoothing edges along their direction
+#[derive(Default)]
+pub struct Fxaa {
+  program: RefCell<Option<FullScreen>>,
+}
+
+impl Fxaa {
+  pub fn new() -> Self { Fxaa::default() }
+}
+
+impl Effect for Fxaa {
+  fn apply(&self, pass: &EffectPass) -> Result<()> {
+    draw(&self.program, shaders::fragment::fxaa, pass.context(), &[("u_input0", input(pass)?)], &[])
+  }
+}
+
+/// Curve of a [`ToneMapping`] effect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMappingOperator {
+  /// `c / (1 + c)`, preserving hues
+  Reinhard,
+  /// Filmic curve of the Academy Color Encoding System, with more contrast
+  Aces,
+}
+
+/// Map high dynamic range colors to the displayable 0 to 1 range
+///
+/// Colors above 1 only reach the effect from scenes rendered into a float
+/// target, see [`Renderer::set_post_settings`](crate::Renderer::set_post_settings).
+pub struct ToneMapping {
+  operator: ToneMappingOperator,
+  exposure: f32,
+  program: RefCell<Option<FullScreen>>,
+}
+
+impl ToneMapping {
+  pub fn new(operator: ToneMappingOperator) -> Self {
+    ToneMapping { operator, exposure: 1.0, program: RefCell::new(None) }
+  }
+
+  /// Set the factor applied to colors before mapping, 1 by default
+  pub fn set_exposure(&mut self, exposure: f32) { self.exposure = exposure; }
+
+  pub fn exposure(&self) -> f32 { self.exposure }
+
+  pub fn set_operator(&mut self, operator: ToneMappingOperator) { self.operator = operator; }
+
+  pub fn operator(&self) -> ToneMappingOperator { self.operator }
+}
+
+impl Effect for ToneMapping {
+  fn apply(&self, pass: &EffectPass) -> Result<()> {
+    let operator = match self.operator { ToneMappingOperator::Reinhard => 0, ToneMappingOperator::Aces => 1 };
+    draw(&self.program, shaders::fragment::tone_mapping, pass.context(), &[("u_input0", input(pass)?)], &[
+      ("u_exposure", UniformValue::Float(self.exposure)),
+      ("u_operator", UniformValue::Int(operator)),
+    ])
+  }
+}
+
+/// Encode linear colors for display, raising them to `1 / gamma`
+///
+/// [`PbrMesh`](crate::structs::renderable::PbrMesh) already gamma encodes
+/// its output: use this effect for scenes of other renderables, whose colors
+/// are linear, typically after [`ToneMapping`].
+pub struct Gamma {
+  gamma: f32,
+  program: RefCell<Option<FullScreen>>,
+}
+
+impl Default for Gamma {
+  fn default() -> Self {
+    Gamma { gamma: 2.2, program: RefCell::new(None) }
+  }
+}
+
+impl Gamma {
+  pub fn new() -> Self { Gamma::default() }
+
+  /// Set the display gamma, 2.2 by default
+  pub fn set_gamma(&mut self, gamma: f32) { self.gamma = gamma; }
+
+  pub fn gamma(&self) -> f32 { self.gamma }
+}
+
+impl Effect for Gamma {
+  fn apply(&self, pass: &EffectPass) -> Result<()> {
+    draw(&self.program, shaders::fragment::gamma, pass.context(), &[("u_input0", input(pass)?)], &[
+      ("u_gamma", UniformValue::Float(self.gamma)),
+    ])
+  }
+}
+
+/// Darken the borders of the image
+pub struct Vignette {
+  intensity: f32,
+  radius: f32,
+  softness: f32,
+  program: RefCell<Option<FullScreen>>,
+}
+
+impl Default for Vignette {
+  fn default() -> Self {
+    Vignette { intensity: 0.5, radius: 0.9, softness: 0.5, program: RefCell::new(None) }
+  }
+}
+
+impl Vignette {
+  pub fn new() -> Self { Vignette::default() }
+
+  /// Set the darkening of the corners, from 0 for none to 1 for black, 0.5 by default
+  pub fn set_intensity(&mut self, intensity: f32) { self.intensity = intensity; }
+
+  /// Set the distance from the center, 1 in the corners, where darkening is
+  /// complete, and the width of the transition, 0.9 and 0.5 by default
+  pub fn set_radius(&mut self, radius: f32, softness: f32) {
+    self.radius = radius;
+    self.softness = softness;
+  }
+
+  pub fn intensity(&self) -> f32 { self.intensity }
+
+  pub fn radius(&self) -> (f32, f32) { (self.radius, self.softness) }
+}
+
+impl Effect for Vignette {
+  fn apply(&self, pass: &EffectPass) -> Result<()> {
+    draw(&self.program, shaders::fragment::vignette, pass.context(), &[("u_input0", input(pass)?)], &[
+      ("u_intensity", UniformValue::Float(self.intensity)),
+      ("u_radius", UniformValue::Float(self.radius)),
+      ("u_softness", UniformValue::Float(self.softness)),
+    ])
+  }
+}
+
+/// Glow around bright areas
+///
+/// The colors brighter than the threshold are blurred at half resolution and
+/// added back to the image. With an RGBA8 scene target colors do not exceed
+/// 1, so the threshold must be lower for any area to glow.
+pub struct Bloom {
+  threshold: f32,
+  intensity: f32,
+  iterations: u32,
+  targets: RefCell<Vec<target::RenderTarget>>,
+  threshold_program: RefCell<Option<FullScreen>>,
+  blur_program: RefCell<Option<FullScreen>>,
+  composite_program: RefCell<Option<FullScreen>>,
+}
+
+impl Default for Bloom {
+  fn default() -> Self {
+    Bloom {
+      threshold: 0.8, intensity: 0.6, iterations: 2,
+      targets: RefCell::new(Vec::new()),
+      threshold_program: RefCell::new(None),
+      blur_program: RefCell::new(None),
+      composite_program: RefCell::new(None),
+    }
+  }
+}
+
+impl Bloom {
+  pub fn new() -> Self { Bloom::default() }
+
+  /// Set the brightness above which colors glow, 0.8 by default
+  pub fn set_threshold(&mut self, threshold: f32) { self.threshold = threshold; }
+
+  /// Set the factor of the glow added to the image, 0.6 by default
+  pub fn set_intensity(&mut self, intensity: f32) { self.intensity = intensity; }
+
+  /// Set the number of horizontal and vertical blur passes, widening the glow, 2 by default
+  pub fn set_iterations(&mut self, iterations: u32) { self.iterations = iterations; }
+
+  pub fn threshold(&self) -> f32 { self.threshold }
+
+  pub fn intensity(&self) -> f32 { self.intensity }
+
+  pub fn iterations(&self) -> u32 { self.iterations }
+}
+
+impl Effect for Bloom {
+  fn apply(&self, pass: &EffectPass) -> Result<()> {
+    let context = pass.context();
+    let color = input(pass)?;
+    let (width, height) = ((pass.width() / 2).max(1), (pass.height() / 2).max(1));
+    let format = if color.format().is_depth() { texture::TextureFormat::Rgba8 } else { color.format() };
+    let settings = target::RenderTargetSettings { color: Some(format), depth: None, samples: 0 };
+
+    let mut targets = self.targets.borrow_mut();
+    targets.retain(|t| t.settings() == &settings);
+    while targets.len() < 2 {
+      targets.push(target::RenderTarget::new(context, width, height, settings)?);
+    }
+    targets.iter_mut().try_for_each(|t| t.resize(width, height))?;
+    let texture = |i: usize| targets[i].color_texture().map(|t| t.as_ref()).ok_or("Bloom target has no color");
+
+    targets[0].bind();
+    draw(&self.threshold_program, shaders::fragment::bloom_threshold, context, &[("u_input0", color)], &[
+      ("u_threshold", UniformValue::Float(self.threshold)),
+    ])?;
+    for _ in 0..self.iterations {
+      for (from, to, horizontal) in [(0, 1, 1), (1, 0, 0)] {
+        targets[to].bind();
+        draw(&self.blur_program, shaders::fragment::gaussian_blur, context, &[("u_input0", texture(from)?)], &[
+          ("u_horizontal", UniformValue::Int(horizontal)),
+        ])?;
+      }
+    }
+
+    pass.bind_output();
+    draw(&self.composite_program, shaders::fragment::bloom_composite, context, &[("u_input0", color), ("u_bloom", texture(0)?)], &[
+      ("u_intensity", UniformValue::Float(self.intensity)),
+    ])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use gl::{RecordingContext, Call};
+
+  #[test]
+  fn bloom_blurs_at_half_resolution() {
+    let recording = Rc::new(RecordingContext::new(64, 32));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let scene = target::RenderTarget::new(&context, 64, 32, target::RenderTargetSettings::default()).unwrap();
+    let mut bloom = Bloom::new();
+    bloom.set_iterations(3);
+    let mut chain = Chain::new();
+    chain.with_effect("bloom".to_string(), Some(bloom));
+
+    recording.take_calls();
+    chain.apply(&context, &scene, None).unwrap();
+    let calls = recording.calls();
+    let draws = calls.iter().filter(|c| matches!(c, Call::DrawArrays { count: 3, .. })).count();
+    assert_eq!(draws, 1 + 2 * 3 + 1);
+    assert!(calls.contains(&Call::Viewport { x: 0, y: 0, width: 32, height: 16 }));
+    assert_eq!(calls.last(), Some(&Call::Enable(GL::DEPTH_TEST)));
+    assert_eq!(recording.state().framebuffer, None);
+  }
+
+  #[test]
+  fn shader_effect_binds_inputs_in_order() {
+    let recording = Rc::new(RecordingContext::new(16, 16));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let scene = target::RenderTarget::new(&context, 16, 16, target::RenderTargetSettings::default()).unwrap();
+    let mut effect = ShaderEffect::new("custom").with_inputs(vec![DEPTH.to_string(), COLOR.to_string()]);
+    effect.set_uniform("u_amount", UniformValue::Float(0.5));
+    effect.set_uniform("u_amount", UniformValue::Float(0.25));
+    let mut chain = Chain::new();
+    chain.with_effect("custom".to_string(), Some(effect));
+    chain.apply(&context, &scene, None).unwrap();
+
+    assert_eq!(recording.uniform("u_input0"), Some(UniformValue::Int(0)));
+    assert_eq!(recording.uniform("u_input1"), Some(UniformValue::Int(1)));
+    assert_eq!(recording.uniform("u_amount"), Some(UniformValue::Float(0.25)));
+    let depth = scene.depth_texture().unwrap().texture().0.id();
+    assert_eq!(recording.state().textures.get(&0).copied(), depth);
+  }
+}
@@ -0,0 +1,248 @@
+use super::{Result, Error, gl, programs, shaders, texture, target};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as GL;
+use gl::GlContext;
+
+mod effects; pub use effects::{ShaderEffect, Fxaa, ToneMapping, ToneMappingOperator, Gamma, Vignette, Bloom};
+
+/// Name of the scene color texture, also written by effects modifying the colors
+pub const COLOR: &str = "color";
+/// Name of the scene depth texture
+pub const DEPTH: &str = "depth";
+
+/// Full-screen effect of a post-processing [`Chain`]
+///
+/// An effect reads named textures and writes one named texture. Chains start
+/// with the scene [`COLOR`] and [`DEPTH`] textures, and each effect output
+/// replaces the texture of the same name for the following effects. The
+/// defaults read and write [`COLOR`].
+pub trait Effect {
+  /// Names of the textures read, handed to [`apply`](Effect::apply) in this order
+  fn inputs(&self) -> Vec<&str> { vec![COLOR] }
+
+  /// Name of the texture written
+  fn output(&self) -> &str { COLOR }
+
+  /// Draw the effect into the output of `pass`, bound on entry
+  ///
+  /// Effects drawing into intermediate render targets first bind the output
+  /// again with [`EffectPass::bind_output`] before their last draw.
+  fn apply(&self, pass: &EffectPass) -> Result<()>;
+}
+
+/// Inputs and output of an [`Effect`] in a [`Chain`]
+pub struct EffectPass<'a> {
+  context: &'a Rc<dyn GlContext>,
+  inputs: Vec<Rc<texture::Texture2D>>,
+  output: Option<&'a target::RenderTarget>,
+  width: u32,
+  height: u32,
+}
+
+impl EffectPass<'_> {
+  pub fn context(&self) -> &Rc<dyn GlContext> { self.context }
+
+  /// Texture of the `index`-th input declared by [`Effect::inputs`]
+  pub fn input(&self, index: usize) -> Option<&texture::Texture2D> {
+    self.inputs.get(index).map(|t| t.as_ref())
+  }
+
+  /// Width of the output in pixels
+  pub fn width(&self) -> u32 { self.width }
+
+  /// Height of the output in pixels
+  pub fn height(&self) -> u32 { self.height }
+
+  /// Bind the output framebuffer and set the viewport to cover it
+  pub fn bind_output(&self) {
+    match self.output {
+      Some(target) => target.bind(),
+      None => {
+        self.context.bind_framebuffer(GL::FRAMEBUFFER, None);
+        self.context.viewport(0, 0, self.width as i32, self.height as i32);
+      },
+    }
+  }
+}
+
+/// Ordered post-processing effects applied to a rendered scene
+///
+/// Each effect but the last draws into an intermediate render target with
+/// the color format of the scene, and the last one into the chain output.
+/// Effects are full-screen passes: the depth test is disabled while they
+/// draw, and enabled again on return.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rc::Rc;
+/// use j_webgl::{gl::{GlContext, RecordingContext}, post, target::{RenderTarget, RenderTargetSettings}};
+///
+/// # fn example() -> j_webgl::Result<()> {
+/// let context: Rc<dyn GlContext> = Rc::new(RecordingContext::new(640, 480));
+/// let mut chain = post::Chain::new();
+/// chain.with_effect("tone mapping".to_string(), Some(post::ToneMapping::new(post::ToneMappingOperator::Aces)));
+/// chain.with_effect("fxaa".to_string(), Some(post::Fxaa::new()));
+///
+/// let scene = RenderTarget::new(&context, 640, 480, RenderTargetSettings::default())?;
+/// // ... render into the scene target ...
+/// chain.apply(&context, &scene, None)?;
+/// # Ok(())
+/// # }
+/// # example().unwrap();
+/// ```
+#[derive(Default)]
+pub struct Chain {
+  effects: Vec<(String, Box<dyn Effect>)>,
+  targets: RefCell<Vec<target::RenderTarget>>,
+}
+
+impl Chain {
+  pub fn new() -> Self { Chain::default() }
+
+  /// Add, update, or remove an effect
+  ///
+  /// New effects are appended to the chain, while updated effects keep their
+  /// position. `None` removes the effect with the given `id`.
+  pub fn with_effect<E: Effect + 'static>(&mut self, id: String, effect: Option<E>) {
+    if let Some(e) = effect {
+      if let Some(i) = self.effects.iter().position(|(s, _)| s.eq(&id)) {
+        self.effects[i].1 = Box::new(e);
+      } else {
+        self.effects.push((id, Box::new(e)));
+      }
+    } else {
+      self.effects.retain(|(s, _)| s.ne(&id));
+    }
+  }
+
+  /// Returns an effect, if in the chain
+  pub fn effect(&self, id: &str) -> Option<&dyn Effect> {
+    self.effects.iter().find(|(s, _)| s.eq(id)).map(|(_, e)| e.as_ref())
+  }
+
+  /// Identifiers of the effects, in the order they are applied
+  pub fn ids(&self) -> impl Iterator<Item = &str> {
+    self.effects.iter().map(|(s, _)| s.as_str())
+  }
+
+  pub fn is_empty(&self) -> bool { self.effects.is_empty() }
+
+  /// Apply the effects to the textures of `scene`, drawing the last one into
+  /// `output`, or into the drawing buffer if `None`
+  ///
+  /// Multisampled scene targets must be resolved beforehand. The drawing
+  /// buffer is bound on return.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if an effect reads a texture that neither the scene
+  /// nor an earlier effect provides, or if an effect fails.
+  pub fn apply(&self, context: &Rc<dyn GlContext>, scene: &target::RenderTarget, output: Option<&target::RenderTarget>) -> Result<()> {
+    let (width, height) = output.map(|t| (t.width(), t.height())).unwrap_or_else(|| {
+      (context.drawing_buffer_width().max(1) as u32, context.drawing_buffer_height().max(1) as u32)
+    });
+    let mut textures: HashMap<String, Rc<texture::Texture2D>> = HashMap::new();
+    if let Some(color) = scene.color_texture() { textures.insert(COLOR.to_string(), color.clone()); }
+    if let Some(depth) = scene.depth_texture() { textures.insert(DEPTH.to_string(), depth.clone()); }
+
+    let settings = target::RenderTargetSettings {
+      color: scene.settings().color.or(Some(texture::TextureFormat::Rgba8)), depth: None, samples: 0,
+    };
+    let count = self.effects.len().saturating_sub(1);
+    let mut targets = self.targets.borrow_mut();
+    targets.retain(|t| t.settings() == &settings);
+    targets.truncate(count);
+    while targets.len() < count {
+      targets.push(target::RenderTarget::new(context, width, height, settings)?);
+    }
+    targets.iter_mut().try_for_each(|t| t.resize(width, height))?;
+
+    context.disable(GL::DEPTH_TEST);
+    let result = self.effects.iter().enumerate().try_for_each(|(i, (id, effect))| {
+      let inputs = effect.inputs().into_iter()
+        .map(|name| textures.get(name).cloned()
+          .ok_or_else(|| Error::Misc(format!("Effect {id} reads texture {name}, written by no earlier effect"))))
+        .collect::<Result<Vec<_>>>()?;
+      let target = targets.get(i);
+      let pass = EffectPass { context, inputs, output: target.or(output), width, height };
+      pass.bind_output();
+      effect.apply(&pass)?;
+      if let Some(color) = target.and_then(|t| t.color_texture()) {
+        textures.insert(effect.output().to_string(), color.clone());
+      }
+      Ok(())
+    });
+    if let Some(output) = output { output.resolve(); }
+    context.bind_framebuffer(GL::FRAMEBUFFER, None);
+    context.enable(GL::DEPTH_TEST);
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use gl::{RecordingContext, Call, UniformValue};
+
+  /// Effect reading and writing arbitrary textures, drawing nothing
+  struct Named(Vec<&'static str>, &'static str);
+
+  impl Effect for Named {
+    fn inputs(&self) -> Vec<&str> { self.0.clone() }
+    fn output(&self) -> &str { self.1 }
+    fn apply(&self, _pass: &EffectPass) -> Result<()> { Ok(()) }
+  }
+
+  #[test]
+  fn effects_draw_into_intermediate_targets_then_output() {
+    let recording = Rc::new(RecordingContext::new(64, 32));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let scene = target::RenderTarget::new(&context, 64, 32, target::RenderTargetSettings::default()).unwrap();
+    let mut chain = Chain::new();
+    chain.with_effect("tone".to_string(), Some(ToneMapping::new(ToneMappingOperator::Reinhard)));
+    chain.with_effect("vignette".to_string(), Some(Vignette::new()));
+    chain.with_effect("gamma".to_string(), Some(Gamma::new()));
+    let mut tone = ToneMapping::new(ToneMappingOperator::Aces);
+    tone.set_exposure(2.0);
+    chain.with_effect("tone".to_string(), Some(tone));
+    chain.with_effect::<Vignette>("vignette".to_string(), None);
+    assert_eq!(chain.ids().collect::<Vec<_>>(), vec!["tone", "gamma"]);
+
+    recording.take_calls();
+    chain.apply(&context, &scene, None).unwrap();
+    let calls = recording.calls();
+    let draws: Vec<usize> = calls.iter().enumerate()
+      .filter(|(_, c)| matches!(c, Call::DrawArrays { count: 3, .. })).map(|(i, _)| i).collect();
+    assert_eq!(draws.len(), 2);
+    let Some(Call::CreateFramebuffer(intermediate)) = calls.iter().find(|c| matches!(c, Call::CreateFramebuffer(_))).cloned() else { panic!() };
+    let bound = calls[..draws[0]].iter().rposition(|c| matches!(c, Call::BindFramebuffer { framebuffer: Some(_), .. }));
+    assert_eq!(calls[bound.unwrap()], Call::BindFramebuffer { target: GL::FRAMEBUFFER, framebuffer: Some(intermediate) });
+    assert!(calls[draws[0]..draws[1]].contains(&Call::BindFramebuffer { target: GL::FRAMEBUFFER, framebuffer: None }));
+    assert_eq!(recording.uniform("u_gamma"), Some(UniformValue::Float(2.2)));
+    assert!(calls.iter().any(|c| matches!(c, Call::Uniform { name, value: UniformValue::Int(1), .. } if name == "u_operator")));
+    assert!(calls.iter().any(|c| matches!(c, Call::Uniform { name, value: UniformValue::Float(2.0), .. } if name == "u_exposure")));
+    assert_eq!(recording.state().viewport, [0, 0, 64, 32]);
+
+    recording.take_calls();
+    chain.apply(&context, &scene, None).unwrap();
+    assert!(!recording.calls().iter().any(|c| matches!(c, Call::CreateFramebuffer(_) | Call::CreateProgram(_))), "targets and programs are reused");
+  }
+
+  #[test]
+  fn inputs_resolve_by_name() {
+    let context: Rc<dyn GlContext> = Rc::new(RecordingContext::new(16, 16));
+    let scene = target::RenderTarget::new(&context, 16, 16, target::RenderTargetSettings::default()).unwrap();
+    let mut chain = Chain::new();
+    chain.with_effect("edges".to_string(), Some(Named(vec![DEPTH], "edges")));
+    chain.with_effect("outline".to_string(), Some(Named(vec![COLOR, "edges"], COLOR)));
+    assert!(chain.apply(&context, &scene, None).is_ok());
+
+    chain.with_effect::<Named>("edges".to_string(), None);
+    let error = chain.apply(&context, &scene, None).unwrap_err();
+    assert!(error.to_string().contains("outline reads texture edges"));
+  }
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+use std::rc::Rc;
+use gl::{GlContext, Program};
+
+/// Program writing the depth of a mesh only, for shadow passes
+pub struct Depth {
+  context: Rc<dyn GlContext>,
+  program: Program,
+  mesh_buffers: Option<mesh::MeshBuffers>,
+}
+
+impl Depth {
+  pub fn new(context: &Rc<dyn GlContext>) -> Result<Self> {
+    let context = context.clone();
+    let program = utils::compile_program(
+      context.as_ref(),
+      shaders::vertex::position_matrix()?,
+      shaders::fragment::depth_only()?,
+    )?;
+    Ok(Depth { context, program, mesh_buffers: None })
+  }
+
+  pub fn with_mvp(&mut self, mvp: &[f32]) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_matrix");
+    context.uniform_matrix4fv_with_f32_array(location.as_ref(), false, mvp);
+    Ok(())
+  }
+
+  pub fn with_mesh(&mut self, mesh: &mesh::Mesh) -> Result<()> {
+    self.mesh_buffers = Some(mesh::MeshBuffers::new(&self.context, &self.program, mesh)?);
+    Ok(())
+  }
+
+  pub fn draw(&self) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    if let Some(mesh_buffers) = self.mesh_buffers.as_ref() {
+      mesh_buffers.draw()?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,59 @@
+use super::*;
+
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as GL;
+use gl::{GlContext, Program, UniformValue};
+
+/// Program drawing a full-screen triangle with a fragment shader, for
+/// post-processing passes
+///
+/// The fragment shader receives the texture coordinates of the fragment in
+/// `in vec2 v_uv`, see [`shaders::vertex::full_screen`].
+pub struct FullScreen {
+  context: Rc<dyn GlContext>,
+  program: Program,
+}
+
+impl FullScreen {
+  pub fn new(context: &Rc<dyn GlContext>, fragment_shader: &str) -> Result<Self> {
+    let context = context.clone();
+    let program = utils::compile_program(
+      context.as_ref(),
+      shaders::vertex::full_screen()?,
+      fragment_shader,
+    )?;
+    Ok(FullScreen { context, program })
+  }
+
+  /// Bind `texture` to texture unit `unit` and sampler uniform `name`
+  pub fn with_texture(&mut self, name: &str, unit: u32, texture: &texture::Texture2D) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    texture.bind(unit);
+    let location = context.get_uniform_location(&self.program, name);
+    context.uniform1i(location.as_ref(), unit.try_into()?);
+    Ok(())
+  }
+
+  pub fn with_uniform(&mut self, name: &str, value: &UniformValue) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, name);
+    match value {
+      UniformValue::Int(x) => context.uniform1i(location.as_ref(), *x),
+      UniformValue::Float(x) => context.uniform1f(location.as_ref(), *x),
+      UniformValue::Vec3(v) => context.uniform3fv_with_f32_array(location.as_ref(), v),
+      UniformValue::Vec4([x, y, z, w]) => context.uniform4f(location.as_ref(), *x, *y, *z, *w),
+      UniformValue::Mat4(m) => context.uniform_matrix4fv_with_f32_array(location.as_ref(), false, m),
+    }
+    Ok(())
+  }
+
+  pub fn draw(&self) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    context.bind_vertex_array(None);
+    context.draw_arrays(GL::TRIANGLES, 0, 3);
+    Ok(())
+  }
+}
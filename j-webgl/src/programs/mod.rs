@@ -1,3 +1,10 @@
-use super::{Result, shaders, utils};
+use super::{Result, Error, gl, shaders, utils, mesh, texture, structs, algebra, shadow};
 
 mod triangles_shaded; pub use triangles_shaded::TrianglesShaded;
+mod points; pub use points::Points;
+mod triangles_textured; pub use triangles_textured::TrianglesTextured;
+mod triangles_phong; pub use triangles_phong::TrianglesPhong;
+mod triangles_pbr; pub use triangles_pbr::TrianglesPbr;
+mod depth; pub use depth::Depth;
+mod object_id; pub use object_id::ObjectId;
+mod full_screen; pub use full_screen::FullScreen;
@@ -0,0 +1,65 @@
+use super::*;
+
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext;
+use gl::{GlContext, Program};
+
+/// Program drawing the vertices of a mesh as `POINTS` in their vertex color
+pub struct Points {
+  context: Rc<dyn GlContext>,
+  program: Program,
+  mesh_buffers: Option<mesh::MeshBuffers>,
+}
+
+impl Points {
+  pub fn new(context: &Rc<dyn GlContext>) -> Result<Self> {
+    let context = context.clone();
+    let program = utils::compile_program(
+      context.as_ref(),
+      shaders::vertex::matrix_position_color_point_size()?,
+      shaders::fragment::vertex_color_point()?,
+    )?;
+    Ok(Points { context, program, mesh_buffers: None })
+  }
+
+  pub fn with_mvp(&mut self, mvp: &[f32]) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_matrix");
+    context.uniform_matrix4fv_with_f32_array(location.as_ref(), false, mvp);
+    Ok(())
+  }
+
+  /// Set the point diameter in pixels, clamped by the implementation to its supported range
+  pub fn with_point_size(&mut self, size: f32) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_pointSize");
+    context.uniform1f(location.as_ref(), size);
+    Ok(())
+  }
+
+  /// Draw round points rather than squares
+  pub fn with_round(&mut self, round: bool) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_round");
+    context.uniform1i(location.as_ref(), round as i32);
+    Ok(())
+  }
+
+  /// Upload the positions and colors of a mesh, whose indices, if any, select the points drawn
+  pub fn with_mesh(&mut self, mesh: &mesh::Mesh) -> Result<()> {
+    self.mesh_buffers = Some(mesh::MeshBuffers::new(&self.context, &self.program, mesh)?);
+    Ok(())
+  }
+
+  pub fn draw(&self) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    if let Some(mesh_buffers) = self.mesh_buffers.as_ref() {
+      mesh_buffers.draw_mode(WebGl2RenderingContext::POINTS)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,161 @@
+use super::*;
+
+use std::rc::Rc;
+use gl::{GlContext, Program};
+use structs::material::PbrMaterial;
+
+/// Texture maps of [`PbrMaterial`], as `(sampler uniform, flag uniform)` in texture unit order
+const MAPS: [(&str, &str); 5] = [
+  ("u_baseColorTexture", "u_hasBaseColorTexture"),
+  ("u_metallicRoughnessTexture", "u_hasMetallicRoughnessTexture"),
+  ("u_normalTexture", "u_hasNormalTexture"),
+  ("u_occlusionTexture", "u_hasOcclusionTexture"),
+  ("u_emissiveTexture", "u_hasEmissiveTexture"),
+];
+
+/// Program drawing a mesh with a physically based metallic-roughness material
+///
+/// Shading follows the Cook-Torrance model with a GGX distribution, lit by
+/// the same lights as [`TrianglesPhong`](super::TrianglesPhong). Light
+/// intensities are radiometric: a white directional light of intensity `π`
+/// lights a white dielectric facing it to full brightness. The mesh must have
+/// normals and texture coordinates. Material maps are bound to texture units
+/// 0 to 4 when drawing.
+pub struct TrianglesPbr {
+  context: Rc<dyn GlContext>,
+  program: Program,
+  mesh_buffers: Option<mesh::MeshBuffers>,
+  maps: [Option<texture::Texture2D>; 5],
+}
+
+impl TrianglesPbr {
+  pub fn new(context: &Rc<dyn GlContext>) -> Result<Self> {
+    let context = context.clone();
+    let program = utils::compile_program(
+      context.as_ref(),
+      shaders::vertex::matrix_model_position_normal_texcoord()?,
+      shaders::fragment::pbr()?,
+    )?;
+    let mut program = TrianglesPbr { context, program, mesh_buffers: None, maps: Default::default() };
+    program.with_material(&PbrMaterial::default())?;
+    Ok(program)
+  }
+
+  pub fn with_mvp(&mut self, mvp: &[f32]) -> Result<()> {
+    self.uniform_matrix("u_matrix", mvp);
+    Ok(())
+  }
+
+  /// Set the model transform, used to light positions in world space
+  pub fn with_model(&mut self, model: &[f32]) -> Result<()> {
+    self.uniform_matrix("u_model", model);
+    Ok(())
+  }
+
+  pub fn with_normal_matrix(&mut self, matrix: &[f32]) -> Result<()> {
+    self.uniform_matrix("u_normalMatrix", matrix);
+    Ok(())
+  }
+
+  /// Set the camera position in world space
+  pub fn with_eye_position(&mut self, eye: &algebra::Point3) -> Result<()> {
+    self.uniform3("u_eyePosition", [eye.x(), eye.y(), eye.z()]);
+    Ok(())
+  }
+
+  /// Set the ambient color and the lights, see [`TrianglesPhong::with_lights`](super::TrianglesPhong::with_lights)
+  pub fn with_lights(&mut self, ambient: [f32; 3], lights: &[structs::light::Light]) -> Result<()> {
+    self.context.use_program(Some(&self.program));
+    utils::set_lights(self.context.as_ref(), &self.program, ambient, lights)
+  }
+
+  /// Set the material factors and upload its maps, replacing the previous ones
+  ///
+  /// Maps are sampled with trilinear filtering and repeated outside `[0, 1]`.
+  pub fn with_material(&mut self, material: &PbrMaterial) -> Result<()> {
+    self.with_factors(material)?;
+    let images = [
+      &material.base_color_map,
+      &material.metallic_roughness_map,
+      &material.normal_map,
+      &material.occlusion_map,
+      &material.emissive_map,
+    ];
+    for (unit, image) in images.into_iter().enumerate() {
+      self.maps[unit] = match image {
+        Some(image) => {
+          let mut texture = texture::Texture2D::from_image(&self.context, image)?;
+          texture.generate_mipmaps()?;
+          texture.set_sampler(texture::Sampler::trilinear())?;
+          Some(texture)
+        },
+        None => None,
+      };
+      let context = &self.context;
+      context.use_program(Some(&self.program));
+      let (sampler, flag) = MAPS[unit];
+      let location = context.get_uniform_location(&self.program, sampler);
+      context.uniform1i(location.as_ref(), unit as i32);
+      let location = context.get_uniform_location(&self.program, flag);
+      context.uniform1i(location.as_ref(), image.is_some() as i32);
+    }
+    Ok(())
+  }
+
+  /// Set the material factors only, keeping the uploaded maps
+  pub fn with_factors(&mut self, material: &PbrMaterial) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let [red, green, blue, alpha] = material.base_color;
+    let location = context.get_uniform_location(&self.program, "u_baseColor");
+    context.uniform4f(location.as_ref(), red, green, blue, alpha);
+    for (name, value) in [
+      ("u_metallic", material.metallic),
+      ("u_roughness", material.roughness),
+      ("u_normalScale", material.normal_scale),
+      ("u_occlusionStrength", material.occlusion_strength),
+    ] {
+      let location = context.get_uniform_location(&self.program, name);
+      context.uniform1f(location.as_ref(), value);
+    }
+    self.uniform3("u_emissive", material.emissive);
+    Ok(())
+  }
+
+  /// Upload a mesh with normals and texture coordinates
+  pub fn with_mesh(&mut self, mesh: &mesh::Mesh) -> Result<()> {
+    if mesh.uvs().is_none() {
+      return Err(Error::InvalidMesh("PBR meshes require texture coordinates".to_string()));
+    }
+    self.mesh_buffers = Some(mesh::MeshBuffers::new(&self.context, &self.program, mesh)?);
+    Ok(())
+  }
+
+  pub fn draw(&self) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    for (unit, map) in self.maps.iter().enumerate() {
+      if let Some(texture) = map {
+        texture.bind(unit as u32);
+      }
+    }
+    if let Some(mesh_buffers) = self.mesh_buffers.as_ref() {
+      mesh_buffers.draw()?;
+    }
+    Ok(())
+  }
+
+  fn uniform_matrix(&self, name: &str, matrix: &[f32]) {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, name);
+    context.uniform_matrix4fv_with_f32_array(location.as_ref(), false, matrix);
+  }
+
+  fn uniform3(&self, name: &str, vector: [f32; 3]) {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, name);
+    context.uniform3fv_with_f32_array(location.as_ref(), &vector);
+  }
+}
@@ -0,0 +1,151 @@
+use super::*;
+
+use std::rc::Rc;
+use gl::{GlContext, Program};
+use structs::light::Light;
+use web_sys::WebGl2RenderingContext as GL;
+
+/// Program drawing a mesh in a single color with Phong or Blinn-Phong
+/// lighting from ambient, directional, point and spot lights
+///
+/// The program created with [`new_shadowed`](TrianglesPhong::new_shadowed)
+/// also occludes the light casting the shadow map set with
+/// [`with_shadow`](TrianglesPhong::with_shadow).
+pub struct TrianglesPhong {
+  context: Rc<dyn GlContext>,
+  program: Program,
+  mesh_buffers: Option<mesh::MeshBuffers>,
+}
+
+impl TrianglesPhong {
+  pub fn new(context: &Rc<dyn GlContext>) -> Result<Self> {
+    TrianglesPhong::with_fragment_shader(context, shaders::fragment::phong()?)
+  }
+
+  /// Create the shadow receiving program
+  pub fn new_shadowed(context: &Rc<dyn GlContext>) -> Result<Self> {
+    let program = TrianglesPhong::with_fragment_shader(context, shaders::fragment::phong_shadow()?)?;
+    program.uniform1i("u_shadowLight", -1);
+    Ok(program)
+  }
+
+  fn with_fragment_shader(context: &Rc<dyn GlContext>, fragment_shader: String) -> Result<Self> {
+    let context = context.clone();
+    let program = utils::compile_program(
+      context.as_ref(),
+      shaders::vertex::matrix_model_position_normal()?,
+      fragment_shader,
+    )?;
+    let mut program = TrianglesPhong { context, program, mesh_buffers: None };
+    program.with_specular([1.0, 1.0, 1.0], 32.0)?;
+    program.with_blinn(true)?;
+    Ok(program)
+  }
+
+  pub fn with_mvp(&mut self, mvp: &[f32]) -> Result<()> {
+    self.uniform_matrix("u_matrix", mvp);
+    Ok(())
+  }
+
+  /// Set the model transform, used to light positions in world space
+  pub fn with_model(&mut self, model: &[f32]) -> Result<()> {
+    self.uniform_matrix("u_model", model);
+    Ok(())
+  }
+
+  pub fn with_normal_matrix(&mut self, matrix: &[f32]) -> Result<()> {
+    self.uniform_matrix("u_normalMatrix", matrix);
+    Ok(())
+  }
+
+  /// Set the camera position in world space, from which specular highlights are seen
+  pub fn with_eye_position(&mut self, eye: &algebra::Point3) -> Result<()> {
+    self.uniform3("u_eyePosition", [eye.x(), eye.y(), eye.z()]);
+    Ok(())
+  }
+
+  /// Set the diffuse and ambient color
+  pub fn with_color(&mut self, red: f32, green: f32, blue: f32, alpha: f32) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_color");
+    context.uniform4f(location.as_ref(), red, green, blue, alpha);
+    Ok(())
+  }
+
+  /// Set the specular color and exponent, white and 32 by default
+  pub fn with_specular(&mut self, color: [f32; 3], shininess: f32) -> Result<()> {
+    self.uniform3("u_specularColor", color);
+    let context = &self.context;
+    let location = context.get_uniform_location(&self.program, "u_shininess");
+    context.uniform1f(location.as_ref(), shininess);
+    Ok(())
+  }
+
+  /// Use the Blinn-Phong half vector, the default, rather than the Phong reflection vector
+  pub fn with_blinn(&mut self, blinn: bool) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_blinn");
+    context.uniform1i(location.as_ref(), blinn as i32);
+    Ok(())
+  }
+
+  /// Set the ambient color and the lights, see [`utils::set_lights`]
+  pub fn with_lights(&mut self, ambient: [f32; 3], lights: &[Light]) -> Result<()> {
+    self.context.use_program(Some(&self.program));
+    utils::set_lights(self.context.as_ref(), &self.program, ambient, lights)
+  }
+
+  /// Bind the shadow map to texture unit 0 and set its uniforms, the light
+  /// index referring to the lights set with [`with_lights`](TrianglesPhong::with_lights)
+  ///
+  /// Only the program created with [`new_shadowed`](TrianglesPhong::new_shadowed) uses the shadow map.
+  pub fn with_shadow(&mut self, shadow: &shadow::FrameShadow) -> Result<()> {
+    let context = &self.context;
+    context.active_texture(GL::TEXTURE0);
+    context.bind_texture(GL::TEXTURE_2D, Some(&shadow.texture));
+    self.uniform1i("u_shadowMap", 0);
+    self.uniform_matrix("u_shadowMatrix", shadow.matrix.as_slice());
+    self.uniform1i("u_shadowLight", shadow.light.try_into()?);
+    self.uniform1i("u_pcfRadius", shadow.pcf_radius.try_into()?);
+    let location = context.get_uniform_location(&self.program, "u_shadowBias");
+    context.uniform1f(location.as_ref(), shadow.bias);
+    Ok(())
+  }
+
+  pub fn with_mesh(&mut self, mesh: &mesh::Mesh) -> Result<()> {
+    self.mesh_buffers = Some(mesh::MeshBuffers::new(&self.context, &self.program, mesh)?);
+    Ok(())
+  }
+
+  pub fn draw(&self) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    if let Some(mesh_buffers) = self.mesh_buffers.as_ref() {
+      mesh_buffers.draw()?;
+    }
+    Ok(())
+  }
+
+  fn uniform_matrix(&self, name: &str, matrix: &[f32]) {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, name);
+    context.uniform_matrix4fv_with_f32_array(location.as_ref(), false, matrix);
+  }
+
+  fn uniform1i(&self, name: &str, value: i32) {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, name);
+    context.uniform1i(location.as_ref(), value);
+  }
+
+  fn uniform3(&self, name: &str, vector: [f32; 3]) {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, name);
+    context.uniform3fv_with_f32_array(location.as_ref(), &vector);
+  }
+}
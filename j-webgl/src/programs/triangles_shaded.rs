@@ -1,21 +1,24 @@
 use super::*;
 
-use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlVertexArrayObject, WebGlBuffer, };
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext;
+use gl::{GlContext, Program, VertexArray, Buffer};
 
 pub struct TrianglesShaded {
-  context: WebGl2RenderingContext,
-  program: WebGlProgram,
-  vertex_array_object: Option<WebGlVertexArrayObject>,
-  position_buffer: Option<WebGlBuffer>,
+  context: Rc<dyn GlContext>,
+  program: Program,
+  vertex_array_object: Option<VertexArray>,
+  position_buffer: Option<Buffer>,
   n_triangles: Option<usize>,
-  normal_buffer: Option<WebGlBuffer>,
+  normal_buffer: Option<Buffer>,
+  mesh_buffers: Option<mesh::MeshBuffers>,
 }
 
 impl TrianglesShaded {
-  pub fn new(context: &WebGl2RenderingContext) -> Result<Self> {
+  pub fn new(context: &Rc<dyn GlContext>) -> Result<Self> {
     let context = context.clone();
     let program = utils::compile_program(
-      &context,
+      context.as_ref(),
       shaders::vertex::matrix_position_normal()?,
       shaders::fragment::color_light()?,
     )?;
@@ -26,6 +29,7 @@ impl TrianglesShaded {
       position_buffer: None,
       n_triangles: None,
       normal_buffer: None,
+      mesh_buffers: None,
     })
   }
 
@@ -37,6 +41,14 @@ impl TrianglesShaded {
     Ok(())
   }
 
+  pub fn with_normal_matrix(&mut self, matrix: &[f32]) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_normalMatrix");
+    context.uniform_matrix4fv_with_f32_array(location.as_ref(), false, matrix);
+    Ok(())
+  }
+
   pub fn with_color(&mut self, red: f32, green: f32, blue: f32, alpha: f32) -> Result<()> {
     let context = &self.context;
     context.use_program(Some(&self.program));
@@ -62,14 +74,11 @@ impl TrianglesShaded {
     let location = context.get_attrib_location(&self.program, "a_normal");
     context.enable_vertex_attrib_array(location as u32);
     context.bind_buffer(WebGl2RenderingContext::ARRAY_BUFFER, self.normal_buffer.as_ref());
-    unsafe {
-      let array = js_sys::Float32Array::view(normals);
-      context.buffer_data_with_array_buffer_view(
-        WebGl2RenderingContext::ARRAY_BUFFER,
-        &array,
-        WebGl2RenderingContext::STATIC_DRAW
-      );
-    }
+    context.buffer_data_with_f32_array(
+      WebGl2RenderingContext::ARRAY_BUFFER,
+      normals,
+      WebGl2RenderingContext::STATIC_DRAW
+    );
     context.vertex_attrib_pointer_with_i32(location as u32,
       3, // size
       WebGl2RenderingContext::FLOAT,
@@ -95,14 +104,11 @@ impl TrianglesShaded {
     context.bind_vertex_array(self.vertex_array_object.as_ref());
     context.enable_vertex_attrib_array(location as u32);
     context.bind_buffer(web_sys::WebGl2RenderingContext::ARRAY_BUFFER, self.position_buffer.as_ref());
-    unsafe {
-          let vertices_array = js_sys::Float32Array::view(values);
-          context.buffer_data_with_array_buffer_view(
-                web_sys::WebGl2RenderingContext::ARRAY_BUFFER,
-                &vertices_array,
-                web_sys::WebGl2RenderingContext::STATIC_DRAW,
-          );
-    }
+    context.buffer_data_with_f32_array(
+          web_sys::WebGl2RenderingContext::ARRAY_BUFFER,
+          values,
+          web_sys::WebGl2RenderingContext::STATIC_DRAW,
+    );
     context.vertex_attrib_pointer_with_i32(location as u32,
           3, // size
           web_sys::WebGl2RenderingContext::FLOAT,
@@ -114,9 +120,19 @@ impl TrianglesShaded {
     Ok(())
   }
 
+  /// Upload an indexed mesh, with normals, drawn instead of the triangles set
+  /// with [`with_position`](Self::with_position)
+  pub fn with_mesh(&mut self, mesh: &mesh::Mesh) -> Result<()> {
+    self.mesh_buffers = Some(mesh::MeshBuffers::new(&self.context, &self.program, mesh)?);
+    Ok(())
+  }
+
   pub fn draw(&self) -> Result<()> {
     let context = &self.context;
     context.use_program(Some(&self.program));
+    if let Some(mesh_buffers) = self.mesh_buffers.as_ref() {
+      return mesh_buffers.draw();
+    }
     context.draw_arrays(
       web_sys::WebGl2RenderingContext::TRIANGLES,
       0, // offset
@@ -0,0 +1,92 @@
+use super::*;
+
+use std::rc::Rc;
+use gl::{GlContext, Program};
+
+/// Program drawing a mesh textured, tinted and lit by a directional light
+///
+/// The mesh must have texture coordinates, read from `a_texcoord`. The
+/// texture is bound to texture unit 0 when drawing.
+pub struct TrianglesTextured {
+  context: Rc<dyn GlContext>,
+  program: Program,
+  mesh_buffers: Option<mesh::MeshBuffers>,
+  texture: Option<Rc<texture::Texture2D>>,
+}
+
+impl TrianglesTextured {
+  pub fn new(context: &Rc<dyn GlContext>) -> Result<Self> {
+    let context = context.clone();
+    let program = utils::compile_program(
+      context.as_ref(),
+      shaders::vertex::matrix_position_normal_texcoord()?,
+      shaders::fragment::texture_light()?,
+    )?;
+    let mut program = TrianglesTextured { context, program, mesh_buffers: None, texture: None };
+    program.with_color(1.0, 1.0, 1.0, 1.0)?;
+    Ok(program)
+  }
+
+  pub fn with_mvp(&mut self, mvp: &[f32]) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_matrix");
+    context.uniform_matrix4fv_with_f32_array(location.as_ref(), false, mvp);
+    Ok(())
+  }
+
+  pub fn with_normal_matrix(&mut self, matrix: &[f32]) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_normalMatrix");
+    context.uniform_matrix4fv_with_f32_array(location.as_ref(), false, matrix);
+    Ok(())
+  }
+
+  /// Set the tint multiplied with the texture color, white by default
+  pub fn with_color(&mut self, red: f32, green: f32, blue: f32, alpha: f32) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_color");
+    context.uniform4f(location.as_ref(), red, green, blue, alpha);
+    Ok(())
+  }
+
+  pub fn with_reverse_light_direction(&mut self, vector: [f32; 3]) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_reverseLightDirection");
+    context.uniform3fv_with_f32_array(location.as_ref(), &vector);
+    Ok(())
+  }
+
+  pub fn with_texture(&mut self, texture: Rc<texture::Texture2D>) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    let location = context.get_uniform_location(&self.program, "u_texture");
+    context.uniform1i(location.as_ref(), 0);
+    self.texture = Some(texture);
+    Ok(())
+  }
+
+  /// Upload a mesh with normals and texture coordinates
+  pub fn with_mesh(&mut self, mesh: &mesh::Mesh) -> Result<()> {
+    if mesh.uvs().is_none() {
+      return Err(Error::InvalidMesh("textured meshes require texture coordinates".to_string()));
+    }
+    self.mesh_buffers = Some(mesh::MeshBuffers::new(&self.context, &self.program, mesh)?);
+    Ok(())
+  }
+
+  pub fn draw(&self) -> Result<()> {
+    let context = &self.context;
+    context.use_program(Some(&self.program));
+    if let Some(texture) = self.texture.as_ref() {
+      texture.bind(0);
+    }
+    if let Some(mesh_buffers) = self.mesh_buffers.as_ref() {
+      mesh_buffers.draw()?;
+    }
+    Ok(())
+  }
+}
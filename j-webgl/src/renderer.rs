@@ -1,11 +1,18 @@
-use super::{Error, Result, traits, };
+use super::{Error, Result, traits, gl, algebra, FrameContext, structs, shadow, target, post, shaders};
+use std::cell::{Cell, Ref, RefCell};
+use std::rc::Rc;
 use wasm_bindgen::JsCast;
+use web_sys::WebGl2RenderingContext as GL;
+
+/// Ambient light color of a new renderer
+const DEFAULT_AMBIENT: [f32; 3] = [0.1, 0.1, 0.1];
 
 /// Renderer for WebGL 2.0 rendering operations
 ///
 /// The `Renderer` manages a WebGL 2.0 context and a collection of renderable objects.
 /// It handles canvas resizing, viewport management, and orchestrates the rendering
-/// of all registered renderable objects.
+/// of all registered renderable objects. Each renderable carries a model transform,
+/// identity by default, placing it in the scene (see [`set_transform`](Renderer::set_transform)).
 ///
 /// # Example
 ///
@@ -30,9 +37,22 @@ use wasm_bindgen::JsCast;
 /// ```
 ///
 pub struct Renderer {
-    context: web_sys::WebGl2RenderingContext,
-    canvas: web_sys::HtmlCanvasElement,
-    renderables: Vec<(String, Box<dyn traits::Renderable>)>,
+    context: Rc<dyn gl::GlContext>,
+    canvas: Option<web_sys::HtmlCanvasElement>,
+    renderables: Vec<(String, algebra::Matrix4, Box<dyn traits::Renderable>)>,
+    lights: Vec<(String, structs::light::Light)>,
+    ambient: [f32; 3],
+    shadows: Option<shadow::ShadowSettings>,
+    shadow_map: RefCell<Option<shadow::ShadowMap>>,
+    render_targets: Vec<(String, RefCell<target::RenderTarget>)>,
+    effects: post::Chain,
+    post_settings: target::RenderTargetSettings,
+    post_scene: RefCell<Option<target::RenderTarget>>,
+    pixel_ratio: Cell<f64>,
+    pick_frame: RefCell<Option<FrameContext>>,
+    pick_target: RefCell<Option<target::RenderTarget>>,
+    rendered: Cell<usize>,
+    culled: Cell<usize>,
 }
 
 impl Renderer {
@@ -40,7 +60,7 @@ impl Renderer {
     ///
     /// This method manages renderable objects in the renderer's collection:
     /// - If `renderable` is `Some`, the object is added or updated (if an object
-    ///   with the same `id` already exists)
+    ///   with the same `id` already exists, keeping its model transform)
     /// - If `renderable` is `None`, the object with the given `id` is removed
     ///
     /// # Parameters
@@ -51,29 +71,202 @@ impl Renderer {
     ///
     /// ```rust
     /// # use j_webgl::{Renderer, Result, Renderable};
-    /// # fn example(mut renderer: Renderer, my_obj: impl Renderable + 'static) -> Result<()> {
+    /// # fn example<R: Renderable + 'static>(mut renderer: Renderer, my_obj: R) -> Result<()> {
     /// // Add a renderable
     /// renderer.with_renderable("cube".to_string(), Some(my_obj))?;
     ///
     /// // Remove it later
-    /// renderer.with_renderable("cube".to_string(), None)?;
+    /// renderer.with_renderable::<R>("cube".to_string(), None)?;
     /// # Ok(())
     /// # }
     /// ```
     pub fn with_renderable<R: traits::Renderable + 'static>(&mut self, id: String, renderable: Option<R>) -> Result<()> {
       if let Some(r) = renderable {
-        if let Some(i) = self.renderables.iter().position(|(s, _)| s.eq(&id)) {
-          self.renderables[i] = (id, Box::new(r));
+        if let Some(i) = self.renderables.iter().position(|(s, _, _)| s.eq(&id)) {
+          self.renderables[i].2 = Box::new(r);
         } else {
-          self.renderables.push((id, Box::new(r)));
+          self.renderables.push((id, algebra::Matrix4::identity(), Box::new(r)));
         }
       } else {
-        self.renderables.retain(|(s, _)| s.ne(&id));
+        self.renderables.retain(|(s, _, _)| s.ne(&id));
       }
 
       Ok(())
     }
 
+    /// Set the model transform of a renderable object
+    ///
+    /// The transform maps the object model space into world space. The renderer
+    /// passes each object a [`FrameContext`] whose MVP matrix is
+    /// `view_projection * model`, along with the matching normal matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no renderable object is registered with `id`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use j_webgl::{Renderer, Result, algebra::Matrix4};
+    /// # fn example(mut renderer: Renderer) -> Result<()> {
+    /// renderer.set_transform("cube", Matrix4::new_translation(1.0, 0.0, 0.0))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_transform(&mut self, id: &str, transform: algebra::Matrix4) -> Result<()> {
+      let (_, model, _) = self.renderables.iter_mut()
+        .find(|(s, _, _)| s.eq(id))
+        .ok_or_else(|| Error::Misc(format!("No renderable with id {id}")))?;
+      *model = transform;
+      Ok(())
+    }
+
+    /// Returns the model transform of a renderable object, if registered
+    pub fn transform(&self, id: &str) -> Option<&algebra::Matrix4> {
+      self.renderables.iter().find(|(s, _, _)| s.eq(id)).map(|(_, m, _)| m)
+    }
+
+    /// Add, update, or remove a light
+    ///
+    /// Lights are handed to every renderable through
+    /// [`FrameContext::lights`], in the order they were first added. Lit
+    /// renderables such as [`PhongMesh`](structs::renderable::PhongMesh) use them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedOperation`] when adding a light past
+    /// [`MAX_LIGHTS`](shaders::fragment::MAX_LIGHTS).
+    pub fn with_light(&mut self, id: String, light: Option<structs::light::Light>) -> Result<()> {
+      if let Some(l) = light {
+        if let Some(i) = self.lights.iter().position(|(s, _)| s.eq(&id)) {
+          self.lights[i].1 = l;
+        } else if self.lights.len() >= shaders::fragment::MAX_LIGHTS {
+          return Err(Error::UnsupportedOperation(
+            format!("light {id}, at most {} lights are supported", shaders::fragment::MAX_LIGHTS)
+          ));
+        } else {
+          self.lights.push((id, l));
+        }
+      } else {
+        self.lights.retain(|(s, _)| s.ne(&id));
+      }
+      Ok(())
+    }
+
+    /// Returns a light, if registered
+    pub fn light(&self, id: &str) -> Option<&structs::light::Light> {
+      self.lights.iter().find(|(s, _)| s.eq(id)).map(|(_, l)| l)
+    }
+
+    /// Set the ambient light color, a dim gray by default
+    pub fn set_ambient(&mut self, red: f32, green: f32, blue: f32) {
+      self.ambient = [red, green, blue];
+    }
+
+    /// Enable shadows with the given settings, or disable them with `None`
+    ///
+    /// When enabled, each [`render`](Renderer::render) starts with a shadow
+    /// pass rendering the depth of the renderables, see
+    /// [`Renderable::render_depth`](traits::Renderable::render_depth), as seen
+    /// from the first directional light set to cast shadows. The shadow map
+    /// is then handed to the color pass through [`FrameContext::shadow`].
+    pub fn set_shadows(&mut self, shadows: Option<shadow::ShadowSettings>) {
+      self.shadows = shadows;
+    }
+
+    /// Returns the shadow settings, if shadows are enabled
+    pub fn shadows(&self) -> Option<&shadow::ShadowSettings> {
+      self.shadows.as_ref()
+    }
+
+    /// Add, update, or remove a render target following the canvas size
+    ///
+    /// The target is created with the drawing buffer size and resized with
+    /// it by [`resize`](Renderer::resize). Render into it with
+    /// [`render_to`](Renderer::render_to) and sample its textures, see
+    /// [`RenderTarget::color_texture`](target::RenderTarget::color_texture).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target cannot be created, see [`target::RenderTarget::new`].
+    pub fn with_render_target(&mut self, id: String, settings: Option<target::RenderTargetSettings>) -> Result<()> {
+      if let Some(settings) = settings {
+        let (width, height) = self.drawing_buffer_size();
+        let render_target = RefCell::new(target::RenderTarget::new(&self.context, width, height, settings)?);
+        if let Some(i) = self.render_targets.iter().position(|(s, _)| s.eq(&id)) {
+          self.render_targets[i].1 = render_target;
+        } else {
+          self.render_targets.push((id, render_target));
+        }
+      } else {
+        self.render_targets.retain(|(s, _)| s.ne(&id));
+      }
+      Ok(())
+    }
+
+    /// Returns a render target, if registered
+    pub fn render_target(&self, id: &str) -> Option<Ref<'_, target::RenderTarget>> {
+      self.render_targets.iter().find(|(s, _)| s.eq(id)).map(|(_, t)| t.borrow())
+    }
+
+    /// Add, update, or remove a post-processing effect
+    ///
+    /// With effects, the renderables are rendered into a scene render target,
+    /// see [`set_post_settings`](Renderer::set_post_settings), and the
+    /// effects are applied in the order they were first added, the last one
+    /// drawing to the canvas. See [`post::Chain`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use j_webgl::{Renderer, Result, post};
+    /// # fn example(mut renderer: Renderer) -> Result<()> {
+    /// renderer.with_effect("bloom".to_string(), Some(post::Bloom::new()))?;
+    /// renderer.with_effect("fxaa".to_string(), Some(post::Fxaa::new()))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_effect<E: post::Effect + 'static>(&mut self, id: String, effect: Option<E>) -> Result<()> {
+      self.effects.with_effect(id, effect);
+      if self.effects.is_empty() { self.post_scene.replace(None); }
+      Ok(())
+    }
+
+    /// Returns the post-processing effects
+    pub fn effects(&self) -> &post::Chain {
+      &self.effects
+    }
+
+    /// Set the attachments of the scene render target of post-processing,
+    /// RGBA8 color with depth and stencil by default
+    ///
+    /// A [`TextureFormat::Rgba16F`](crate::texture::TextureFormat::Rgba16F)
+    /// color keeps the colors above 1 for [`post::ToneMapping`] and
+    /// [`post::Bloom`], and samples above 0 multisample the scene.
+    pub fn set_post_settings(&mut self, settings: target::RenderTargetSettings) {
+      self.post_settings = settings;
+    }
+
+    /// Returns the attachments of the scene render target of post-processing
+    pub fn post_settings(&self) -> &target::RenderTargetSettings {
+      &self.post_settings
+    }
+
+    /// Number of renderables drawn by the last render
+    pub fn rendered_count(&self) -> usize {
+      self.rendered.get()
+    }
+
+    /// Number of renderables skipped by the last render, their bounding
+    /// volumes being outside of the view frustum
+    ///
+    /// See [`Renderable::bounding_box`](traits::Renderable::bounding_box).
+    /// Renderables nested in a [`Scene`](crate::scene::Scene) are culled
+    /// individually but counted with their scene.
+    pub fn culled_count(&self) -> usize {
+      self.culled.get()
+    }
+
     /// Create a new renderer from a canvas element
     ///
     /// This initializes a WebGL 2.0 context from the provided canvas and sets up
@@ -100,9 +293,22 @@ impl Renderer {
             .map_err(|_| Error::UnsupportedOperation("Failed to cast to WebGL 2.0 context".to_string()))?;
 
         let renderer = Renderer {
-            context,
-            canvas,
+            context: Rc::new(context),
+            canvas: Some(canvas),
             renderables: Vec::new(),
+            lights: Vec::new(),
+            ambient: DEFAULT_AMBIENT,
+            shadows: None,
+            shadow_map: RefCell::new(None),
+            render_targets: Vec::new(),
+            effects: post::Chain::new(),
+            post_settings: target::RenderTargetSettings::default(),
+            post_scene: RefCell::new(None),
+            pixel_ratio: Cell::new(1.0),
+            pick_frame: RefCell::new(None),
+            pick_target: RefCell::new(None),
+            rendered: Cell::new(0),
+            culled: Cell::new(0),
         };
 
         // Resize the canvas to match its CSS size, accounting for device pixel ratio
@@ -111,10 +317,49 @@ impl Renderer {
         Ok(renderer)
     }
 
+    /// Create a new renderer drawing through an existing context
+    ///
+    /// The renderer is not attached to a canvas: the viewport follows the
+    /// context drawing buffer size. This is typically used with an in-memory
+    /// context such as [`gl::RecordingContext`] to test rendering code without
+    /// a browser.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use j_webgl::{Renderer, gl::RecordingContext};
+    ///
+    /// let context = Rc::new(RecordingContext::new(640, 480));
+    /// let renderer = Renderer::from_context(context.clone());
+    /// ```
+    pub fn from_context(context: Rc<dyn gl::GlContext>) -> Self {
+        Renderer {
+            context,
+            canvas: None,
+            renderables: Vec::new(),
+            lights: Vec::new(),
+            ambient: DEFAULT_AMBIENT,
+            shadows: None,
+            shadow_map: RefCell::new(None),
+            render_targets: Vec::new(),
+            effects: post::Chain::new(),
+            post_settings: target::RenderTargetSettings::default(),
+            post_scene: RefCell::new(None),
+            pixel_ratio: Cell::new(1.0),
+            pick_frame: RefCell::new(None),
+            pick_target: RefCell::new(None),
+            rendered: Cell::new(0),
+            culled: Cell::new(0),
+        }
+    }
+
     /// Render all registered renderable objects
     ///
-    /// This method sets up the viewport and renders all registered renderable objects
-    /// using the provided camera's MVP matrix.
+    /// This method sets up the viewport and renders all registered renderable objects,
+    /// passing each of them a [`FrameContext`] built from the provided camera and
+    /// its model transform. With shadows enabled, see [`set_shadows`](Renderer::set_shadows),
+    /// a shadow pass into the shadow map precedes this color pass.
     ///
     /// # Parameters
     ///
@@ -125,43 +370,229 @@ impl Renderer {
     /// Returns an error if:
     /// - The camera cannot provide its MVP matrix
     /// - Any renderable object fails to render
-
     pub fn render<C: traits::Camera>(&self, camera: &C) -> Result<()> {
-      self.context.viewport(0, 0, self.canvas.width() as i32, self.canvas.height() as i32);
-      /*
-      use traits::Camera;
-      let camera = structs::camera::Basic::new(
-        self.canvas.client_width() as f32, self.canvas.client_height() as f32,
-      )?;
-      */
-      for (_, r) in self.renderables.iter() {
-        r.render(&self.context, camera.as_f32_array()?)?;
+      let viewport = (self.context.drawing_buffer_width(), self.context.drawing_buffer_height());
+      self.pick_frame.replace(Some(FrameContext::from_camera(camera, viewport)?));
+      self.post_pass(camera, None)
+    }
+
+    /// Returns the identifier of the renderable visible at a canvas position
+    ///
+    /// `x` and `y` are in CSS pixels from the top left corner of the canvas,
+    /// as the `offsetX` and `offsetY` of mouse events, and are scaled by the
+    /// device pixel ratio of the last [`resize`](Renderer::resize). The
+    /// renderables are drawn with the camera of the last [`render`](Renderer::render)
+    /// into an offscreen unsigned integer target, each writing its index with
+    /// [`Renderable::render_id`](traits::Renderable::render_id), and the pixel
+    /// under the position is read back. Renders with [`render_into`](Renderer::render_into)
+    /// and [`render_to`](Renderer::render_to), such as a minimap, do not change
+    /// the camera used for picking.
+    ///
+    /// Returns `None` outside of any pickable renderable, outside of the
+    /// canvas, or before the first render.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target cannot be created or a renderable fails to render.
+    pub fn pick(&self, x: f32, y: f32) -> Result<Option<String>> {
+      let Some(frame) = self.pick_frame.borrow().clone() else { return Ok(None); };
+      let (width, height) = self.drawing_buffer_size();
+      let ratio = self.pixel_ratio.get();
+      let (px, py) = ((x as f64 * ratio).floor(), (y as f64 * ratio).floor());
+      if px < 0.0 || py < 0.0 || px >= width as f64 || py >= height as f64 { return Ok(None); }
+      // Window coordinates start from the bottom row
+      let (px, py) = (px as i32, height as i32 - 1 - py as i32);
+
+      let mut pick_target = self.pick_target.borrow_mut();
+      if pick_target.is_none() {
+        let settings = target::RenderTargetSettings {
+          color: Some(crate::texture::TextureFormat::R32UI), depth: Some(crate::texture::TextureFormat::Depth24Stencil8), samples: 0,
+        };
+        *pick_target = Some(target::RenderTarget::new(&self.context, width, height, settings)?);
       }
-      Ok(())
+      let pick_target = pick_target.as_mut().unwrap();
+      pick_target.resize(width, height)?;
+
+      let context = &self.context;
+      pick_target.bind();
+      context.enable(GL::DEPTH_TEST);
+      context.clear_bufferuiv_with_u32_array(GL::COLOR, 0, &[0, 0, 0, 0]);
+      context.clear(GL::DEPTH_BUFFER_BIT);
+      let mut pixel = [0u32; 4];
+      let result = self.renderables.iter().enumerate()
+        .map(|(i, (_, model, r))| (i, frame.with_model(model), r))
+        .filter(|(_, frame, r)| frame.is_visible(r.as_ref()))
+        .try_for_each(|(i, frame, r)| r.render_id(context, &frame, (i + 1).try_into()?))
+        .and_then(|_| {
+          context.bind_framebuffer(GL::READ_FRAMEBUFFER, Some(pick_target.framebuffer()));
+          context.read_pixels_with_u32_array(px, py, 1, 1, GL::RGBA_INTEGER, GL::UNSIGNED_INT, &mut pixel)
+        });
+      context.bind_framebuffer(GL::FRAMEBUFFER, None);
+      context.viewport(0, 0, context.drawing_buffer_width(), context.drawing_buffer_height());
+      result?;
+
+      Ok(pixel[0].checked_sub(1)
+        .and_then(|i| self.renderables.get(i as usize))
+        .map(|(id, _, _)| id.clone()))
+    }
+
+    /// Render all registered renderable objects into a render target
+    ///
+    /// Same as [`render`](Renderer::render), drawing into `target` instead of
+    /// the canvas. Multisampled targets are resolved, so that the target
+    /// textures hold the image on return, and the canvas is bound again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the camera cannot provide its matrices or any
+    /// renderable object fails to render.
+    pub fn render_into<C: traits::Camera>(&self, camera: &C, target: &target::RenderTarget) -> Result<()> {
+      self.post_pass(camera, Some(target))
+    }
+
+    /// Render all registered renderable objects into the render target
+    /// registered with `id`, see [`with_render_target`](Renderer::with_render_target)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no render target is registered with `id`, or if rendering fails.
+    pub fn render_to<C: traits::Camera>(&self, camera: &C, id: &str) -> Result<()> {
+      let target = self.render_target(id)
+        .ok_or_else(|| Error::Misc(format!("No render target with id {id}")))?;
+      self.render_into(camera, &target)
+    }
+
+    /// Render the renderables into the scene target and apply the effects into
+    /// `output`, or the canvas, or render the renderables directly without effects
+    fn post_pass<C: traits::Camera>(&self, camera: &C, output: Option<&target::RenderTarget>) -> Result<()> {
+      if self.effects.is_empty() { return self.color_pass(camera, output); }
+      let (width, height) = output.map(|t| (t.width(), t.height())).unwrap_or_else(|| self.drawing_buffer_size());
+      let mut scene = self.post_scene.borrow_mut();
+      if scene.as_ref().map(|s| s.settings()) != Some(&self.post_settings) {
+        *scene = Some(target::RenderTarget::new(&self.context, width, height, self.post_settings)?);
+      }
+      let scene = scene.as_mut().unwrap();
+      scene.resize(width, height)?;
+
+      self.color_pass(camera, Some(scene))?;
+      let result = self.effects.apply(&self.context, scene, output);
+      self.context.viewport(0, 0, self.context.drawing_buffer_width(), self.context.drawing_buffer_height());
+      result
+    }
+
+    /// Render the renderables into `target`, or the canvas, after the shadow pass
+    fn color_pass<C: traits::Camera>(&self, camera: &C, target: Option<&target::RenderTarget>) -> Result<()> {
+      let viewport = match target {
+        Some(t) => (t.width() as i32, t.height() as i32),
+        None => (self.context.drawing_buffer_width(), self.context.drawing_buffer_height()),
+      };
+      let frame = FrameContext::from_camera(camera, viewport)?
+        .with_lights(self.ambient, self.lights.iter().map(|(_, l)| l.clone()).collect());
+      let frame = frame.with_shadow(self.shadow_pass(&frame)?);
+
+      match target {
+        Some(t) => t.bind(),
+        None => self.context.viewport(0, 0, viewport.0, viewport.1),
+      }
+      let (mut rendered, mut culled) = (0, 0);
+      let result = self.renderables.iter().try_for_each(|(_, model, r)| {
+        let frame = frame.with_model(model);
+        if !frame.is_visible(r.as_ref()) {
+          culled += 1;
+          return Ok(());
+        }
+        rendered += 1;
+        r.render(&self.context, &frame)
+      });
+      self.rendered.set(rendered);
+      self.culled.set(culled);
+      if let Some(t) = target {
+        t.resolve();
+        self.context.bind_framebuffer(GL::FRAMEBUFFER, None);
+        self.context.viewport(0, 0, self.context.drawing_buffer_width(), self.context.drawing_buffer_height());
+      }
+      result
+    }
+
+    /// Drawing buffer size, at least one pixel wide and high
+    fn drawing_buffer_size(&self) -> (u32, u32) {
+      (self.context.drawing_buffer_width().max(1) as u32, self.context.drawing_buffer_height().max(1) as u32)
+    }
+
+    /// Render the shadow map of the first directional light casting shadows,
+    /// if shadows are enabled, and return it
+    fn shadow_pass(&self, frame: &FrameContext) -> Result<Option<shadow::FrameShadow>> {
+      let Some(settings) = self.shadows.as_ref() else { return Ok(None); };
+      let Some((index, light)) = frame.lights().iter().enumerate()
+        .find(|(_, l)| l.casts_shadow() && l.kind() == structs::light::LightKind::Directional) else { return Ok(None); };
+
+      let mut shadow_map = self.shadow_map.borrow_mut();
+      if shadow_map.as_ref().map(|m| m.size()) != Some(settings.size) {
+        *shadow_map = Some(shadow::ShadowMap::new(&self.context, settings.size)?);
+      }
+      let shadow_map = shadow_map.as_ref().unwrap();
+
+      let (view, projection) = settings.light_matrices(light);
+      let eye = view.inverse().map(|v| v.transform_point(&algebra::Point3::origin())).unwrap_or_else(algebra::Point3::origin);
+      let size = settings.size as i32;
+      let light_frame = FrameContext::new(view, projection, eye, (size, size));
+
+      shadow_map.bind();
+      self.context.enable(GL::DEPTH_TEST);
+      self.context.clear(GL::DEPTH_BUFFER_BIT);
+      let result = self.renderables.iter()
+        .map(|(_, model, r)| (light_frame.with_model(model), r))
+        .filter(|(frame, r)| frame.is_visible(r.as_ref()))
+        .try_for_each(|(frame, r)| r.render_depth(&self.context, &frame));
+      self.context.bind_framebuffer(GL::FRAMEBUFFER, None);
+      result?;
+
+      Ok(Some(shadow::FrameShadow {
+        texture: shadow_map.depth().texture().clone(),
+        matrix: *light_frame.view_projection(),
+        light: index,
+        bias: settings.bias,
+        pcf_radius: settings.pcf_radius,
+      }))
     }
 
     /// Resize the canvas to match its CSS size, accounting for device pixel ratio
     ///
     /// This method should be called when the canvas size changes (e.g., window resize).
-    /// It updates both the canvas's internal size and the WebGL viewport.
+    /// It updates both the canvas's internal size and the WebGL viewport. A renderer
+    /// created with [`from_context`](Self::from_context) only updates the viewport.
+    /// Render targets registered with [`with_render_target`](Self::with_render_target)
+    /// are resized to the drawing buffer size.
     ///
     /// # Errors
     ///
     /// Returns an error if the window object is not available.
     ///
     pub fn resize(&self) -> Result<()> {
+        let Some(canvas) = self.canvas.as_ref() else {
+            self.context.viewport(0, 0, self.context.drawing_buffer_width(), self.context.drawing_buffer_height());
+            return self.resize_render_targets();
+        };
         let window = web_sys::window()
             .ok_or_else(|| Error::UnsupportedOperation("Window object not available".to_string()))?;
         
         let dpr = window.device_pixel_ratio();
-        let rect = self.canvas.get_bounding_client_rect();
+        self.pixel_ratio.set(dpr);
+        let rect = canvas.get_bounding_client_rect();
         
-        self.canvas.set_width((rect.width() * dpr) as u32);
-        self.canvas.set_height((rect.height() * dpr) as u32);
+        canvas.set_width((rect.width() * dpr) as u32);
+        canvas.set_height((rect.height() * dpr) as u32);
         
-        self.context.viewport(0, 0, self.canvas.width() as i32, self.canvas.height() as i32);
+        self.context.viewport(0, 0, canvas.width() as i32, canvas.height() as i32);
         
-        Ok(())
+        self.resize_render_targets()
+    }
+
+    /// Resize the registered render targets to the drawing buffer size
+    fn resize_render_targets(&self) -> Result<()> {
+      let (width, height) = self.drawing_buffer_size();
+      self.render_targets.iter()
+        .try_for_each(|(_, t)| t.borrow_mut().resize(width, height))
     }
 
     /// Resize the canvas and render in one operation
@@ -184,3 +615,227 @@ impl Renderer {
     }
 }
 
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::structs;
+  use gl::{RecordingContext, Call, UniformValue};
+
+  #[test]
+  fn render_composes_model_transform_per_renderable() {
+    let recording = Rc::new(RecordingContext::new(640, 480));
+    let mut renderer = Renderer::from_context(recording.clone());
+    renderer.with_renderable("left".to_string(), Some(structs::renderable::CubeWithNormals::new().unwrap())).unwrap();
+    renderer.with_renderable("right".to_string(), Some(structs::renderable::CubeWithNormals::new().unwrap())).unwrap();
+    let model = algebra::Matrix4::new_translation(2.0, 0.0, 0.0);
+    renderer.set_transform("right", model).unwrap();
+    assert!(renderer.set_transform("missing", model).is_err());
+
+    let camera = structs::camera::Basic::new(640.0, 480.0).unwrap();
+    renderer.render(&camera).unwrap();
+
+    let vp = traits::Camera::view_projection(&camera).unwrap();
+    let matrices: Vec<_> = recording.calls().into_iter()
+      .filter_map(|c| match c {
+        Call::Uniform { name, value: UniformValue::Mat4(m), .. } if name == "u_matrix" => Some(m),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(matrices, vec![
+      <[f32; 16]>::try_from(vp.as_slice()).unwrap(),
+      <[f32; 16]>::try_from((&vp * &model).as_slice()).unwrap(),
+    ]);
+  }
+
+  #[test]
+  fn replacing_renderable_keeps_transform() {
+    let mut renderer = Renderer::from_context(Rc::new(RecordingContext::new(64, 64)));
+    renderer.with_renderable("cube".to_string(), Some(structs::renderable::Cube::new().unwrap())).unwrap();
+    let model = algebra::Matrix4::new_scaling(2.0, 2.0, 2.0);
+    renderer.set_transform("cube", model).unwrap();
+    renderer.with_renderable("cube".to_string(), Some(structs::renderable::Cube::new().unwrap())).unwrap();
+    assert_eq!(renderer.transform("cube"), Some(&model));
+    renderer.with_renderable::<structs::renderable::Cube>("cube".to_string(), None).unwrap();
+    assert_eq!(renderer.transform("cube"), None);
+  }
+
+  #[test]
+  fn lights_reach_lit_renderables() {
+    let recording = Rc::new(RecordingContext::new(64, 64));
+    let mut renderer = Renderer::from_context(recording.clone());
+    renderer.with_renderable("sphere".to_string(), Some(structs::renderable::PhongMesh::icosphere(1.0, 0).unwrap())).unwrap();
+    renderer.with_light("key".to_string(), Some(structs::light::Light::directional(0.0, -1.0, 0.0))).unwrap();
+    renderer.with_light("fill".to_string(), Some(structs::light::Light::point(1.0, 0.0, 0.0))).unwrap();
+    renderer.with_light("key".to_string(), Some(structs::light::Light::point(0.0, 2.0, 0.0))).unwrap();
+    renderer.with_light("fill".to_string(), None).unwrap();
+    renderer.set_ambient(0.0, 0.0, 0.5);
+    assert_eq!(renderer.light("key").map(|l| l.kind()), Some(structs::light::LightKind::Point));
+
+    renderer.render(&structs::camera::Basic::new(64.0, 64.0).unwrap()).unwrap();
+    assert_eq!(recording.uniform("u_lightCount"), Some(UniformValue::Int(1)));
+    assert_eq!(recording.uniform("u_lights[0].position"), Some(UniformValue::Vec3([0.0, 2.0, 0.0])));
+    assert_eq!(recording.uniform("u_ambient"), Some(UniformValue::Vec3([0.0, 0.0, 0.5])));
+  }
+
+  #[test]
+  fn rejects_lights_past_the_limit() {
+    let mut renderer = Renderer::from_context(Rc::new(RecordingContext::new(64, 64)));
+    renderer.with_renderable("sphere".to_string(), Some(structs::renderable::PhongMesh::icosphere(1.0, 0).unwrap())).unwrap();
+    let light = structs::light::Light::point(0.0, 2.0, 0.0);
+    for i in 0..shaders::fragment::MAX_LIGHTS {
+      renderer.with_light(format!("light {i}"), Some(light.clone())).unwrap();
+    }
+    let result = renderer.with_light("one too many".to_string(), Some(light.clone()));
+    assert!(matches!(result, Err(Error::UnsupportedOperation(_))));
+    assert!(renderer.light("one too many").is_none());
+    // Existing lights can still be updated, and rendering goes on
+    renderer.with_light("light 0".to_string(), Some(light)).unwrap();
+    renderer.render(&structs::camera::Basic::new(64.0, 64.0).unwrap()).unwrap();
+  }
+
+  #[test]
+  fn shadow_pass_precedes_color_pass() {
+    let recording = Rc::new(RecordingContext::new(64, 64));
+    let mut renderer = Renderer::from_context(recording.clone());
+    renderer.with_renderable("sphere".to_string(), Some(structs::renderable::PhongMesh::icosphere(1.0, 0).unwrap())).unwrap();
+    let mut sun = structs::light::Light::directional(0.0, -1.0, 0.0);
+    renderer.with_light("fill".to_string(), Some(structs::light::Light::point(1.0, 0.0, 0.0))).unwrap();
+    renderer.with_light("sun".to_string(), Some(sun.clone())).unwrap();
+    let camera = structs::camera::Basic::new(64.0, 64.0).unwrap();
+
+    renderer.render(&camera).unwrap();
+    assert!(!recording.calls().iter().any(|c| matches!(c, Call::CreateFramebuffer(_))));
+
+    renderer.set_shadows(Some(shadow::ShadowSettings { size: 128, ..Default::default() }));
+    renderer.render(&camera).unwrap();
+    assert!(!recording.calls().iter().any(|c| matches!(c, Call::CreateFramebuffer(_))), "no light casts shadows");
+
+    sun.set_cast_shadow(true);
+    renderer.with_light("sun".to_string(), Some(sun)).unwrap();
+    recording.take_calls();
+    renderer.render(&camera).unwrap();
+    let calls = recording.calls();
+    let position = |call: &Call| calls.iter().position(|c| c == call).unwrap();
+    let Some(Call::CreateFramebuffer(framebuffer)) = calls.iter().find(|c| matches!(c, Call::CreateFramebuffer(_))).cloned() else { panic!() };
+    let bound = position(&Call::BindFramebuffer { target: GL::FRAMEBUFFER, framebuffer: Some(framebuffer) });
+    let unbound = calls.iter().rposition(|c| c == &Call::BindFramebuffer { target: GL::FRAMEBUFFER, framebuffer: None }).unwrap();
+    let draws: Vec<usize> = calls.iter().enumerate().filter(|(_, c)| matches!(c, Call::DrawElements { .. })).map(|(i, _)| i).collect();
+    assert_eq!(draws.len(), 2);
+    assert!(bound < draws[0] && draws[0] < unbound && unbound < draws[1]);
+    assert!(calls[bound..draws[0]].contains(&Call::Viewport { x: 0, y: 0, width: 128, height: 128 }));
+    assert_eq!(recording.uniform("u_shadowLight"), Some(UniformValue::Int(1)));
+
+    recording.take_calls();
+    renderer.render(&camera).unwrap();
+    assert!(!recording.calls().iter().any(|c| matches!(c, Call::CreateFramebuffer(_))), "shadow map is reused");
+  }
+
+  #[test]
+  fn effects_apply_to_the_scene_target() {
+    let recording = Rc::new(RecordingContext::new(64, 32));
+    let mut renderer = Renderer::from_context(recording.clone());
+    renderer.with_renderable("cube".to_string(), Some(structs::renderable::Cube::new().unwrap())).unwrap();
+    renderer.set_post_settings(target::RenderTargetSettings { samples: 4, ..Default::default() });
+    renderer.with_effect("fxaa".to_string(), Some(post::Fxaa::new())).unwrap();
+    let camera = structs::camera::Basic::new(64.0, 32.0).unwrap();
+
+    recording.take_calls();
+    renderer.render(&camera).unwrap();
+    let calls = recording.calls();
+    let draw = calls.iter().position(|c| matches!(c, Call::DrawElements { .. })).unwrap();
+    let blit = calls.iter().position(|c| matches!(c, Call::BlitFramebuffer { .. })).unwrap();
+    let fxaa = calls.iter().position(|c| matches!(c, Call::DrawArrays { count: 3, .. })).unwrap();
+    assert!(draw < blit && blit < fxaa);
+    assert!(calls[blit..fxaa].contains(&Call::BindFramebuffer { target: GL::FRAMEBUFFER, framebuffer: None }));
+    assert_eq!(recording.state().framebuffer, None);
+
+    renderer.with_effect::<post::Fxaa>("fxaa".to_string(), None).unwrap();
+    recording.take_calls();
+    renderer.render(&camera).unwrap();
+    assert!(!recording.calls().iter().any(|c| matches!(c, Call::DrawArrays { .. } | Call::BlitFramebuffer { .. })));
+  }
+
+  #[test]
+  fn render_culls_renderables_outside_of_frustum() {
+    let recording = Rc::new(gl::RecordingContext::new(64, 64));
+    let mut renderer = Renderer::from_context(recording.clone());
+    renderer.with_renderable("initializer".to_string(), Some(structs::renderable::Initializer::new().unwrap())).unwrap();
+    renderer.with_renderable("near".to_string(), Some(structs::renderable::ShadedMesh::cuboid(1.0, 1.0, 1.0).unwrap())).unwrap();
+    renderer.with_renderable("far".to_string(), Some(structs::renderable::ShadedMesh::cuboid(1.0, 1.0, 1.0).unwrap())).unwrap();
+    renderer.set_transform("far", algebra::Matrix4::new_translation(100.0, 0.0, 0.0)).unwrap();
+    let camera = structs::camera::Basic::new(64.0, 64.0).unwrap();
+
+    renderer.render(&camera).unwrap();
+    assert_eq!((renderer.rendered_count(), renderer.culled_count()), (2, 1));
+    let draws = recording.take_calls().into_iter().filter(|c| matches!(c, gl::Call::DrawElements { .. })).count();
+    assert_eq!(draws, 1);
+
+    // Objects crossing the edge of the view are kept
+    renderer.set_transform("far", algebra::Matrix4::new_scaling(200.0, 1.0, 1.0)).unwrap();
+    renderer.render(&camera).unwrap();
+    assert_eq!((renderer.rendered_count(), renderer.culled_count()), (3, 0));
+  }
+
+  #[test]
+  fn pick_returns_the_closest_renderable() {
+    let context = Rc::new(gl::SoftwareContext::new(64, 64));
+    let mut renderer = Renderer::from_context(context.clone());
+    renderer.with_renderable("background".to_string(), Some(structs::renderable::Initializer::new().unwrap())).unwrap();
+    renderer.with_renderable("cube".to_string(), Some(structs::renderable::ShadedMesh::cuboid(1.0, 1.0, 1.0).unwrap())).unwrap();
+    assert_eq!(renderer.pick(32.0, 32.0).unwrap(), None, "nothing rendered yet");
+
+    renderer.render(&structs::camera::Basic::new(64.0, 64.0).unwrap()).unwrap();
+    assert_eq!(renderer.pick(32.0, 32.0).unwrap().as_deref(), Some("cube"));
+    assert_eq!(renderer.pick(1.0, 1.0).unwrap(), None);
+    assert_eq!(renderer.pick(-1.0, 32.0).unwrap(), None);
+    assert_eq!(renderer.pick(32.0, 64.0).unwrap(), None);
+
+    renderer.with_renderable("shell".to_string(), Some(structs::renderable::ShadedMesh::cuboid(1.5, 1.5, 1.5).unwrap())).unwrap();
+    assert_eq!(renderer.pick(32.0, 32.0).unwrap().as_deref(), Some("shell"));
+    renderer.set_transform("shell", algebra::Matrix4::new_translation(0.0, 10.0, 0.0)).unwrap();
+    assert_eq!(renderer.pick(32.0, 32.0).unwrap().as_deref(), Some("cube"));
+  }
+
+  #[test]
+  fn pick_ignores_the_camera_of_render_to() {
+    let context = Rc::new(gl::SoftwareContext::new(64, 64));
+    let mut renderer = Renderer::from_context(context.clone());
+    renderer.with_renderable("cube".to_string(), Some(structs::renderable::ShadedMesh::cuboid(1.0, 1.0, 1.0).unwrap())).unwrap();
+    renderer.with_render_target("offscreen".to_string(), Some(Default::default())).unwrap();
+    renderer.render_to(&structs::camera::Basic::new(64.0, 64.0).unwrap(), "offscreen").unwrap();
+    assert_eq!(renderer.pick(32.0, 32.0).unwrap(), None, "nothing rendered to the canvas yet");
+
+    renderer.render(&structs::camera::Basic::new(64.0, 64.0).unwrap()).unwrap();
+    // A minimap camera looking away from the cube
+    let mut camera = structs::camera::Orbit::new(64.0, 64.0).unwrap();
+    camera.set_target(0.0, 100.0, 0.0);
+    renderer.render_to(&camera, "offscreen").unwrap();
+    assert_eq!(renderer.pick(32.0, 32.0).unwrap().as_deref(), Some("cube"));
+  }
+
+  #[test]
+  fn render_to_target_follows_resize() {
+    let recording = Rc::new(RecordingContext::new(64, 32));
+    let mut renderer = Renderer::from_context(recording.clone());
+    renderer.with_renderable("cube".to_string(), Some(structs::renderable::Cube::new().unwrap())).unwrap();
+    let settings = target::RenderTargetSettings { samples: 4, ..Default::default() };
+    renderer.with_render_target("offscreen".to_string(), Some(settings)).unwrap();
+    let camera = structs::camera::Basic::new(64.0, 32.0).unwrap();
+    assert!(renderer.render_to(&camera, "missing").is_err());
+
+    recording.take_calls();
+    renderer.render_to(&camera, "offscreen").unwrap();
+    let calls = recording.calls();
+    let draw = calls.iter().position(|c| matches!(c, Call::DrawElements { .. })).unwrap();
+    let blit = calls.iter().position(|c| matches!(c, Call::BlitFramebuffer { .. })).unwrap();
+    assert!(calls[..draw].iter().any(|c| matches!(c, Call::BindFramebuffer { framebuffer: Some(_), .. })));
+    assert!(draw < blit);
+    assert_eq!(recording.state().framebuffer, None);
+
+    recording.set_drawing_buffer_size(128, 96);
+    renderer.resize().unwrap();
+    let target = renderer.render_target("offscreen").unwrap();
+    assert_eq!((target.width(), target.height()), (128, 96));
+    assert_eq!(target.color_texture().map(|t| (t.width(), t.height())), Some((128, 96)));
+  }
+}
@@ -0,0 +1,391 @@
+use super::*;
+
+/// Hierarchy of nodes with local transforms and attached renderables
+///
+/// Each node transform is relative to its parent, so that moving a node moves
+/// its whole subtree, e.g. a wrist attached to an elbow:
+///
+/// ```rust
+/// use j_webgl::scene::{Scene, Transform};
+///
+/// let mut scene = Scene::new();
+/// let elbow = scene.add_node(None, "elbow").unwrap();
+/// let wrist = scene.add_node(Some(elbow), "wrist").unwrap();
+/// scene.set_transform(wrist, Transform::from_translation(1.0, 0.0, 0.0)).unwrap();
+/// scene.set_transform(elbow, Transform::identity().with_euler(0.0, 0.0, std::f32::consts::FRAC_PI_2)).unwrap();
+/// let position = scene.world_transform(wrist).unwrap().transform_point(&j_webgl::algebra::Point3::origin());
+/// assert!((position.y() - 1.0).abs() < 1e-6);
+/// ```
+///
+/// World transforms are computed on demand and cached until the transform of
+/// the node or of one of its ancestors changes. A scene is itself a
+/// [`Renderable`](traits::Renderable): rendering it draws the renderables of
+/// visible nodes with their world transform, composed with the model
+/// transform of the scene.
+#[derive(Default)]
+pub struct Scene {
+  nodes: Vec<Option<Node>>,
+  roots: Vec<NodeId>,
+}
+
+impl Scene {
+  pub fn new() -> Self {
+    Scene::default()
+  }
+
+  /// Add a node under `parent`, or as a root node when `parent` is `None`
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if `parent` is not a node of the scene.
+  pub fn add_node(&mut self, parent: Option<NodeId>, name: &str) -> Result<NodeId> {
+    if let Some(parent) = parent { self.get(parent)?; }
+    let id = NodeId(self.nodes.len());
+    self.nodes.push(Some(Node::new(name, parent)));
+    self.siblings_mut(parent).push(id);
+    Ok(id)
+  }
+
+  /// Attach a renderable to a node
+  pub fn add_renderable<R: traits::Renderable + 'static>(&mut self, id: NodeId, renderable: R) -> Result<()> {
+    self.get_mut(id)?.renderables.push(Box::new(renderable));
+    Ok(())
+  }
+
+  /// Remove a node with its subtree
+  pub fn remove(&mut self, id: NodeId) -> Result<()> {
+    let parent = self.get(id)?.parent;
+    self.siblings_mut(parent).retain(|c| *c != id);
+    let mut stack = vec![id];
+    while let Some(id) = stack.pop() {
+      if let Some(node) = self.nodes[id.0].take() { stack.extend(node.children); }
+    }
+    Ok(())
+  }
+
+  /// Move a node, with its subtree, under a new parent (or to the roots when `None`)
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if either node is not in the scene, or if `parent` is
+  /// in the subtree of `id`.
+  pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) -> Result<()> {
+    let previous = self.get(id)?.parent;
+    let mut ancestor = parent;
+    while let Some(a) = ancestor {
+      if a == id { return Err(Error::Misc(format!("Node {} cannot be its own ancestor", self.get(id)?.name))); }
+      ancestor = self.get(a)?.parent;
+    }
+    self.siblings_mut(previous).retain(|c| *c != id);
+    self.siblings_mut(parent).push(id);
+    self.get_mut(id)?.parent = parent;
+    self.invalidate(id);
+    Ok(())
+  }
+
+  pub fn node(&self, id: NodeId) -> Option<&Node> {
+    self.nodes.get(id.0).and_then(|n| n.as_ref())
+  }
+
+  /// Returns the first node with the given name, in depth-first order
+  pub fn find(&self, name: &str) -> Option<NodeId> {
+    let mut stack: Vec<NodeId> = self.roots.iter().rev().copied().collect();
+    while let Some(id) = stack.pop() {
+      let node = self.node(id)?;
+      if node.name == name { return Some(id); }
+      stack.extend(node.children.iter().rev());
+    }
+    None
+  }
+
+  pub fn roots(&self) -> &[NodeId] { &self.roots }
+
+  pub fn set_transform(&mut self, id: NodeId, transform: Transform) -> Result<()> {
+    self.get_mut(id)?.transform = transform;
+    self.invalidate(id);
+    Ok(())
+  }
+
+  pub fn set_visible(&mut self, id: NodeId, visible: bool) -> Result<()> {
+    self.get_mut(id)?.visible = visible;
+    Ok(())
+  }
+
+  /// Returns whether the node and all its ancestors are visible
+  pub fn is_visible(&self, id: NodeId) -> bool {
+    let mut current = Some(id);
+    while let Some(id) = current {
+      let Some(node) = self.node(id) else { return false; };
+      if !node.visible { return false; }
+      current = node.parent;
+    }
+    true
+  }
+
+  /// Returns the transform from the node space into the scene space
+  pub fn world_transform(&self, id: NodeId) -> Option<algebra::Matrix4> {
+    let node = self.node(id)?;
+    if node.dirty.get() {
+      let local = node.transform.matrix();
+      let world = match node.parent {
+        Some(parent) => &self.world_transform(parent)? * &local,
+        None => local,
+      };
+      node.world.set(world);
+      node.dirty.set(false);
+    }
+    Some(node.world.get())
+  }
+
+  /// Visit visible nodes depth-first, parents before children, with their
+  /// world transform. Hidden nodes are skipped with their subtree.
+  pub fn traverse<F: FnMut(NodeId, &Node, &algebra::Matrix4)>(&self, mut f: F) {
+    let mut stack: Vec<NodeId> = self.roots.iter().rev().copied().collect();
+    while let Some(id) = stack.pop() {
+      let Some(node) = self.node(id) else { continue; };
+      if !node.visible { continue; }
+      let Some(world) = self.world_transform(id) else { continue; };
+      f(id, node, &world);
+      stack.extend(node.children.iter().rev());
+    }
+  }
+
+  fn get(&self, id: NodeId) -> Result<&Node> {
+    self.node(id).ok_or_else(|| Error::Misc(format!("No node {} in scene", id.0)))
+  }
+
+  fn get_mut(&mut self, id: NodeId) -> Result<&mut Node> {
+    self.nodes.get_mut(id.0).and_then(|n| n.as_mut())
+      .ok_or_else(|| Error::Misc(format!("No node {} in scene", id.0)))
+  }
+
+  fn siblings_mut(&mut self, parent: Option<NodeId>) -> &mut Vec<NodeId> {
+    match parent.and_then(|p| self.nodes.get_mut(p.0)).and_then(|n| n.as_mut()) {
+      Some(node) => &mut node.children,
+      None => &mut self.roots,
+    }
+  }
+
+  /// Mark the world transforms of a node and its subtree as outdated. A node
+  /// is only cached after its ancestors, so a dirty node only has dirty
+  /// descendants and the walk can stop there.
+  fn invalidate(&self, id: NodeId) {
+    let mut stack = vec![id];
+    while let Some(id) = stack.pop() {
+      let Some(node) = self.node(id) else { continue; };
+      if node.dirty.replace(true) { continue; }
+      stack.extend(node.children.iter());
+    }
+  }
+}
+
+impl traits::Renderable for Scene {
+  fn render(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    let mut result = Ok(());
+    self.traverse(|_, node, world| {
+      if result.is_err() || node.renderables.is_empty() { return; }
+      let frame = frame.with_model(&(frame.model() * world));
+      result = node.renderables.iter()
+        .filter(|r| frame.is_visible(r.as_ref()))
+        .try_for_each(|r| r.render(context, &frame));
+    });
+    result
+  }
+
+  fn render_depth(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    let mut result = Ok(());
+    self.traverse(|_, node, world| {
+      if result.is_err() || node.renderables.is_empty() { return; }
+      let frame = frame.with_model(&(frame.model() * world));
+      result = node.renderables.iter()
+        .filter(|r| frame.is_visible(r.as_ref()))
+        .try_for_each(|r| r.render_depth(context, &frame));
+    });
+    result
+  }
+
+  /// Returns the box bounding the renderables of all nodes, `None` if the
+  /// scene is empty or one of them has no bounding box
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    let mut bounds: Option<Option<algebra::Aabb>> = None;
+    self.traverse(|_, node, world| {
+      for r in node.renderables.iter() {
+        let aabb = r.bounding_box().map(|b| b.transform(world));
+        bounds = Some(match bounds {
+          None => aabb,
+          Some(bounds) => bounds.zip(aabb).map(|(a, b)| a.union(&b)),
+        });
+      }
+    });
+    bounds.flatten()
+  }
+
+  /// Renders the identifiers of all visible renderables as `id`, so that the
+  /// whole scene is picked as one object
+  fn render_id(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+    id: u32,
+  ) -> Result<()>
+  {
+    let mut result = Ok(());
+    self.traverse(|_, node, world| {
+      if result.is_err() || node.renderables.is_empty() { return; }
+      let frame = frame.with_model(&(frame.model() * world));
+      result = node.renderables.iter()
+        .filter(|r| frame.is_visible(r.as_ref()))
+        .try_for_each(|r| r.render_id(context, &frame, id));
+    });
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::rc::Rc;
+  use std::cell::RefCell;
+
+  fn position(scene: &Scene, id: NodeId) -> algebra::Point3 {
+    scene.world_transform(id).unwrap().transform_point(&algebra::Point3::origin())
+  }
+
+  fn assert_close(a: &algebra::Point3, b: &algebra::Point3) {
+    assert!((a - b).norm() < 1e-5, "{:?} != {:?}", a, b);
+  }
+
+  /// Renderable recording the model matrices it is rendered with
+  struct Probe(Rc<RefCell<Vec<algebra::Matrix4>>>);
+
+  impl traits::Renderable for Probe {
+    fn render(&self, _: &Rc<dyn gl::GlContext>, frame: &FrameContext) -> Result<()> {
+      self.0.borrow_mut().push(*frame.model());
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn wrist_follows_elbow() {
+    let mut scene = Scene::new();
+    let shoulder = scene.add_node(None, "shoulder").unwrap();
+    let elbow = scene.add_node(Some(shoulder), "elbow").unwrap();
+    let wrist = scene.add_node(Some(elbow), "wrist").unwrap();
+    scene.set_transform(elbow, Transform::from_translation(0.0, 2.0, 0.0)).unwrap();
+    scene.set_transform(wrist, Transform::from_translation(1.0, 0.0, 0.0)).unwrap();
+    assert_close(&position(&scene, wrist), &algebra::Point3::new(1.0, 2.0, 0.0));
+
+    // Cached world transforms are refreshed when an ancestor moves
+    scene.set_transform(elbow, Transform::from_translation(0.0, 2.0, 0.0).with_euler(0.0, 0.0, std::f32::consts::FRAC_PI_2)).unwrap();
+    assert_close(&position(&scene, wrist), &algebra::Point3::new(0.0, 3.0, 0.0));
+    scene.set_transform(shoulder, Transform::identity().with_scale(2.0, 2.0, 2.0)).unwrap();
+    assert_close(&position(&scene, elbow), &algebra::Point3::new(0.0, 4.0, 0.0));
+    assert_close(&position(&scene, wrist), &algebra::Point3::new(0.0, 6.0, 0.0));
+  }
+
+  #[test]
+  fn invalidation_reaches_cached_descendants_of_dirty_nodes() {
+    let mut scene = Scene::new();
+    let a = scene.add_node(None, "a").unwrap();
+    let b = scene.add_node(Some(a), "b").unwrap();
+    let c = scene.add_node(Some(b), "c").unwrap();
+    position(&scene, c);
+    // Make b dirty without updating, then move a: c must still be refreshed
+    scene.set_transform(b, Transform::from_translation(1.0, 0.0, 0.0)).unwrap();
+    scene.set_transform(a, Transform::from_translation(0.0, 1.0, 0.0)).unwrap();
+    assert_close(&position(&scene, c), &algebra::Point3::new(1.0, 1.0, 0.0));
+  }
+
+  #[test]
+  fn reparenting_and_removal() {
+    let mut scene = Scene::new();
+    let a = scene.add_node(None, "a").unwrap();
+    let b = scene.add_node(None, "b").unwrap();
+    let c = scene.add_node(Some(a), "c").unwrap();
+    scene.set_transform(a, Transform::from_translation(1.0, 0.0, 0.0)).unwrap();
+    scene.set_transform(b, Transform::from_translation(0.0, 0.0, 1.0)).unwrap();
+    assert_close(&position(&scene, c), &algebra::Point3::new(1.0, 0.0, 0.0));
+
+    scene.set_parent(c, Some(b)).unwrap();
+    assert_close(&position(&scene, c), &algebra::Point3::new(0.0, 0.0, 1.0));
+    assert!(scene.set_parent(b, Some(c)).is_err());
+    assert_eq!(scene.find("c"), Some(c));
+
+    scene.remove(b).unwrap();
+    assert_eq!(scene.roots(), &[a]);
+    assert!(scene.node(c).is_none());
+    assert_eq!(scene.find("c"), None);
+    assert!(scene.add_node(Some(c), "d").is_err());
+  }
+
+  #[test]
+  fn render_skips_hidden_subtrees_and_composes_scene_model() {
+    let models = Rc::new(RefCell::new(Vec::new()));
+    let mut scene = Scene::new();
+    let a = scene.add_node(None, "a").unwrap();
+    let b = scene.add_node(Some(a), "b").unwrap();
+    let c = scene.add_node(None, "c").unwrap();
+    for id in [a, b, c] { scene.add_renderable(id, Probe(models.clone())).unwrap(); }
+    scene.set_transform(b, Transform::from_translation(1.0, 0.0, 0.0)).unwrap();
+    scene.set_visible(c, false).unwrap();
+    assert!(!scene.is_visible(c));
+
+    let context: Rc<dyn gl::GlContext> = Rc::new(gl::RecordingContext::new(64, 64));
+    let placement = algebra::Matrix4::new_translation(0.0, 5.0, 0.0);
+    let frame = FrameContext::new(
+      algebra::Matrix4::identity(), algebra::Matrix4::identity(), algebra::Point3::origin(), (64, 64),
+    ).with_model(&placement);
+    traits::Renderable::render(&scene, &context, &frame).unwrap();
+    assert_eq!(*models.borrow(), vec![placement, &placement * &algebra::Matrix4::new_translation(1.0, 0.0, 0.0)]);
+
+    scene.set_visible(a, false).unwrap();
+    assert!(!scene.is_visible(b));
+    models.borrow_mut().clear();
+    traits::Renderable::render(&scene, &context, &frame).unwrap();
+    assert!(models.borrow().is_empty());
+  }
+
+  /// Probe bounded by a small box around its origin
+  struct BoundedProbe(Probe);
+
+  impl traits::Renderable for BoundedProbe {
+    fn render(&self, context: &Rc<dyn gl::GlContext>, frame: &FrameContext) -> Result<()> {
+      self.0.render(context, frame)
+    }
+
+    fn bounding_box(&self) -> Option<algebra::Aabb> {
+      Some(algebra::Aabb::new(algebra::Point3::new(-0.1, -0.1, -0.1), algebra::Point3::new(0.1, 0.1, 0.1)))
+    }
+  }
+
+  #[test]
+  fn render_culls_nodes_outside_of_frustum() {
+    let models = Rc::new(RefCell::new(Vec::new()));
+    let mut scene = Scene::new();
+    let a = scene.add_node(None, "a").unwrap();
+    let b = scene.add_node(Some(a), "b").unwrap();
+    for id in [a, b] { scene.add_renderable(id, BoundedProbe(Probe(models.clone()))).unwrap(); }
+    scene.set_transform(b, Transform::from_translation(5.0, 0.0, 0.0)).unwrap();
+    let bounds = traits::Renderable::bounding_box(&scene).unwrap();
+    assert_eq!((bounds.min(), bounds.max()), (algebra::Point3::new(-0.1, -0.1, -0.1), algebra::Point3::new(5.1, 0.1, 0.1)));
+
+    // The frustum of an identity view projection is the [-1, 1] cube
+    let context: Rc<dyn gl::GlContext> = Rc::new(gl::RecordingContext::new(64, 64));
+    let frame = FrameContext::new(algebra::Matrix4::identity(), algebra::Matrix4::identity(), algebra::Point3::origin(), (64, 64));
+    traits::Renderable::render(&scene, &context, &frame).unwrap();
+    assert_eq!(*models.borrow(), vec![algebra::Matrix4::identity()]);
+
+    scene.add_renderable(b, Probe(models.clone())).unwrap();
+    assert_eq!(traits::Renderable::bounding_box(&scene), None);
+  }
+}
@@ -0,0 +1,5 @@
+use super::*;
+
+mod transform; pub use transform::Transform;
+mod node; pub use node::{Node, NodeId};
+mod graph; pub use graph::Scene;
@@ -0,0 +1,45 @@
+use super::*;
+
+use std::cell::Cell;
+
+/// Handle to a node of a [`Scene`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(pub(super) usize);
+
+/// Node of a [`Scene`], with a local transform, children and attached renderables
+pub struct Node {
+  pub(super) name: String,
+  pub(super) transform: Transform,
+  pub(super) parent: Option<NodeId>,
+  pub(super) children: Vec<NodeId>,
+  pub(super) visible: bool,
+  pub(super) renderables: Vec<Box<dyn traits::Renderable>>,
+  /// Cached world transform, valid when `dirty` is false
+  pub(super) world: Cell<algebra::Matrix4>,
+  pub(super) dirty: Cell<bool>,
+}
+
+impl Node {
+  pub(super) fn new(name: &str, parent: Option<NodeId>) -> Self {
+    Node {
+      name: name.to_string(),
+      transform: Transform::identity(),
+      parent,
+      children: Vec::new(),
+      visible: true,
+      renderables: Vec::new(),
+      world: Cell::new(algebra::Matrix4::identity()),
+      dirty: Cell::new(true),
+    }
+  }
+
+  pub fn name(&self) -> &str { &self.name }
+  pub fn transform(&self) -> &Transform { &self.transform }
+  pub fn parent(&self) -> Option<NodeId> { self.parent }
+  pub fn children(&self) -> &[NodeId] { &self.children }
+
+  /// Returns the node own visibility flag, regardless of its ancestors
+  pub fn visible(&self) -> bool { self.visible }
+
+  pub fn renderables(&self) -> &[Box<dyn traits::Renderable>] { &self.renderables }
+}
@@ -0,0 +1,99 @@
+use super::*;
+
+/// Local transform of a scene node, applied as scale, then rotation, then translation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+  pub translation: algebra::Vector3,
+  pub rotation: algebra::Quaternion,
+  pub scale: algebra::Vector3,
+}
+
+impl Default for Transform {
+  fn default() -> Self {
+    Transform::identity()
+  }
+}
+
+impl Transform {
+  pub fn identity() -> Self {
+    Transform {
+      translation: algebra::Vector3::zero(),
+      rotation: algebra::Quaternion::identity(),
+      scale: algebra::Vector3::new(1.0, 1.0, 1.0),
+    }
+  }
+
+  pub fn from_translation(x: f32, y: f32, z: f32) -> Self {
+    Transform::identity().with_translation(x, y, z)
+  }
+
+  pub fn with_translation(mut self, x: f32, y: f32, z: f32) -> Self {
+    self.translation = algebra::Vector3::new(x, y, z);
+    self
+  }
+
+  pub fn with_rotation(mut self, rotation: algebra::Quaternion) -> Self {
+    self.rotation = rotation;
+    self
+  }
+
+  /// Set the rotation from Euler angles in radians, see [`Quaternion::from_euler`](algebra::Quaternion::from_euler)
+  pub fn with_euler(self, x: f32, y: f32, z: f32) -> Self {
+    self.with_rotation(algebra::Quaternion::from_euler(x, y, z))
+  }
+
+  pub fn with_scale(mut self, x: f32, y: f32, z: f32) -> Self {
+    self.scale = algebra::Vector3::new(x, y, z);
+    self
+  }
+
+  /// Decompose a matrix made of a translation, a rotation and a scale
+  ///
+  /// A negative determinant is taken as a mirroring along x. Shear, and the
+  /// projective row of the matrix, are dropped.
+  pub fn from_matrix(matrix: &algebra::Matrix4) -> Self {
+    let column = |c: usize| algebra::Vector3::new(matrix.get(0, c), matrix.get(1, c), matrix.get(2, c)).norm();
+    let sign = if matrix.determinant() < 0.0 { -1.0 } else { 1.0 };
+    let mut rotation = *matrix;
+    rotation.scale(sign, 1.0, 1.0);
+    Transform {
+      translation: algebra::Vector3::new(matrix.get(0, 3), matrix.get(1, 3), matrix.get(2, 3)),
+      rotation: algebra::Quaternion::from_matrix4(&rotation),
+      scale: algebra::Vector3::new(sign * column(0), column(1), column(2)),
+    }
+  }
+
+  /// Returns the matrix `translation * rotation * scale`
+  pub fn matrix(&self) -> algebra::Matrix4 {
+    let mut matrix = algebra::Matrix4::new_translation(self.translation.dx(), self.translation.dy(), self.translation.dz());
+    matrix = &matrix * &self.rotation.to_matrix4();
+    matrix.scale(self.scale.dx(), self.scale.dy(), self.scale.dz());
+    matrix
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn matrix_applies_scale_rotation_then_translation() {
+    let transform = Transform::from_translation(1.0, 2.0, 3.0)
+      .with_euler(0.0, std::f32::consts::FRAC_PI_2, 0.0)
+      .with_scale(2.0, 2.0, 2.0);
+    let p = transform.matrix().transform_point(&algebra::Point3::new(1.0, 0.0, 0.0));
+    // (1, 0, 0) scaled to (2, 0, 0), turned to (0, 0, -2) around y, then moved
+    assert!((&p - &algebra::Point3::new(1.0, 2.0, 1.0)).norm() < 1e-5, "{:?}", p);
+  }
+
+  #[test]
+  fn from_matrix_recovers_mirrored_transform() {
+    let transform = Transform::from_translation(1.0, -2.0, 3.0)
+      .with_euler(0.3, -1.2, 0.7)
+      .with_scale(-2.0, 0.5, 3.0);
+    let decomposed = Transform::from_matrix(&transform.matrix());
+    let (expected, actual) = (transform.matrix(), decomposed.matrix());
+    assert!(expected.as_slice().iter().zip(actual.as_slice()).all(|(a, b)| (a - b).abs() < 1e-5), "{:?}", decomposed);
+    assert!((decomposed.scale.dx() + 2.0).abs() < 1e-5);
+  }
+}
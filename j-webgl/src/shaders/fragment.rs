@@ -47,3 +47,534 @@ pub fn color_light() -> Result<String> {
      "#.to_string()
   )
 }
+
+pub fn texture_light() -> Result<String> {
+  Ok(r#"#version 300 es
+
+        precision highp float;
+
+        in vec3 v_normal;
+        in vec2 v_texcoord;
+
+        uniform sampler2D u_texture;
+        uniform vec3 u_reverseLightDirection;
+        // Tint multiplied with the texture color
+        uniform vec4 u_color;
+
+        out vec4 outColor;
+
+        void main() {
+          vec3 normal = normalize(v_normal);
+          float light = dot(normal, u_reverseLightDirection);
+
+          outColor = texture(u_texture, v_texcoord) * u_color;
+          outColor.rgb *= light;
+        }
+     "#.to_string()
+  )
+}
+
+/// Size of the light arrays of [`phong`] and [`pbr`]
+pub const MAX_LIGHTS: usize = 8;
+
+/// Light uniforms shared by [`phong`] and [`pbr`], with a function returning
+/// the radiance reaching a surface point and the direction towards the light
+fn lights() -> String {
+  format!(r#"
+        #define MAX_LIGHTS {MAX_LIGHTS}
+        #define DIRECTIONAL 0
+        #define POINT 1
+        #define SPOT 2
+
+        struct Light {{
+          int type;
+          vec3 position;
+          // Direction the light travels along
+          vec3 direction;
+          // Color multiplied by intensity
+          vec3 color;
+          // Constant, linear and quadratic attenuation coefficients
+          vec3 attenuation;
+          // Cosines of the outer and inner spot cone half-angles
+          float cosOuter;
+          float cosInner;
+        }};
+
+        uniform Light u_lights[MAX_LIGHTS];
+        uniform int u_lightCount;
+        uniform vec3 u_ambient;
+
+        vec3 lightRadiance(Light light, vec3 position, out vec3 toLight) {{
+          toLight = -light.direction;
+          vec3 radiance = light.color;
+          if (light.type != DIRECTIONAL) {{
+            toLight = light.position - position;
+            float d = length(toLight);
+            toLight /= d;
+            radiance /= light.attenuation.x + light.attenuation.y * d + light.attenuation.z * d * d;
+            if (light.type == SPOT) {{
+              radiance *= smoothstep(light.cosOuter, light.cosInner, dot(-toLight, light.direction));
+            }}
+          }}
+          return radiance;
+        }}
+  "#)
+}
+
+/// Phong or Blinn-Phong shading of a single color with ambient, diffuse and
+/// specular terms, lit by up to [`MAX_LIGHTS`] lights
+pub fn phong() -> Result<String> {
+  Ok(phong_source(r#"
+        float shadow(int light) {
+          return 1.0;
+        }
+  "#))
+}
+
+/// [`phong`] shading with the light of index `u_shadowLight` occluded as seen
+/// from its shadow map
+///
+/// `u_shadowMatrix` transforms world positions to the clip space of the
+/// depth pass rendered into `u_shadowMap`. A point is lit if its depth minus
+/// `u_shadowBias` is not beyond the stored depth, averaged over the
+/// `(2 * u_pcfRadius + 1)^2` neighbouring texels (percentage closer filtering).
+pub fn phong_shadow() -> Result<String> {
+  Ok(phong_source(r#"
+        uniform highp sampler2D u_shadowMap;
+        uniform mat4 u_shadowMatrix;
+        uniform int u_shadowLight;
+        uniform float u_shadowBias;
+        uniform int u_pcfRadius;
+
+        float shadow(int light) {
+          if (light != u_shadowLight) { return 1.0; }
+          vec4 clip = u_shadowMatrix * vec4(v_position, 1.0);
+          vec3 coords = clip.xyz / clip.w * 0.5 + 0.5;
+          if (any(lessThan(coords, vec3(0.0))) || any(greaterThan(coords, vec3(1.0)))) { return 1.0; }
+          vec2 texel = 1.0 / vec2(textureSize(u_shadowMap, 0));
+          float lit = 0.0;
+          float count = 0.0;
+          for (int x = -u_pcfRadius; x <= u_pcfRadius; x++) {
+            for (int y = -u_pcfRadius; y <= u_pcfRadius; y++) {
+              float depth = texture(u_shadowMap, coords.xy + vec2(x, y) * texel).r;
+              lit += coords.z - u_shadowBias <= depth ? 1.0 : 0.0;
+              count += 1.0;
+            }
+          }
+          return lit / count;
+        }
+  "#))
+}
+
+/// Source of [`phong`] with a `float shadow(int light)` function scaling the light radiances
+fn phong_source(shadow: &str) -> String {
+  format!(r#"#version 300 es
+
+        precision highp float;
+        {lights}
+        in vec3 v_position;
+        in vec3 v_normal;
+
+        uniform vec3 u_eyePosition;
+        uniform vec4 u_color;
+        uniform vec3 u_specularColor;
+        uniform float u_shininess;
+        // Whether to use the Blinn-Phong half vector rather than the reflected light
+        uniform bool u_blinn;
+
+        out vec4 outColor;
+        {shadow}
+        void main() {{
+          vec3 normal = normalize(v_normal);
+          vec3 toEye = normalize(u_eyePosition - v_position);
+          vec3 diffuse = u_ambient;
+          vec3 specular = vec3(0.0);
+
+          for (int i = 0; i < MAX_LIGHTS; i++) {{
+            if (i >= u_lightCount) {{ break; }}
+            vec3 toLight;
+            vec3 radiance = lightRadiance(u_lights[i], v_position, toLight) * shadow(i);
+
+            float lambert = max(dot(normal, toLight), 0.0);
+            diffuse += lambert * radiance;
+            if (lambert > 0.0) {{
+              float angle = u_blinn
+                ? dot(normal, normalize(toLight + toEye))
+                : dot(reflect(-toLight, normal), toEye);
+              specular += pow(max(angle, 0.0), u_shininess) * radiance;
+            }}
+          }}
+
+          outColor = vec4(u_color.rgb * diffuse + u_specularColor * specular, u_color.a);
+        }}
+     "#, lights = lights())
+}
+
+/// Cook-Torrance shading with the GGX distribution of a metallic-roughness
+/// material, lit by up to [`MAX_LIGHTS`] lights and output in sRGB
+///
+/// Each map is sampled when its `u_has...` flag is set. The normal map is
+/// applied in a tangent frame derived from screen-space derivatives of the
+/// position and texture coordinates, so meshes need no tangent attribute,
+/// and ignored where the texture coordinates are degenerate.
+pub fn pbr() -> Result<String> {
+  Ok(format!(r#"#version 300 es
+
+        precision highp float;
+        {lights}
+        #define PI 3.14159265359
+
+        in vec3 v_position;
+        in vec3 v_normal;
+        in vec2 v_texcoord;
+
+        uniform vec3 u_eyePosition;
+        uniform vec4 u_baseColor;
+        uniform float u_metallic;
+        uniform float u_roughness;
+        uniform vec3 u_emissive;
+        uniform float u_normalScale;
+        uniform float u_occlusionStrength;
+
+        uniform sampler2D u_baseColorTexture;
+        uniform sampler2D u_metallicRoughnessTexture;
+        uniform sampler2D u_normalTexture;
+        uniform sampler2D u_occlusionTexture;
+        uniform sampler2D u_emissiveTexture;
+        uniform bool u_hasBaseColorTexture;
+        uniform bool u_hasMetallicRoughnessTexture;
+        uniform bool u_hasNormalTexture;
+        uniform bool u_hasOcclusionTexture;
+        uniform bool u_hasEmissiveTexture;
+
+        out vec4 outColor;
+
+        vec3 toLinear(vec3 srgb) {{
+          return pow(srgb, vec3(2.2));
+        }}
+
+        vec3 perturbedNormal(vec3 normal) {{
+          vec3 dpdx = dFdx(v_position);
+          vec3 dpdy = dFdy(v_position);
+          vec2 dtdx = dFdx(v_texcoord);
+          vec2 dtdy = dFdy(v_texcoord);
+          // Constant or collinear texture coordinates define no tangent frame
+          float determinant = dtdx.s * dtdy.t - dtdy.s * dtdx.t;
+          if (abs(determinant) < 1e-12) {{ return normal; }}
+          vec3 tangent = (dtdy.t * dpdx - dtdx.t * dpdy) / determinant;
+          tangent -= normal * dot(normal, tangent);
+          if (dot(tangent, tangent) < 1e-12) {{ return normal; }}
+          tangent = normalize(tangent);
+          vec3 bitangent = cross(normal, tangent);
+          vec3 sampled = texture(u_normalTexture, v_texcoord).xyz * 2.0 - 1.0;
+          sampled.xy *= u_normalScale;
+          return normalize(mat3(tangent, bitangent, normal) * sampled);
+        }}
+
+        void main() {{
+          vec4 baseColor = u_baseColor;
+          if (u_hasBaseColorTexture) {{
+            vec4 texel = texture(u_baseColorTexture, v_texcoord);
+            baseColor *= vec4(toLinear(texel.rgb), texel.a);
+          }}
+          float metallic = u_metallic;
+          float roughness = u_roughness;
+          if (u_hasMetallicRoughnessTexture) {{
+            vec4 texel = texture(u_metallicRoughnessTexture, v_texcoord);
+            roughness *= texel.g;
+            metallic *= texel.b;
+          }}
+          roughness = clamp(roughness, 0.04, 1.0);
+          metallic = clamp(metallic, 0.0, 1.0);
+
+          vec3 normal = normalize(v_normal);
+          if (u_hasNormalTexture) {{
+            normal = perturbedNormal(normal);
+          }}
+          vec3 toEye = normalize(u_eyePosition - v_position);
+          float nDotV = max(dot(normal, toEye), 1e-4);
+
+          vec3 f0 = mix(vec3(0.04), baseColor.rgb, metallic);
+          float alpha = roughness * roughness;
+          float k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+
+          vec3 color = vec3(0.0);
+          for (int i = 0; i < MAX_LIGHTS; i++) {{
+            if (i >= u_lightCount) {{ break; }}
+            vec3 toLight;
+            vec3 radiance = lightRadiance(u_lights[i], v_position, toLight);
+            float nDotL = dot(normal, toLight);
+            if (nDotL <= 0.0) {{ continue; }}
+
+            vec3 halfway = normalize(toLight + toEye);
+            float nDotH = max(dot(normal, halfway), 0.0);
+            float vDotH = max(dot(toEye, halfway), 0.0);
+            float d = nDotH * nDotH * (alpha * alpha - 1.0) + 1.0;
+            float distribution = alpha * alpha / (PI * d * d);
+            float geometry = nDotL / (nDotL * (1.0 - k) + k) * nDotV / (nDotV * (1.0 - k) + k);
+            vec3 fresnel = f0 + (1.0 - f0) * pow(1.0 - vDotH, 5.0);
+
+            vec3 diffuse = (1.0 - fresnel) * (1.0 - metallic) * baseColor.rgb / PI;
+            vec3 specular = distribution * geometry * fresnel / (4.0 * nDotL * nDotV);
+            color += (diffuse + specular) * radiance * nDotL;
+          }}
+
+          float occlusion = 1.0;
+          if (u_hasOcclusionTexture) {{
+            occlusion = mix(1.0, texture(u_occlusionTexture, v_texcoord).r, u_occlusionStrength);
+          }}
+          color += u_ambient * baseColor.rgb * occlusion;
+
+          vec3 emissive = u_emissive;
+          if (u_hasEmissiveTexture) {{
+            emissive *= toLinear(texture(u_emissiveTexture, v_texcoord).rgb);
+          }}
+          color += emissive;
+
+          outColor = vec4(pow(color, vec3(1.0 / 2.2)), baseColor.a);
+        }}
+     "#, lights = lights()))
+}
+
+pub fn vertex_color_point() -> Result<String> {
+  Ok(r#"#version 300 es
+
+        precision highp float;
+
+        in vec4 v_color;
+
+        // Whether to draw round points rather than squares
+        uniform bool u_round;
+
+        out vec4 outColor;
+
+        void main() {
+          // gl_PointCoord spans the point square from 0 to 1
+          if (u_round && length(gl_PointCoord - vec2(0.5)) > 0.5) {
+            discard;
+          }
+          outColor = v_color;
+        }
+     "#.to_string()
+  )
+}
+
+/// Fragment shader of depth-only passes, such as shadow map rendering
+pub fn depth_only() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+
+        void main() {}
+     "#.to_string()
+  )
+}
+
+/// Fragment shader of the fast approximate anti-aliasing post-processing effect
+pub fn fxaa() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D u_input0;
+
+        in vec2 v_uv;
+        out vec4 outColor;
+
+        const float REDUCE_MIN = 1.0 / 128.0;
+        const float REDUCE_MUL = 1.0 / 8.0;
+        const float SPAN_MAX = 8.0;
+
+        float luma(vec3 color) {
+          return dot(color, vec3(0.299, 0.587, 0.114));
+        }
+
+        void main() {
+          vec2 texel = 1.0 / vec2(textureSize(u_input0, 0));
+          vec4 center = texture(u_input0, v_uv);
+          float lumaNW = luma(texture(u_input0, v_uv + vec2(-1.0, -1.0) * texel).rgb);
+          float lumaNE = luma(texture(u_input0, v_uv + vec2(1.0, -1.0) * texel).rgb);
+          float lumaSW = luma(texture(u_input0, v_uv + vec2(-1.0, 1.0) * texel).rgb);
+          float lumaSE = luma(texture(u_input0, v_uv + vec2(1.0, 1.0) * texel).rgb);
+          float lumaM = luma(center.rgb);
+          float lumaMin = min(lumaM, min(min(lumaNW, lumaNE), min(lumaSW, lumaSE)));
+          float lumaMax = max(lumaM, max(max(lumaNW, lumaNE), max(lumaSW, lumaSE)));
+
+          // blur along the edge, perpendicular to the luma gradient
+          vec2 direction = vec2(-((lumaNW + lumaNE) - (lumaSW + lumaSE)), (lumaNW + lumaSW) - (lumaNE + lumaSE));
+          float reduce = max((lumaNW + lumaNE + lumaSW + lumaSE) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+          float scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+          direction = clamp(direction * scale, vec2(-SPAN_MAX), vec2(SPAN_MAX)) * texel;
+
+          vec3 rgbA = 0.5 * (
+            texture(u_input0, v_uv + direction * (1.0 / 3.0 - 0.5)).rgb
+            + texture(u_input0, v_uv + direction * (2.0 / 3.0 - 0.5)).rgb);
+          vec3 rgbB = rgbA * 0.5 + 0.25 * (
+            texture(u_input0, v_uv - direction * 0.5).rgb
+            + texture(u_input0, v_uv + direction * 0.5).rgb);
+          float lumaB = luma(rgbB);
+          outColor = vec4((lumaB < lumaMin || lumaB > lumaMax) ? rgbA : rgbB, center.a);
+        }
+     "#.to_string()
+  )
+}
+
+/// Fragment shader of the tone mapping post-processing effect, mapping high
+/// dynamic range colors scaled by `u_exposure` to the 0 to 1 range with the
+/// Reinhard (`u_operator` 0) or ACES filmic (`u_operator` 1) curve
+pub fn tone_mapping() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D u_input0;
+        uniform float u_exposure;
+        uniform int u_operator;
+
+        in vec2 v_uv;
+        out vec4 outColor;
+
+        // Narkowicz fit of the ACES filmic curve
+        vec3 aces(vec3 x) {
+          return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), 0.0, 1.0);
+        }
+
+        void main() {
+          vec4 color = texture(u_input0, v_uv);
+          vec3 exposed = color.rgb * u_exposure;
+          vec3 mapped = u_operator == 0 ? exposed / (1.0 + exposed) : aces(exposed);
+          outColor = vec4(mapped, color.a);
+        }
+     "#.to_string()
+  )
+}
+
+/// Fragment shader of the gamma correction post-processing effect
+pub fn gamma() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D u_input0;
+        uniform float u_gamma;
+
+        in vec2 v_uv;
+        out vec4 outColor;
+
+        void main() {
+          vec4 color = texture(u_input0, v_uv);
+          outColor = vec4(pow(max(color.rgb, 0.0), vec3(1.0 / u_gamma)), color.a);
+        }
+     "#.to_string()
+  )
+}
+
+/// Fragment shader of the vignette post-processing effect, darkening by up to
+/// `u_intensity` the fragments further than `u_radius` from the center, over
+/// a `u_softness` wide transition. Distances are 1 in the corners.
+pub fn vignette() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D u_input0;
+        uniform float u_intensity;
+        uniform float u_radius;
+        uniform float u_softness;
+
+        in vec2 v_uv;
+        out vec4 outColor;
+
+        void main() {
+          vec4 color = texture(u_input0, v_uv);
+          float distance = length(v_uv - 0.5) * 1.41421356;
+          float shade = 1.0 - smoothstep(u_radius - u_softness, u_radius, distance);
+          outColor = vec4(color.rgb * mix(1.0, shade, u_intensity), color.a);
+        }
+     "#.to_string()
+  )
+}
+
+/// Fragment shader of the bright pass of bloom, keeping the part of the
+/// colors brighter than `u_threshold`
+pub fn bloom_threshold() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D u_input0;
+        uniform float u_threshold;
+
+        in vec2 v_uv;
+        out vec4 outColor;
+
+        void main() {
+          vec3 color = texture(u_input0, v_uv).rgb;
+          float brightness = max(color.r, max(color.g, color.b));
+          float keep = max(brightness - u_threshold, 0.0) / max(brightness, 1e-4);
+          outColor = vec4(color * keep, 1.0);
+        }
+     "#.to_string()
+  )
+}
+
+/// Fragment shader of a 9 tap separable gaussian blur, horizontal when
+/// `u_horizontal` is 1 and vertical otherwise
+pub fn gaussian_blur() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D u_input0;
+        uniform int u_horizontal;
+
+        in vec2 v_uv;
+        out vec4 outColor;
+
+        const float weights[5] = float[](0.2270270, 0.1945946, 0.1216216, 0.0540540, 0.0162162);
+
+        void main() {
+          vec2 texel = 1.0 / vec2(textureSize(u_input0, 0));
+          vec2 offset = u_horizontal == 1 ? vec2(texel.x, 0.0) : vec2(0.0, texel.y);
+          vec3 sum = texture(u_input0, v_uv).rgb * weights[0];
+          for (int i = 1; i < 5; i++) {
+            sum += (texture(u_input0, v_uv + offset * float(i)).rgb
+              + texture(u_input0, v_uv - offset * float(i)).rgb) * weights[i];
+          }
+          outColor = vec4(sum, 1.0);
+        }
+     "#.to_string()
+  )
+}
+
+/// Fragment shader adding the blurred bright pass `u_bloom`, scaled by
+/// `u_intensity`, to the colors
+pub fn bloom_composite() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D u_input0;
+        uniform sampler2D u_bloom;
+        uniform float u_intensity;
+
+        in vec2 v_uv;
+        out vec4 outColor;
+
+        void main() {
+          vec4 color = texture(u_input0, v_uv);
+          outColor = vec4(color.rgb + u_intensity * texture(u_bloom, v_uv).rgb, color.a);
+        }
+     "#.to_string()
+  )
+}
+
+/// Fragment shader of object picking passes, writing the identifier
+/// `u_id` into an unsigned integer color buffer
+pub fn object_id() -> Result<String> {
+  Ok(r#"#version 300 es
+        precision highp float;
+        precision highp int;
+
+        uniform int u_id;
+
+        out uint outId;
+
+        void main() {
+          outId = uint(u_id);
+        }
+     "#.to_string()
+  )
+}
@@ -23,6 +23,8 @@ pub fn matrix_position_normal() -> Result<String> {
 
        // A matrix to transform the positions by
        uniform mat4 u_matrix;
+       // A matrix to transform the normals by
+       uniform mat4 u_normalMatrix;
 
        // varying to pass the normal to the fragment shader
        out vec3 v_normal;
@@ -32,10 +34,128 @@ pub fn matrix_position_normal() -> Result<String> {
          // Multiply the position by the matrix.
          gl_Position = u_matrix * a_position;
        
-         // Pass the normal to the fragment shader
-         v_normal = a_normal;
+         // Orient the normal and pass it to the fragment shader
+         v_normal = mat3(u_normalMatrix) * a_normal;
        }
      "#
      .to_string()
   )
 }
+
+pub fn matrix_model_position_normal() -> Result<String> {
+  Ok(
+    r#"#version 300 es
+
+       in vec4 a_position;
+       in vec3 a_normal;
+
+       uniform mat4 u_matrix;
+       // Model transform, placing the position in world space
+       uniform mat4 u_model;
+       uniform mat4 u_normalMatrix;
+
+       out vec3 v_position;
+       out vec3 v_normal;
+
+       void main() {
+         gl_Position = u_matrix * a_position;
+         v_position = (u_model * a_position).xyz;
+         v_normal = mat3(u_normalMatrix) * a_normal;
+       }
+     "#
+     .to_string()
+  )
+}
+
+pub fn matrix_model_position_normal_texcoord() -> Result<String> {
+  Ok(
+    r#"#version 300 es
+
+       in vec4 a_position;
+       in vec3 a_normal;
+       in vec2 a_texcoord;
+
+       uniform mat4 u_matrix;
+       // Model transform, placing the position in world space
+       uniform mat4 u_model;
+       uniform mat4 u_normalMatrix;
+
+       out vec3 v_position;
+       out vec3 v_normal;
+       out vec2 v_texcoord;
+
+       void main() {
+         gl_Position = u_matrix * a_position;
+         v_position = (u_model * a_position).xyz;
+         v_normal = mat3(u_normalMatrix) * a_normal;
+         v_texcoord = a_texcoord;
+       }
+     "#
+     .to_string()
+  )
+}
+
+pub fn matrix_position_normal_texcoord() -> Result<String> {
+  Ok(
+    r#"#version 300 es
+
+       in vec4 a_position;
+       in vec3 a_normal;
+       in vec2 a_texcoord;
+
+       uniform mat4 u_matrix;
+       uniform mat4 u_normalMatrix;
+
+       out vec3 v_normal;
+       out vec2 v_texcoord;
+
+       void main() {
+         gl_Position = u_matrix * a_position;
+         v_normal = mat3(u_normalMatrix) * a_normal;
+         v_texcoord = a_texcoord;
+       }
+     "#
+     .to_string()
+  )
+}
+
+pub fn matrix_position_color_point_size() -> Result<String> {
+  Ok(
+    r#"#version 300 es
+
+       in vec4 a_position;
+       in vec4 a_color;
+
+       uniform mat4 u_matrix;
+       // Diameter of the points, in pixels
+       uniform float u_pointSize;
+
+       out vec4 v_color;
+
+       void main() {
+         gl_Position = u_matrix * a_position;
+         gl_PointSize = u_pointSize;
+         v_color = a_color;
+       }
+     "#
+     .to_string()
+  )
+}
+
+/// Vertex shader of full-screen passes, drawing a single triangle covering
+/// the viewport from `gl_VertexID` without vertex attributes
+pub fn full_screen() -> Result<String> {
+  Ok(r#"#version 300 es
+
+       // texture coordinates of the fragment, 0 to 1 over the viewport
+       out vec2 v_uv;
+
+       void main() {
+         // vertices (0, 0), (2, 0) and (0, 2) in texture coordinates
+         vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+         v_uv = position;
+         gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+       }
+     "#.to_string()
+  )
+}
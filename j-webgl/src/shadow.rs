@@ -0,0 +1,120 @@
+use super::{Result, gl, algebra, texture, target, structs::light::Light};
+
+use std::rc::Rc;
+use gl::GlContext;
+
+/// Shadow settings of a [`Renderer`](crate::Renderer)
+///
+/// Shadows are cast by the first directional light set to cast shadows, see
+/// [`Light::set_cast_shadow`]. The light sees the scene through an
+/// orthographic box of half-size `extent` centered on `center`, so only
+/// objects within `extent` of `center` cast shadows: fit it to the region
+/// of interest, as a larger box spreads the shadow map texels further apart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowSettings {
+  /// Width and height of the shadow map in texels
+  pub size: u32,
+  /// Depth offset, in shadow map depth units from 0 to 1, below which a
+  /// surface is not shadowed by itself
+  pub bias: f32,
+  /// Radius in texels of the percentage closer filtering kernel, 0 for hard shadows
+  pub pcf_radius: u32,
+  /// Center of the shadowed region in world space
+  pub center: algebra::Point3,
+  /// Half-size of the shadowed region
+  pub extent: f32,
+}
+
+impl Default for ShadowSettings {
+  fn default() -> Self {
+    ShadowSettings { size: 1024, bias: 0.005, pcf_radius: 1, center: algebra::Point3::origin(), extent: 10.0 }
+  }
+}
+
+impl ShadowSettings {
+  /// View and projection matrices of the shadow pass of a directional light
+  pub fn light_matrices(&self, light: &Light) -> (algebra::Matrix4, algebra::Matrix4) {
+    let direction = light.direction();
+    let eye = &self.center + &(-2.0 * self.extent * direction);
+    let up = if direction.dy().abs() > 0.99 { algebra::Vector3::z() } else { algebra::Vector3::y() };
+    let view = algebra::Matrix4::look_at_rh(&eye, &self.center, &up);
+    let e = self.extent;
+    let projection = algebra::Matrix4::new_orthographic(-e, e, -e, e, e, 3.0 * e);
+    (view, projection)
+  }
+}
+
+/// Shadow map of the current frame, handed to renderables through
+/// [`FrameContext::shadow`](crate::FrameContext::shadow)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameShadow {
+  /// Depth texture rendered by the shadow pass
+  pub texture: gl::Texture,
+  /// Matrix transforming world positions into the clip space of the shadow pass
+  pub matrix: algebra::Matrix4,
+  /// Index of the shadow casting light in [`FrameContext::lights`](crate::FrameContext::lights)
+  pub light: usize,
+  pub bias: f32,
+  pub pcf_radius: u32,
+}
+
+/// Depth-only [`RenderTarget`](target::RenderTarget) rendered by shadow passes
+pub struct ShadowMap {
+  target: target::RenderTarget,
+}
+
+impl ShadowMap {
+  /// Create a `size` by `size` shadow map
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::UnsupportedOperation`](crate::Error::UnsupportedOperation) if the framebuffer is not complete.
+  pub fn new(context: &Rc<dyn GlContext>, size: u32) -> Result<Self> {
+    let settings = target::RenderTargetSettings { color: None, depth: Some(texture::TextureFormat::Depth32F), samples: 0 };
+    Ok(ShadowMap { target: target::RenderTarget::new(context, size, size, settings)? })
+  }
+
+  /// Bind the framebuffer and set the viewport to cover the shadow map
+  pub fn bind(&self) {
+    self.target.bind();
+  }
+
+  pub fn size(&self) -> u32 { self.target.width() }
+
+  pub fn depth(&self) -> &texture::Texture2D {
+    self.target.depth_texture().expect("shadow maps have a depth texture")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use gl::{RecordingContext, Call};
+  use web_sys::WebGl2RenderingContext as GL;
+
+  #[test]
+  fn light_matrices_fit_the_region() {
+    let settings = ShadowSettings { center: algebra::Point3::new(1.0, 0.0, 0.0), extent: 2.0, ..Default::default() };
+    let (view, projection) = settings.light_matrices(&Light::directional(0.0, -1.0, 0.0));
+    let view_projection = &projection * &view;
+    let center = view_projection.transform_point(&settings.center);
+    assert!(center.x().abs() < 1e-5 && center.y().abs() < 1e-5 && center.z().abs() < 1e-5);
+    let corner = view_projection.transform_point(&algebra::Point3::new(3.0, 2.0, 2.0));
+    assert!(corner.x().abs().max(corner.y().abs()) <= 1.0 + 1e-5);
+    assert!((corner.z() + 1.0).abs() < 1e-5, "closest point to the light maps to the near plane");
+  }
+
+  #[test]
+  fn attaches_depth_texture() {
+    let recording = Rc::new(RecordingContext::new(8, 8));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let shadow_map = ShadowMap::new(&context, 256).unwrap();
+    assert_eq!(shadow_map.size(), 256);
+    let texture = shadow_map.depth().texture().0.id().unwrap();
+    assert!(recording.calls().contains(&Call::FramebufferTexture2D {
+      target: GL::FRAMEBUFFER, attachment: GL::DEPTH_ATTACHMENT, textarget: GL::TEXTURE_2D, texture: Some(texture), level: 0,
+    }));
+    drop(shadow_map);
+    assert!(matches!(recording.calls().last(), Some(Call::DeleteTexture(_))));
+  }
+}
@@ -2,7 +2,12 @@ use super::*;
 
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub struct Basic {
+  eye: algebra::Point3,
+  view: algebra::Matrix4,
+  projection: algebra::Matrix4,
   matrix: algebra::Matrix4,
+  width: f32,
+  height: f32,
 }
 
 #[wasm_bindgen::prelude::wasm_bindgen]
@@ -22,7 +27,7 @@ impl Basic {
     let view = algebra::Matrix4::look_at_rh(&eye, &target, &up);
 
     let matrix = &projection * &view;
-    Ok(Basic { matrix })
+    Ok(Basic { eye, view, projection, matrix, width, height })
   }
 }
 
@@ -30,4 +35,10 @@ impl traits::Camera for Basic {
   fn as_f32_array(&self) -> Result<&[f32]> {
     Ok(self.matrix.as_slice())
   }
+
+  fn view(&self) -> Result<algebra::Matrix4> { Ok(self.view) }
+  fn projection(&self) -> Result<algebra::Matrix4> { Ok(self.projection) }
+  fn view_projection(&self) -> Result<algebra::Matrix4> { Ok(self.matrix) }
+  fn eye_position(&self) -> Result<algebra::Point3> { Ok(self.eye) }
+  fn viewport_size(&self) -> Option<(f32, f32)> { Some((self.width, self.height)) }
 }
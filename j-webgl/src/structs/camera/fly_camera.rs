@@ -0,0 +1,205 @@
+use super::*;
+
+const MAX_PITCH: f32 = 0.49 * std::f32::consts::PI;
+
+/// First-person camera flying freely through the scene
+///
+/// The camera holds a position with a `yaw` (rotation around the y axis, 0
+/// looking down -z and positive turning right) and a `pitch` (positive looking
+/// up), clamped short of the vertical. Movements take a time step in seconds
+/// and are scaled by the movement speed, so that keyboard state can be
+/// integrated once per frame:
+///
+/// ```rust
+/// # use j_webgl::structs::camera::FlyCamera;
+/// # let mut camera = FlyCamera::new(640.0, 480.0).unwrap();
+/// # let (w_pressed, a_pressed, dt) = (true, false, 0.016);
+/// if w_pressed { camera.move_forward(dt); }
+/// if a_pressed { camera.strafe(-dt); }
+/// ```
+#[wasm_bindgen::prelude::wasm_bindgen]
+#[derive(Clone)]
+pub struct FlyCamera {
+  position: algebra::Point3,
+  yaw: f32,
+  pitch: f32,
+  projection: Projection,
+  width: f32,
+  height: f32,
+  move_speed: f32,
+  look_speed: f32,
+  view: algebra::Matrix4,
+  projection_matrix: algebra::Matrix4,
+  matrix: algebra::Matrix4,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl FlyCamera {
+  /// Create a camera at the [`Basic`](super::Basic) eye position looking at the origin
+  #[wasm_bindgen(constructor)]
+  pub fn new(width: f32, height: f32) -> Result<Self> {
+    let direction = algebra::Vector3::new(-EYE_X, -EYE_Y, -EYE_Z).normalize();
+    let mut camera = FlyCamera {
+      position: algebra::Point3::new(EYE_X, EYE_Y, EYE_Z),
+      yaw: direction.dx().atan2(-direction.dz()),
+      pitch: direction.dy().asin(),
+      projection: Projection::default(),
+      width, height,
+      move_speed: 1.0,
+      look_speed: std::f32::consts::PI / 720.0,
+      view: algebra::Matrix4::identity(),
+      projection_matrix: algebra::Matrix4::identity(),
+      matrix: algebra::Matrix4::identity(),
+    };
+    camera.update();
+    Ok(camera)
+  }
+
+  pub fn set_position(&mut self, x: f32, y: f32, z: f32) {
+    self.position = algebra::Point3::new(x, y, z);
+    self.update();
+  }
+
+  /// Set yaw and pitch in radians, the pitch being clamped short of the vertical
+  pub fn set_orientation(&mut self, yaw_in_radians: f32, pitch_in_radians: f32) {
+    self.yaw = yaw_in_radians;
+    self.pitch = pitch_in_radians.clamp(-MAX_PITCH, MAX_PITCH);
+    self.update();
+  }
+
+  /// Switch to a perspective projection
+  pub fn set_perspective(&mut self, field_of_view_in_radians: f32, near: f32, far: f32) {
+    self.set_projection(Projection::Perspective { field_of_view: field_of_view_in_radians, near, far });
+  }
+
+  /// Switch to an orthographic projection of the given view-space box
+  pub fn set_orthographic(&mut self, left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) {
+    self.set_projection(Projection::Orthographic { left, right, bottom, top, near, far });
+  }
+
+  pub fn set_near_far(&mut self, near: f32, far: f32) {
+    self.set_projection(self.projection.with_near_far(near, far));
+  }
+
+  /// Set the viewport size in CSS pixels. An orthographic box keeps its
+  /// height and center, its width following the new aspect ratio.
+  pub fn resize(&mut self, width: f32, height: f32) {
+    self.width = width;
+    self.height = height;
+    self.projection = self.projection.with_aspect(width / height);
+    self.update();
+  }
+
+  /// Set the movement speed in units per second and the look speed in radians per pixel
+  pub fn set_speeds(&mut self, move_speed: f32, look_speed: f32) {
+    self.move_speed = move_speed;
+    self.look_speed = look_speed;
+  }
+
+  pub fn yaw(&self) -> f32 { self.yaw }
+  pub fn pitch(&self) -> f32 { self.pitch }
+
+  /// Move along the view direction for `dt` seconds (backwards when negative)
+  pub fn move_forward(&mut self, dt: f32) {
+    self.translate(&self.forward(), dt);
+  }
+
+  /// Move sideways for `dt` seconds, to the right when positive
+  pub fn strafe(&mut self, dt: f32) {
+    self.translate(&self.right(), dt);
+  }
+
+  /// Move along the world up axis for `dt` seconds
+  pub fn ascend(&mut self, dt: f32) {
+    self.translate(&algebra::Vector3::y(), dt);
+  }
+
+  /// Turn following a mouse movement of `dx`, `dy` pixels
+  pub fn look(&mut self, dx: f32, dy: f32) {
+    self.set_orientation(self.yaw + dx * self.look_speed, self.pitch - dy * self.look_speed);
+  }
+}
+
+impl FlyCamera {
+  pub fn position(&self) -> algebra::Point3 { self.position }
+
+  pub fn projection(&self) -> Projection { self.projection }
+
+  pub fn set_projection(&mut self, projection: Projection) {
+    self.projection = projection;
+    self.update();
+  }
+
+  /// Returns the unit view direction
+  pub fn forward(&self) -> algebra::Vector3 {
+    let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+    let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+    algebra::Vector3::new(sin_yaw * cos_pitch, sin_pitch, -cos_yaw * cos_pitch)
+  }
+
+  /// Returns the unit horizontal vector pointing to the right of the view
+  pub fn right(&self) -> algebra::Vector3 {
+    let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+    algebra::Vector3::new(cos_yaw, 0.0, sin_yaw)
+  }
+
+  fn translate(&mut self, direction: &algebra::Vector3, dt: f32) {
+    self.position = &self.position + &(direction * (dt * self.move_speed));
+    self.update();
+  }
+
+  fn update(&mut self) {
+    let projection = self.projection.matrix(self.width / self.height);
+    let target = &self.position + &self.forward();
+    let view = algebra::Matrix4::look_at_rh(&self.position, &target, &algebra::Vector3::y());
+    self.matrix = &projection * &view;
+    self.view = view;
+    self.projection_matrix = projection;
+  }
+}
+
+impl traits::Camera for FlyCamera {
+  fn as_f32_array(&self) -> Result<&[f32]> {
+    Ok(self.matrix.as_slice())
+  }
+
+  fn view(&self) -> Result<algebra::Matrix4> { Ok(self.view) }
+  fn projection(&self) -> Result<algebra::Matrix4> { Ok(self.projection_matrix) }
+  fn view_projection(&self) -> Result<algebra::Matrix4> { Ok(self.matrix) }
+  fn eye_position(&self) -> Result<algebra::Point3> { Ok(self.position) }
+  fn viewport_size(&self) -> Option<(f32, f32)> { Some((self.width, self.height)) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn starts_looking_at_origin() {
+    let camera = FlyCamera::new(640.0, 480.0).unwrap();
+    let to_origin = (&algebra::Point3::origin() - &camera.position()).normalize();
+    assert!((camera.forward().dot(&to_origin) - 1.0).abs() < 1e-5);
+  }
+
+  #[test]
+  fn moves_relative_to_orientation() {
+    let mut camera = FlyCamera::new(640.0, 480.0).unwrap();
+    camera.set_position(0.0, 0.0, 0.0);
+    camera.set_orientation(0.0, 0.0);
+    camera.set_speeds(2.0, 0.01);
+    camera.move_forward(0.5);
+    assert_eq!(camera.position(), algebra::Point3::new(0.0, 0.0, -1.0));
+    camera.strafe(0.5);
+    camera.ascend(-0.5);
+    assert_eq!(camera.position(), algebra::Point3::new(1.0, -1.0, -1.0));
+  }
+
+  #[test]
+  fn look_clamps_pitch() {
+    let mut camera = FlyCamera::new(640.0, 480.0).unwrap();
+    camera.look(10.0, -1.0e6);
+    assert_eq!(camera.pitch(), MAX_PITCH);
+    camera.look(0.0, 1.0e6);
+    assert_eq!(camera.pitch(), -MAX_PITCH);
+  }
+}
@@ -3,3 +3,8 @@ use super::*;
 const EYE_X: f32 = 1.0; const EYE_Y: f32 = 5.0; const EYE_Z: f32 = 5.0;
 
 mod basic; pub use basic::{ Basic };
+mod orbit; pub use orbit::{ Orbit };
+mod orbit_controls; pub use orbit_controls::{ OrbitControls };
+mod fly_camera; pub use fly_camera::{ FlyCamera };
+mod projection; pub use projection::{ Projection };
+mod orthographic; pub use orthographic::{ Orthographic };
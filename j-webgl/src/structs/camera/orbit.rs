@@ -0,0 +1,260 @@
+use super::*;
+
+const MIN_ELEVATION: f32 = -0.49 * std::f32::consts::PI;
+const MAX_ELEVATION: f32 = 0.49 * std::f32::consts::PI;
+
+/// Camera orbiting around a target point
+///
+/// The eye sits at `distance` from `target`, at an `azimuth` measured around the
+/// y axis from the +z axis towards the +x axis, and an `elevation` measured from
+/// the xz plane towards +y. Drag, pan and zoom deltas are expressed in CSS pixels
+/// (or wheel delta units) so that they can be fed straight from input events, see
+/// [`OrbitControls`](super::OrbitControls) for a browser helper.
+#[wasm_bindgen::prelude::wasm_bindgen]
+#[derive(Clone)]
+pub struct Orbit {
+  target: algebra::Point3,
+  distance: f32,
+  azimuth: f32,
+  elevation: f32,
+  projection: Projection,
+  width: f32,
+  height: f32,
+  min_distance: f32,
+  max_distance: f32,
+  rotate_speed: f32,
+  zoom_speed: f32,
+  view: algebra::Matrix4,
+  projection_matrix: algebra::Matrix4,
+  matrix: algebra::Matrix4,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl Orbit {
+  /// Create an orbit camera around the origin, with the eye at the same
+  /// position as the [`Basic`](super::Basic) camera
+  #[wasm_bindgen(constructor)]
+  pub fn new(width: f32, height: f32) -> Result<Self> {
+    let eye = algebra::Vector3::new(EYE_X, EYE_Y, EYE_Z);
+    let distance = eye.norm();
+    let mut orbit = Orbit {
+      target: algebra::Point3::origin(),
+      distance,
+      azimuth: EYE_X.atan2(EYE_Z),
+      elevation: (EYE_Y / distance).asin(),
+      projection: Projection::default(),
+      width, height,
+      min_distance: 0.01,
+      max_distance: f32::INFINITY,
+      rotate_speed: std::f32::consts::PI / 360.0,
+      zoom_speed: 0.001,
+      view: algebra::Matrix4::identity(),
+      projection_matrix: algebra::Matrix4::identity(),
+      matrix: algebra::Matrix4::identity(),
+    };
+    orbit.update();
+    Ok(orbit)
+  }
+
+  pub fn set_target(&mut self, x: f32, y: f32, z: f32) {
+    self.target = algebra::Point3::new(x, y, z);
+    self.update();
+  }
+
+  /// Set the distance from eye to target, clamped to the distance limits.
+  /// With an orthographic projection, the projection box is scaled alike so
+  /// that zooming keeps its effect.
+  pub fn set_distance(&mut self, distance: f32) {
+    let distance = distance.clamp(self.min_distance, self.max_distance);
+    if self.distance > 0.0 {
+      self.projection = self.projection.scaled(distance / self.distance);
+    }
+    self.distance = distance;
+    self.update();
+  }
+
+  pub fn set_distance_limits(&mut self, min_distance: f32, max_distance: f32) {
+    self.min_distance = min_distance;
+    self.max_distance = max_distance;
+    self.set_distance(self.distance);
+  }
+
+  pub fn set_azimuth(&mut self, azimuth_in_radians: f32) {
+    self.azimuth = azimuth_in_radians;
+    self.update();
+  }
+
+  /// Set the elevation, clamped just short of the poles to keep the up vector defined
+  pub fn set_elevation(&mut self, elevation_in_radians: f32) {
+    self.elevation = elevation_in_radians.clamp(MIN_ELEVATION, MAX_ELEVATION);
+    self.update();
+  }
+
+  /// Switch to a perspective projection
+  pub fn set_perspective(&mut self, field_of_view_in_radians: f32, near: f32, far: f32) {
+    self.set_projection(Projection::Perspective { field_of_view: field_of_view_in_radians, near, far });
+  }
+
+  /// Switch to an orthographic projection of the given view-space box
+  pub fn set_orthographic(&mut self, left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) {
+    self.set_projection(Projection::Orthographic { left, right, bottom, top, near, far });
+  }
+
+  /// Switch to an orthographic projection framing the same region of the
+  /// target plane as the current perspective projection
+  pub fn set_orthographic_from_perspective(&mut self) {
+    self.set_projection(self.projection.to_orthographic(self.distance, self.width / self.height));
+  }
+
+  pub fn set_near_far(&mut self, near: f32, far: f32) {
+    self.set_projection(self.projection.with_near_far(near, far));
+  }
+
+  /// Set the viewport size in CSS pixels. An orthographic box keeps its
+  /// height and center, its width following the new aspect ratio.
+  pub fn resize(&mut self, width: f32, height: f32) {
+    self.width = width;
+    self.height = height;
+    self.projection = self.projection.with_aspect(width / height);
+    self.update();
+  }
+
+  /// Set the rotation in radians per pixel of drag and the zoom factor per wheel unit
+  pub fn set_speeds(&mut self, rotate_speed: f32, zoom_speed: f32) {
+    self.rotate_speed = rotate_speed;
+    self.zoom_speed = zoom_speed;
+  }
+
+  pub fn distance(&self) -> f32 { self.distance }
+  pub fn azimuth(&self) -> f32 { self.azimuth }
+  pub fn elevation(&self) -> f32 { self.elevation }
+
+  /// Rotate around the target following a drag of `dx`, `dy` pixels.
+  /// Dragging right turns the scene right, dragging down tilts the eye up.
+  pub fn rotate(&mut self, dx: f32, dy: f32) {
+    self.azimuth -= dx * self.rotate_speed;
+    self.elevation = (self.elevation + dy * self.rotate_speed).clamp(MIN_ELEVATION, MAX_ELEVATION);
+    self.update();
+  }
+
+  /// Move the target in the view plane so that the point under the cursor
+  /// follows a drag of `dx`, `dy` pixels
+  pub fn pan(&mut self, dx: f32, dy: f32) {
+    let (right, up) = self.axes();
+    let units_per_pixel = match self.projection {
+      Projection::Perspective { field_of_view, .. } =>
+        2.0 * self.distance * (0.5 * field_of_view).tan() / self.height.max(1.0),
+      Projection::Orthographic { bottom, top, .. } => (top - bottom) / self.height.max(1.0),
+    };
+    let offset = &(&right * (-dx * units_per_pixel)) + &(&up * (dy * units_per_pixel));
+    self.target = &self.target + &offset;
+    self.update();
+  }
+
+  /// Move the eye towards (negative `delta`) or away from (positive `delta`) the target
+  pub fn zoom(&mut self, delta: f32) {
+    self.set_distance(self.distance * (delta * self.zoom_speed).exp());
+  }
+
+  /// Scale the distance to the target by `factor`, e.g. from a pinch gesture
+  pub fn zoom_by(&mut self, factor: f32) {
+    if factor > 0.0 { self.set_distance(self.distance * factor); }
+  }
+}
+
+impl Orbit {
+  pub fn target(&self) -> algebra::Point3 { self.target }
+
+  pub fn projection(&self) -> Projection { self.projection }
+
+  pub fn set_projection(&mut self, projection: Projection) {
+    self.projection = projection;
+    self.update();
+  }
+
+  /// Returns the eye position
+  pub fn eye(&self) -> algebra::Point3 {
+    let (sin_el, cos_el) = self.elevation.sin_cos();
+    let (sin_az, cos_az) = self.azimuth.sin_cos();
+    let direction = algebra::Vector3::new(cos_el * sin_az, sin_el, cos_el * cos_az);
+    &self.target + &(&direction * self.distance)
+  }
+
+  /// Returns the camera right and up unit vectors in world space
+  fn axes(&self) -> (algebra::Vector3, algebra::Vector3) {
+    let backward = (&self.eye() - &self.target).normalize();
+    let right = algebra::Vector3::y().cross(&backward).normalize();
+    let up = backward.cross(&right);
+    (right, up)
+  }
+
+  fn update(&mut self) {
+    let projection = self.projection.matrix(self.width / self.height);
+    let view = algebra::Matrix4::look_at_rh(&self.eye(), &self.target, &algebra::Vector3::y());
+    self.matrix = &projection * &view;
+    self.view = view;
+    self.projection_matrix = projection;
+  }
+}
+
+impl traits::Camera for Orbit {
+  fn as_f32_array(&self) -> Result<&[f32]> {
+    Ok(self.matrix.as_slice())
+  }
+
+  fn view(&self) -> Result<algebra::Matrix4> { Ok(self.view) }
+  fn projection(&self) -> Result<algebra::Matrix4> { Ok(self.projection_matrix) }
+  fn view_projection(&self) -> Result<algebra::Matrix4> { Ok(self.matrix) }
+  fn eye_position(&self) -> Result<algebra::Point3> { Ok(self.eye()) }
+  fn viewport_size(&self) -> Option<(f32, f32)> { Some((self.width, self.height)) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn starts_at_basic_eye() {
+    let orbit = Orbit::new(640.0, 480.0).unwrap();
+    let eye = orbit.eye();
+    assert!((eye.x() - EYE_X).abs() < 1e-5 && (eye.y() - EYE_Y).abs() < 1e-5 && (eye.z() - EYE_Z).abs() < 1e-5);
+  }
+
+  #[test]
+  fn rotate_clamps_elevation() {
+    let mut orbit = Orbit::new(640.0, 480.0).unwrap();
+    orbit.rotate(0.0, 1.0e6);
+    assert_eq!(orbit.elevation(), MAX_ELEVATION);
+    orbit.rotate(0.0, -1.0e6);
+    assert_eq!(orbit.elevation(), MIN_ELEVATION);
+  }
+
+  #[test]
+  fn zoom_scales_distance_within_limits() {
+    let mut orbit = Orbit::new(640.0, 480.0).unwrap();
+    orbit.set_distance_limits(1.0, 10.0);
+    orbit.set_distance(5.0);
+    orbit.zoom(-100.0);
+    assert!(orbit.distance() < 5.0);
+    orbit.zoom(1.0e6);
+    assert_eq!(orbit.distance(), 10.0);
+  }
+
+  #[test]
+  fn zoom_scales_orthographic_box() {
+    let mut orbit = Orbit::new(640.0, 480.0).unwrap();
+    orbit.set_orthographic(-2.0, 2.0, -1.0, 1.0, 0.1, 100.0);
+    orbit.set_distance(orbit.distance() * 2.0);
+    assert_eq!(orbit.projection(), Projection::Orthographic { left: -4.0, right: 4.0, bottom: -2.0, top: 2.0, near: 0.1, far: 100.0 });
+  }
+
+  #[test]
+  fn pan_keeps_view_direction() {
+    let mut orbit = Orbit::new(640.0, 480.0).unwrap();
+    let before = (&orbit.eye() - &orbit.target()).normalize();
+    orbit.pan(25.0, -40.0);
+    let after = (&orbit.eye() - &orbit.target()).normalize();
+    assert!(orbit.target() != algebra::Point3::origin());
+    assert!((before.dot(&after) - 1.0).abs() < 1e-6);
+  }
+}
@@ -0,0 +1,156 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+#[derive(Default)]
+struct Pointers {
+  /// Active pointers with their last position
+  active: Vec<(i32, f32, f32)>,
+}
+
+impl Pointers {
+  fn position(&self, id: i32) -> Option<(f32, f32)> {
+    self.active.iter().find(|(i, _, _)| *i == id).map(|(_, x, y)| (*x, *y))
+  }
+
+  fn set(&mut self, id: i32, x: f32, y: f32) {
+    match self.active.iter_mut().find(|(i, _, _)| *i == id) {
+      Some(p) => { p.1 = x; p.2 = y; },
+      None => self.active.push((id, x, y)),
+    }
+  }
+
+  fn remove(&mut self, id: i32) {
+    self.active.retain(|(i, _, _)| *i != id);
+  }
+
+  /// Midpoint and spread of the first two pointers
+  fn pinch(&self) -> Option<((f32, f32), f32)> {
+    let [(_, x0, y0), (_, x1, y1), ..] = self.active.as_slice() else { return None; };
+    Some((((x0 + x1) * 0.5, (y0 + y1) * 0.5), ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt()))
+  }
+}
+
+struct State {
+  orbit: Orbit,
+  pointers: Pointers,
+  on_change: Option<js_sys::Function>,
+}
+
+type Listener = (&'static str, Closure<dyn FnMut(web_sys::Event)>);
+
+/// Pointer and wheel listeners driving an [`Orbit`] camera from a canvas
+///
+/// A primary-button (or single touch) drag rotates, a secondary-button or
+/// shift drag pans, the wheel zooms and a two-finger gesture pinch-zooms and
+/// pans. The canvas should use the `touch-action: none` CSS property so that
+/// touch gestures are not consumed by the browser. Listeners are removed when
+/// the controls are dropped (or freed from JavaScript).
+///
+/// ```js
+/// const controls = new OrbitControls(canvas, new Orbit(canvas.clientWidth, canvas.clientHeight));
+/// controls.on_change(() => renderer.render(controls.camera()));
+/// ```
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub struct OrbitControls {
+  canvas: web_sys::HtmlCanvasElement,
+  state: Rc<RefCell<State>>,
+  listeners: Vec<Listener>,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl OrbitControls {
+  #[wasm_bindgen(constructor)]
+  pub fn new(canvas: web_sys::HtmlCanvasElement, orbit: Orbit) -> Result<OrbitControls> {
+    let state = Rc::new(RefCell::new(State { orbit, pointers: Pointers::default(), on_change: None }));
+    let mut controls = OrbitControls { canvas, state, listeners: Vec::new() };
+
+    controls.listen("pointerdown", |state, canvas, event: web_sys::PointerEvent| {
+      let _ = canvas.set_pointer_capture(event.pointer_id());
+      state.pointers.set(event.pointer_id(), event.client_x() as f32, event.client_y() as f32);
+      false
+    })?;
+    controls.listen("pointermove", |state, _, event: web_sys::PointerEvent| {
+      let id = event.pointer_id();
+      let Some((x0, y0)) = state.pointers.position(id) else { return false; };
+      let (x, y) = (event.client_x() as f32, event.client_y() as f32);
+      let pinch = state.pointers.pinch();
+      state.pointers.set(id, x, y);
+      match (pinch, state.pointers.pinch()) {
+        (Some(((mx0, my0), d0)), Some(((mx1, my1), d1))) => {
+          if d1 > 0.0 { state.orbit.zoom_by(d0 / d1); }
+          state.orbit.pan(mx1 - mx0, my1 - my0);
+        },
+        _ if event.buttons() & 2 != 0 || event.shift_key() => state.orbit.pan(x - x0, y - y0),
+        _ => state.orbit.rotate(x - x0, y - y0),
+      }
+      true
+    })?;
+    for name in ["pointerup", "pointercancel"] {
+      controls.listen(name, |state, _, event: web_sys::PointerEvent| {
+        state.pointers.remove(event.pointer_id());
+        false
+      })?;
+    }
+    controls.listen("wheel", |state, _, event: web_sys::WheelEvent| {
+      event.prevent_default();
+      state.orbit.zoom(event.delta_y() as f32);
+      true
+    })?;
+    controls.listen("contextmenu", |_, _, event: web_sys::MouseEvent| {
+      event.prevent_default();
+      false
+    })?;
+
+    Ok(controls)
+  }
+
+  /// Register a JavaScript function called after each camera change
+  pub fn on_change(&self, callback: js_sys::Function) {
+    self.state.borrow_mut().on_change = Some(callback);
+  }
+
+  /// Returns a snapshot of the camera, to be passed to the renderer
+  pub fn camera(&self) -> Orbit {
+    self.state.borrow().orbit.clone()
+  }
+
+  /// Update the camera aspect ratio, typically after a canvas resize
+  pub fn resize(&self, width: f32, height: f32) {
+    self.state.borrow_mut().orbit.resize(width, height);
+  }
+}
+
+impl OrbitControls {
+  /// Add an event listener on the canvas. The handler returns `true` when the camera changed.
+  fn listen<E, F>(&mut self, name: &'static str, mut handler: F) -> Result<()>
+  where E: JsCast,
+        F: FnMut(&mut State, &web_sys::HtmlCanvasElement, E) -> bool + 'static,
+  {
+    let state = self.state.clone();
+    let canvas = self.canvas.clone();
+    let closure = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+      let Ok(event) = event.dyn_into::<E>() else { return; };
+      let changed = handler(&mut state.borrow_mut(), &canvas, event);
+      // Release the state before the callback, which may call back into the controls
+      let on_change = if changed { state.borrow().on_change.clone() } else { None };
+      if let Some(f) = on_change {
+        let _ = f.call0(&wasm_bindgen::JsValue::NULL);
+      }
+    });
+    self.canvas.add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())?;
+    self.listeners.push((name, closure));
+    Ok(())
+  }
+}
+
+impl Drop for OrbitControls {
+  fn drop(&mut self) {
+    for (name, closure) in self.listeners.drain(..) {
+      let _ = self.canvas.remove_event_listener_with_callback(name, closure.as_ref().unchecked_ref());
+    }
+  }
+}
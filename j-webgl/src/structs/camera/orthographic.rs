@@ -0,0 +1,226 @@
+use super::*;
+
+/// Camera looking from an eye position towards a target, orthographic by default
+///
+/// The projection box is expressed in view space (x to the right, y up, looking
+/// down -z). The camera can switch to a perspective projection at runtime with
+/// [`set_projection`](Orthographic::set_projection). The `view_front`,
+/// `view_top` and `view_side` methods provide the usual engineering views
+/// around the current target.
+#[wasm_bindgen::prelude::wasm_bindgen]
+#[derive(Clone)]
+pub struct Orthographic {
+  eye: algebra::Point3,
+  target: algebra::Point3,
+  up: algebra::Vector3,
+  projection: Projection,
+  width: f32,
+  height: f32,
+  view: algebra::Matrix4,
+  projection_matrix: algebra::Matrix4,
+  matrix: algebra::Matrix4,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl Orthographic {
+  /// Create a camera at the [`Basic`](super::Basic) eye position looking at the
+  /// origin, with a projection box 4 units high
+  #[wasm_bindgen(constructor)]
+  pub fn new(width: f32, height: f32) -> Result<Self> {
+    let half_width = 2.0 * width / height;
+    let mut camera = Orthographic {
+      eye: algebra::Point3::new(EYE_X, EYE_Y, EYE_Z),
+      target: algebra::Point3::origin(),
+      up: algebra::Vector3::y(),
+      projection: Projection::Orthographic {
+        left: -half_width, right: half_width, bottom: -2.0, top: 2.0,
+        near: 0.1f32, far: 200f32,
+      },
+      width, height,
+      view: algebra::Matrix4::identity(),
+      projection_matrix: algebra::Matrix4::identity(),
+      matrix: algebra::Matrix4::identity(),
+    };
+    camera.update();
+    Ok(camera)
+  }
+
+  /// Set the eye and target positions
+  pub fn set_view(&mut self, eye_x: f32, eye_y: f32, eye_z: f32, target_x: f32, target_y: f32, target_z: f32) {
+    self.eye = algebra::Point3::new(eye_x, eye_y, eye_z);
+    self.target = algebra::Point3::new(target_x, target_y, target_z);
+    self.up = algebra::Vector3::y();
+    self.update();
+  }
+
+  /// Switch to an orthographic projection of the given view-space box
+  pub fn set_orthographic(&mut self, left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) {
+    self.set_projection(Projection::Orthographic { left, right, bottom, top, near, far });
+  }
+
+  /// Switch to a perspective projection
+  pub fn set_perspective(&mut self, field_of_view_in_radians: f32, near: f32, far: f32) {
+    self.set_projection(Projection::Perspective { field_of_view: field_of_view_in_radians, near, far });
+  }
+
+  pub fn set_near_far(&mut self, near: f32, far: f32) {
+    self.set_projection(self.projection.with_near_far(near, far));
+  }
+
+  /// Set the viewport size in CSS pixels. The orthographic box keeps its
+  /// height and center, its width following the new aspect ratio.
+  pub fn resize(&mut self, width: f32, height: f32) {
+    self.width = width;
+    self.height = height;
+    self.projection = self.projection.with_aspect(width / height);
+    self.update();
+  }
+
+  /// Look at the target along -z
+  pub fn view_front(&mut self) {
+    self.view_along(algebra::Vector3::z(), algebra::Vector3::y());
+  }
+
+  /// Look down at the target along -y, with -z pointing up on screen
+  pub fn view_top(&mut self) {
+    self.view_along(algebra::Vector3::y(), algebra::Vector3::new(0.0, 0.0, -1.0));
+  }
+
+  /// Look at the target along -x
+  pub fn view_side(&mut self) {
+    self.view_along(algebra::Vector3::x(), algebra::Vector3::y());
+  }
+
+  /// Frame the axis-aligned box from `min` to `max` while keeping the view
+  /// direction, see [`fit_bounding_box`](Orthographic::fit_bounding_box)
+  pub fn zoom_to_fit(&mut self, min_x: f32, min_y: f32, min_z: f32, max_x: f32, max_y: f32, max_z: f32) {
+    self.fit_bounding_box(&algebra::Point3::new(min_x, min_y, min_z), &algebra::Point3::new(max_x, max_y, max_z));
+  }
+}
+
+impl Orthographic {
+  pub fn eye(&self) -> algebra::Point3 { self.eye }
+  pub fn target(&self) -> algebra::Point3 { self.target }
+  pub fn projection(&self) -> Projection { self.projection }
+
+  pub fn set_projection(&mut self, projection: Projection) {
+    self.projection = projection;
+    self.update();
+  }
+
+  /// Frame the axis-aligned box from `min` to `max` while keeping the view direction
+  ///
+  /// The target moves to the box center. An orthographic box is fitted tightly
+  /// to the projected box corners at the viewport aspect ratio, while a
+  /// perspective camera moves back until the box bounding sphere fits the
+  /// field of view. Near and far planes enclose the box.
+  pub fn fit_bounding_box(&mut self, min: &algebra::Point3, max: &algebra::Point3) {
+    let half_diagonal = &(max - min) * 0.5;
+    let center = min + &half_diagonal;
+    let radius = half_diagonal.norm().max(f32::EPSILON);
+    let direction = (&self.eye - &self.target).normalize();
+    let aspect = self.width / self.height;
+
+    let distance = match self.projection {
+      Projection::Perspective { field_of_view, .. } => {
+        let horizontal = 2.0 * ((0.5 * field_of_view).tan() * aspect).atan();
+        radius / (0.5 * field_of_view.min(horizontal)).sin()
+      },
+      Projection::Orthographic { .. } => 2.0 * radius,
+    };
+    self.target = center;
+    self.eye = &center + &(&direction * distance);
+    let (near, far) = ((distance - radius).max(0.01 * radius), distance + radius);
+
+    self.projection = match self.projection {
+      Projection::Perspective { field_of_view, .. } => Projection::Perspective { field_of_view, near, far },
+      Projection::Orthographic { .. } => {
+        let view = algebra::Matrix4::look_at_rh(&self.eye, &self.target, &self.up);
+        let (mut x_max, mut y_max) = (0.0f32, 0.0f32);
+        for i in 0..8 {
+          let corner = algebra::Point3::new(
+            if i & 1 == 0 { min.x() } else { max.x() },
+            if i & 2 == 0 { min.y() } else { max.y() },
+            if i & 4 == 0 { min.z() } else { max.z() },
+          );
+          let p = view.transform_point(&corner);
+          x_max = x_max.max(p.x().abs());
+          y_max = y_max.max(p.y().abs());
+        }
+        let half_height = y_max.max(x_max / aspect);
+        let half_width = half_height * aspect;
+        Projection::Orthographic { left: -half_width, right: half_width, bottom: -half_height, top: half_height, near, far }
+      },
+    };
+    self.update();
+  }
+
+  fn view_along(&mut self, direction: algebra::Vector3, up: algebra::Vector3) {
+    let distance = (&self.eye - &self.target).norm();
+    self.eye = &self.target + &(&direction * distance);
+    self.up = up;
+    self.update();
+  }
+
+  fn update(&mut self) {
+    let projection = self.projection.matrix(self.width / self.height);
+    let view = algebra::Matrix4::look_at_rh(&self.eye, &self.target, &self.up);
+    self.matrix = &projection * &view;
+    self.view = view;
+    self.projection_matrix = projection;
+  }
+}
+
+impl traits::Camera for Orthographic {
+  fn as_f32_array(&self) -> Result<&[f32]> {
+    Ok(self.matrix.as_slice())
+  }
+
+  fn view(&self) -> Result<algebra::Matrix4> { Ok(self.view) }
+  fn projection(&self) -> Result<algebra::Matrix4> { Ok(self.projection_matrix) }
+  fn view_projection(&self) -> Result<algebra::Matrix4> { Ok(self.matrix) }
+  fn eye_position(&self) -> Result<algebra::Point3> { Ok(self.eye) }
+  fn viewport_size(&self) -> Option<(f32, f32)> { Some((self.width, self.height)) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_framed(camera: &Orthographic, min: &algebra::Point3, max: &algebra::Point3) {
+    let matrix = traits::Camera::view_projection(camera).unwrap();
+    for i in 0..8 {
+      let corner = algebra::Point3::new(
+        if i & 1 == 0 { min.x() } else { max.x() },
+        if i & 2 == 0 { min.y() } else { max.y() },
+        if i & 4 == 0 { min.z() } else { max.z() },
+      );
+      let p = matrix.transform_point(&corner);
+      for c in [p.x(), p.y(), p.z()] {
+        assert!(c.abs() <= 1.0 + 1e-4, "{:?} projects outside the view volume to {:?}", corner, p);
+      }
+    }
+  }
+
+  #[test]
+  fn zoom_to_fit_frames_box_at_viewport_aspect() {
+    let (min, max) = (algebra::Point3::new(-1.0, 2.0, -3.0), algebra::Point3::new(4.0, 3.0, 1.0));
+    for view in [Orthographic::view_front, Orthographic::view_top, Orthographic::view_side] {
+      let mut camera = Orthographic::new(800.0, 400.0).unwrap();
+      view(&mut camera);
+      camera.fit_bounding_box(&min, &max);
+      assert_framed(&camera, &min, &max);
+      let Projection::Orthographic { left, right, bottom, top, .. } = camera.projection() else { panic!(); };
+      assert!(((right - left) / (top - bottom) - 2.0).abs() < 1e-5);
+    }
+  }
+
+  #[test]
+  fn zoom_to_fit_with_perspective() {
+    let (min, max) = (algebra::Point3::new(-1.0, -1.0, -1.0), algebra::Point3::new(1.0, 1.0, 1.0));
+    let mut camera = Orthographic::new(400.0, 800.0).unwrap();
+    camera.set_projection(Projection::default());
+    camera.fit_bounding_box(&min, &max);
+    assert_framed(&camera, &min, &max);
+  }
+}
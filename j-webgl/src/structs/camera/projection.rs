@@ -0,0 +1,106 @@
+use super::*;
+
+/// Projection used by a camera, which can be switched at runtime
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+  /// Perspective projection with a vertical field of view, the horizontal
+  /// extent following the viewport aspect ratio
+  Perspective { field_of_view: f32, near: f32, far: f32 },
+  /// Orthographic projection of a view-space box, see
+  /// [`Matrix4::new_orthographic`](algebra::Matrix4::new_orthographic). Cameras
+  /// fit its width to the viewport aspect ratio on resize, see
+  /// [`with_aspect`](Projection::with_aspect).
+  Orthographic { left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32 },
+}
+
+impl Default for Projection {
+  /// Perspective projection used by the [`Basic`](super::Basic) camera
+  fn default() -> Self {
+    Projection::Perspective {
+      field_of_view: 45.0 * std::f32::consts::PI / 180f32,
+      near: 0.1f32,
+      far: 200f32,
+    }
+  }
+}
+
+impl Projection {
+  /// Returns the projection matrix for a viewport of the given aspect ratio (width / height)
+  pub fn matrix(&self, aspect: f32) -> algebra::Matrix4 {
+    match *self {
+      Projection::Perspective { field_of_view, near, far } =>
+        algebra::Matrix4::new_perspective(aspect, field_of_view, near, far),
+      Projection::Orthographic { left, right, bottom, top, near, far } =>
+        algebra::Matrix4::new_orthographic(left, right, bottom, top, near, far),
+    }
+  }
+
+  /// Returns the projection with its orthographic box keeping its height and
+  /// center, its width following the aspect ratio (width / height) the way a
+  /// perspective projection does. Perspective projections are returned unchanged.
+  pub fn with_aspect(&self, aspect: f32) -> Self {
+    match *self {
+      Projection::Orthographic { left, right, bottom, top, near, far } => {
+        let (center, half_width) = (0.5 * (left + right), 0.5 * (top - bottom) * aspect);
+        Projection::Orthographic { left: center - half_width, right: center + half_width, bottom, top, near, far }
+      },
+      perspective => perspective,
+    }
+  }
+
+  /// Returns the same projection with new near and far planes
+  pub fn with_near_far(&self, near: f32, far: f32) -> Self {
+    match *self {
+      Projection::Perspective { field_of_view, .. } => Projection::Perspective { field_of_view, near, far },
+      Projection::Orthographic { left, right, bottom, top, .. } =>
+        Projection::Orthographic { left, right, bottom, top, near, far },
+    }
+  }
+
+  /// Returns the projection with its orthographic box scaled by `factor`
+  /// around its center. Perspective projections are returned unchanged.
+  pub fn scaled(&self, factor: f32) -> Self {
+    match *self {
+      Projection::Orthographic { left, right, bottom, top, near, far } => {
+        let (cx, cy) = (0.5 * (left + right), 0.5 * (bottom + top));
+        Projection::Orthographic {
+          left: cx + (left - cx) * factor, right: cx + (right - cx) * factor,
+          bottom: cy + (bottom - cy) * factor, top: cy + (top - cy) * factor,
+          near, far,
+        }
+      },
+      perspective => perspective,
+    }
+  }
+
+  /// Returns an orthographic projection framing, at `distance` from the eye,
+  /// the same region as this projection for a viewport of the given aspect
+  /// ratio. Orthographic projections are returned unchanged.
+  pub fn to_orthographic(&self, distance: f32, aspect: f32) -> Self {
+    match *self {
+      Projection::Perspective { field_of_view, near, far } => {
+        let half_height = distance * (0.5 * field_of_view).tan();
+        let half_width = half_height * aspect;
+        Projection::Orthographic {
+          left: -half_width, right: half_width, bottom: -half_height, top: half_height,
+          near, far,
+        }
+      },
+      orthographic => orthographic,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn orthographic_width_follows_aspect() {
+    let projection = Projection::Orthographic { left: 0.0, right: 3.0, bottom: -1.0, top: 1.0, near: 0.1, far: 10.0 };
+    // The box is used as given, whatever the aspect ratio
+    assert_eq!(projection.matrix(2.0), algebra::Matrix4::new_orthographic(0.0, 3.0, -1.0, 1.0, 0.1, 10.0));
+    assert_eq!(projection.with_aspect(2.0), Projection::Orthographic { left: -0.5, right: 3.5, bottom: -1.0, top: 1.0, near: 0.1, far: 10.0 });
+    assert_eq!(Projection::default().with_aspect(2.0), Projection::default());
+  }
+}
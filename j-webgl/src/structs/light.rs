@@ -0,0 +1,140 @@
+use super::*;
+
+/// Kind of a [`Light`], which selects the fields it uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightKind {
+  /// Parallel rays travelling along the light direction, such as sunlight
+  Directional,
+  /// Rays emitted in all directions from the light position, attenuated with distance
+  Point,
+  /// Point light restricted to a cone around the light direction
+  Spot,
+}
+
+/// Light source registered with a [`Renderer`](crate::Renderer)
+///
+/// Lights are in world space. The radiance reaching a surface is
+/// `color * intensity`, divided for point and spot lights by the attenuation
+/// `constant + linear * d + quadratic * d * d` at distance `d`. Spot lights
+/// fade out between their inner and outer cone half-angles.
+#[wasm_bindgen::prelude::wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Light {
+  kind: LightKind,
+  position: algebra::Point3,
+  direction: algebra::Vector3,
+  color: [f32; 3],
+  intensity: f32,
+  attenuation: [f32; 3],
+  cone: (f32, f32),
+  cast_shadow: bool,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl Light {
+  /// Create a white directional light travelling along `(dx, dy, dz)`
+  pub fn directional(dx: f32, dy: f32, dz: f32) -> Light {
+    Light { kind: LightKind::Directional, direction: algebra::Vector3::new(dx, dy, dz).normalize(), ..Light::default() }
+  }
+
+  /// Create a white point light at `(x, y, z)`
+  pub fn point(x: f32, y: f32, z: f32) -> Light {
+    Light { kind: LightKind::Point, position: algebra::Point3::new(x, y, z), ..Light::default() }
+  }
+
+  /// Create a white spot light at `(x, y, z)` pointing along `(dx, dy, dz)`,
+  /// with cone half-angles in radians
+  #[allow(clippy::too_many_arguments)]
+  pub fn spot(x: f32, y: f32, z: f32, dx: f32, dy: f32, dz: f32, inner_angle: f32, outer_angle: f32) -> Light {
+    Light {
+      kind: LightKind::Spot,
+      position: algebra::Point3::new(x, y, z),
+      direction: algebra::Vector3::new(dx, dy, dz).normalize(),
+      cone: (inner_angle.min(outer_angle), outer_angle),
+      ..Light::default()
+    }
+  }
+
+  pub fn set_color(&mut self, red: f32, green: f32, blue: f32) {
+    self.color = [red, green, blue];
+  }
+
+  pub fn set_intensity(&mut self, intensity: f32) {
+    self.intensity = intensity;
+  }
+
+  pub fn set_position(&mut self, x: f32, y: f32, z: f32) {
+    self.position = algebra::Point3::new(x, y, z);
+  }
+
+  pub fn set_direction(&mut self, dx: f32, dy: f32, dz: f32) {
+    self.direction = algebra::Vector3::new(dx, dy, dz).normalize();
+  }
+
+  /// Set the distance attenuation coefficients, `(1, 0.09, 0.032)` by default
+  pub fn set_attenuation(&mut self, constant: f32, linear: f32, quadratic: f32) {
+    self.attenuation = [constant, linear, quadratic];
+  }
+
+  /// Set the spot cone half-angles in radians, full intensity within `inner_angle`
+  pub fn set_cone(&mut self, inner_angle: f32, outer_angle: f32) {
+    self.cone = (inner_angle.min(outer_angle), outer_angle);
+  }
+
+  /// Make the light cast shadows when the renderer has shadows enabled,
+  /// which only directional lights support
+  pub fn set_cast_shadow(&mut self, cast_shadow: bool) {
+    self.cast_shadow = cast_shadow;
+  }
+}
+
+impl Default for Light {
+  fn default() -> Self {
+    Light {
+      kind: LightKind::Directional,
+      position: algebra::Point3::origin(),
+      direction: algebra::Vector3::new(0.0, -1.0, 0.0),
+      color: [1.0, 1.0, 1.0],
+      intensity: 1.0,
+      attenuation: [1.0, 0.09, 0.032],
+      cone: (std::f32::consts::FRAC_PI_8, std::f32::consts::FRAC_PI_6),
+      cast_shadow: false,
+    }
+  }
+}
+
+impl Light {
+  pub fn kind(&self) -> LightKind { self.kind }
+  pub fn position(&self) -> &algebra::Point3 { &self.position }
+  /// Unit direction the light travels along
+  pub fn direction(&self) -> &algebra::Vector3 { &self.direction }
+  pub fn color(&self) -> [f32; 3] { self.color }
+  pub fn intensity(&self) -> f32 { self.intensity }
+  pub fn attenuation(&self) -> [f32; 3] { self.attenuation }
+  /// Inner and outer spot cone half-angles, in radians
+  pub fn cone(&self) -> (f32, f32) { self.cone }
+  pub fn casts_shadow(&self) -> bool { self.cast_shadow }
+
+  /// `color * intensity`
+  pub fn radiance(&self) -> [f32; 3] {
+    self.color.map(|c| c * self.intensity)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn constructors_normalize_direction() {
+    let light = Light::directional(0.0, -2.0, 0.0);
+    assert_eq!(light.kind(), LightKind::Directional);
+    assert_eq!(light.direction(), &algebra::Vector3::new(0.0, -1.0, 0.0));
+
+    let mut spot = Light::spot(0.0, 4.0, 0.0, 0.0, -1.0, 0.0, 0.6, 0.4);
+    assert_eq!(spot.cone(), (0.4, 0.4));
+    spot.set_color(1.0, 0.5, 0.0);
+    spot.set_intensity(2.0);
+    assert_eq!(spot.radiance(), [2.0, 1.0, 0.0]);
+  }
+}
@@ -0,0 +1,95 @@
+use super::*;
+
+/// Metallic-roughness material, following the glTF 2.0 material model
+///
+/// Factors multiply the values read from the optional maps. Maps are images
+/// in CPU memory, uploaded as textures by
+/// [`TrianglesPbr::with_material`](programs::TrianglesPbr::with_material):
+///
+/// - `base_color_map`: sRGB base color and linear alpha
+/// - `metallic_roughness_map`: roughness in the green channel and metalness in the blue channel
+/// - `normal_map`: tangent space normal, `+y` towards decreasing `v`
+/// - `occlusion_map`: ambient occlusion in the red channel
+/// - `emissive_map`: sRGB emitted color
+#[derive(Debug, Clone, PartialEq)]
+pub struct PbrMaterial {
+  /// Linear RGBA base color factor
+  pub base_color: [f32; 4],
+  pub metallic: f32,
+  pub roughness: f32,
+  /// Linear emitted color factor
+  pub emissive: [f32; 3],
+  /// Scale of the normal map `x` and `y` components
+  pub normal_scale: f32,
+  /// Weight of the occlusion map, from 0 (no occlusion) to 1
+  pub occlusion_strength: f32,
+  pub base_color_map: Option<texture::Image>,
+  pub metallic_roughness_map: Option<texture::Image>,
+  pub normal_map: Option<texture::Image>,
+  pub occlusion_map: Option<texture::Image>,
+  pub emissive_map: Option<texture::Image>,
+}
+
+impl Default for PbrMaterial {
+  fn default() -> Self {
+    PbrMaterial {
+      base_color: [1.0; 4],
+      metallic: 1.0,
+      roughness: 1.0,
+      emissive: [0.0; 3],
+      normal_scale: 1.0,
+      occlusion_strength: 1.0,
+      base_color_map: None,
+      metallic_roughness_map: None,
+      normal_map: None,
+      occlusion_map: None,
+      emissive_map: None,
+    }
+  }
+}
+
+impl PbrMaterial {
+  /// Material without maps
+  pub fn new(base_color: [f32; 4], metallic: f32, roughness: f32) -> Self {
+    PbrMaterial { base_color, metallic, roughness, ..Default::default() }
+  }
+
+  pub fn with_emissive(mut self, emissive: [f32; 3]) -> Self {
+    self.emissive = emissive;
+    self
+  }
+
+  pub fn with_base_color_map(mut self, image: texture::Image) -> Self {
+    self.base_color_map = Some(image);
+    self
+  }
+
+  pub fn with_metallic_roughness_map(mut self, image: texture::Image) -> Self {
+    self.metallic_roughness_map = Some(image);
+    self
+  }
+
+  pub fn with_normal_map(mut self, image: texture::Image, scale: f32) -> Self {
+    self.normal_map = Some(image);
+    self.normal_scale = scale;
+    self
+  }
+
+  pub fn with_occlusion_map(mut self, image: texture::Image, strength: f32) -> Self {
+    self.occlusion_map = Some(image);
+    self.occlusion_strength = strength;
+    self
+  }
+
+  pub fn with_emissive_map(mut self, image: texture::Image) -> Self {
+    self.emissive_map = Some(image);
+    self
+  }
+}
+
+/// Factors of a glTF material, its textures not being decoded by [`loaders::parse_gltf`]
+impl From<&loaders::GltfMaterial> for PbrMaterial {
+  fn from(material: &loaders::GltfMaterial) -> Self {
+    PbrMaterial::new(material.base_color, material.metallic, material.roughness)
+  }
+}
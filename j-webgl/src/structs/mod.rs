@@ -2,3 +2,5 @@ use super::*;
 
 pub mod camera;
 pub mod renderable;
+pub mod light;
+pub mod material;
@@ -1,9 +1,9 @@
 use super::*;
 
 struct Inner {
-  program: web_sys::WebGlProgram,
-  vao: web_sys::WebGlVertexArrayObject,
-  matrix_location: web_sys::WebGlUniformLocation,
+  program: gl::Program,
+  mesh_buffers: mesh::MeshBuffers,
+  matrix_location: gl::UniformLocation,
 }
 
 #[wasm_bindgen::prelude::wasm_bindgen]
@@ -22,82 +22,22 @@ impl Cube {
 impl traits::Renderable for Cube {
   fn render(
     &self,
-    context: &web_sys::WebGl2RenderingContext,
-    camera_mvp: &[f32],
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
   ) -> Result<()>
   {
     if self.inner.borrow().is_none() {
       let program = utils::compile_program(
-        context,
+        context.as_ref(),
         shaders::vertex::position_matrix()?, // VERTEX_SHADER_SOURCE,
         shaders::fragment::color()?, // FRAGMENT_SHADER_SOURCE,
       )?;
 
-      let position_attribute_location = context
-          .get_attrib_location(&program, "a_position");
       let matrix_location = context
           .get_uniform_location(&program, "u_matrix");
       let color_location = context.get_uniform_location(&program, "u_color");
 
-      let position_buffer = context.create_buffer().ok_or("Unable to create buffer")?;
-
-      let vao = context.create_vertex_array().ok_or("Unable to create vertex array")?;
-      context.bind_vertex_array(Some(&vao));
-
-      context.enable_vertex_attrib_array(position_attribute_location as u32);
-      context.bind_buffer(web_sys::WebGl2RenderingContext::ARRAY_BUFFER, Some(&position_buffer));
-      let vertices: [f32; 24] = [
-            // Front face
-            -0.5, -0.5,  0.5,  // 0: bottom-left-front
-             0.5, -0.5,  0.5,  // 1: bottom-right-front
-             0.5,  0.5,  0.5,  // 2: top-right-front
-            -0.5,  0.5,  0.5,  // 3: top-left-front
-            // Back face
-            -0.5, -0.5, -0.5,  // 4: bottom-left-back
-             0.5, -0.5, -0.5,  // 5: bottom-right-back
-             0.5,  0.5, -0.5,  // 6: top-right-back
-            -0.5,  0.5, -0.5,  // 7: top-left-back
-      ];
-
-      // Cube indices: 12 triangles (2 per face) = 36 indices
-      let indices: [usize; 36] = [
-            // Front face
-            0, 1, 2,  2, 3, 0,
-            // Back face
-            4, 6, 5,  6, 4, 7,
-            // Top face
-            3, 2, 6,  6, 7, 3,
-            // Bottom face
-            0, 4, 5,  5, 1, 0,
-            // Right face
-            1, 5, 6,  6, 2, 1,
-            // Left face
-            0, 3, 7,  7, 4, 0,
-      ];
-      let array: Vec<f32> = indices.iter()
-      .fold(Vec::new(), |mut a, i| {
-          a.push(vertices[*i*3]);
-          a.push(vertices[*i*3+1]);
-          a.push(vertices[*i*3+2]);
-          a
-      });
-
-      unsafe {
-          let vertices_array = js_sys::Float32Array::view(array.as_slice());
-          context.buffer_data_with_array_buffer_view(
-                web_sys::WebGl2RenderingContext::ARRAY_BUFFER,
-                &vertices_array,
-                web_sys::WebGl2RenderingContext::STATIC_DRAW,
-          );
-      }
-
-      context.vertex_attrib_pointer_with_i32(position_attribute_location as u32,
-          3, // size
-          web_sys::WebGl2RenderingContext::FLOAT,
-          false, // don't normalize
-          0, // stride
-          0, // offset
-      );
+      let mesh_buffers = mesh::MeshBuffers::new(context, &program, &geometry::cuboid(1.0, 1.0, 1.0)?)?;
 
       context.use_program(Some(&program));
       context.uniform4f(
@@ -111,7 +51,7 @@ impl traits::Renderable for Cube {
       *self.inner.borrow_mut() = Some(
         Inner {
           program,
-          vao,
+          mesh_buffers,
           matrix_location: matrix_location.unwrap(),
         }
       );
@@ -121,18 +61,17 @@ impl traits::Renderable for Cube {
     let inner = binding.as_ref().unwrap();
 
     context.use_program(Some(&inner.program));
-    context.bind_vertex_array(Some(&inner.vao));
     context.uniform_matrix4fv_with_f32_array(
       Some(&inner.matrix_location),
       false,
-      camera_mvp,
-    );
-    context.draw_arrays(
-          web_sys::WebGl2RenderingContext::TRIANGLES,
-          0, // offset
-          12 * 3, // count
+      frame.mvp(),
     );
+    inner.mesh_buffers.draw()?;
 
     Ok(())
   }
+
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    Some(algebra::Aabb::new(algebra::Point3::new(-0.5, -0.5, -0.5), algebra::Point3::new(0.5, 0.5, 0.5)))
+  }
 }
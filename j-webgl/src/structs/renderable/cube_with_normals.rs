@@ -17,8 +17,8 @@ impl CubeWithNormals {
 impl traits::Renderable for CubeWithNormals {
   fn render(
     &self,
-    context: &web_sys::WebGl2RenderingContext,
-    camera_mvp: &[f32],
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
   ) -> Result<()>
   {
     if self.inner.borrow().is_none() {
@@ -30,67 +30,54 @@ impl traits::Renderable for CubeWithNormals {
         1.0, // Alpha
       )?;
       program.with_reverse_light_direction([0.5, 0.7, 1.0])?;
-      program.with_position(12, get_positions().as_slice())?;
-      program.with_normals(get_normals().as_slice())?;
+      program.with_mesh(&geometry::cuboid(1.0, 1.0, 1.0)?)?;
       *self.inner.borrow_mut() = Some(program);
     }
 
     let mut binding = self.inner.borrow_mut();
     let inner = binding.as_mut().unwrap();
-    inner.with_mvp(camera_mvp)?;
+    inner.with_mvp(frame.mvp())?;
+    inner.with_normal_matrix(frame.normal_matrix().as_slice())?;
     inner.draw()?;
 
     Ok(())
   }
+
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    Some(algebra::Aabb::new(algebra::Point3::new(-0.5, -0.5, -0.5), algebra::Point3::new(0.5, 0.5, 0.5)))
+  }
 }
 
-fn get_positions() -> Vec<f32> {
-  let vertices: [f32; 24] = [
-            // Front face
-            -0.5, -0.5,  0.5,  // 0: bottom-left-front
-             0.5, -0.5,  0.5,  // 1: bottom-right-front
-             0.5,  0.5,  0.5,  // 2: top-right-front
-            -0.5,  0.5,  0.5,  // 3: top-left-front
-            // Back face
-            -0.5, -0.5, -0.5,  // 4: bottom-left-back
-             0.5, -0.5, -0.5,  // 5: bottom-right-back
-             0.5,  0.5, -0.5,  // 6: top-right-back
-            -0.5,  0.5, -0.5,  // 7: top-left-back
-  ];
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::rc::Rc;
+  use traits::Renderable;
+  use gl::{RecordingContext, Call, UniformValue};
 
-  // Cube indices: 12 triangles (2 per face) = 36 indices
-  let indices: [usize; 36] = [
-            // Front face
-            0, 1, 2,  2, 3, 0,
-            // Back face
-            4, 6, 5,  6, 4, 7,
-            // Top face
-            3, 2, 6,  6, 7, 3,
-            // Bottom face
-            0, 4, 5,  5, 1, 0,
-            // Right face
-            1, 5, 6,  6, 2, 1,
-            // Left face
-            0, 3, 7,  7, 4, 0,
-  ];
-  let array: Vec<f32> = indices.iter()
-  .fold(Vec::new(), |mut a, i| {
-          a.push(vertices[*i*3]);
-          a.push(vertices[*i*3+1]);
-          a.push(vertices[*i*3+2]);
-          a
-  });
-  array
-}
+  #[test]
+  fn render_sets_matrix_and_draws_indexed_cube() {
+    let recording = Rc::new(RecordingContext::new(640, 480));
+    let context: Rc<dyn gl::GlContext> = recording.clone();
+    let mvp: [f32; 16] = std::array::from_fn(|i| i as f32);
+    let frame = FrameContext::new(
+      algebra::Matrix4::identity(), mvp.into(), algebra::Point3::origin(), (640, 480),
+    );
 
-fn get_normals() -> Vec<f32> {
-  let mut normals = Vec::<f32>::new();
-  for _ in 0..6 { normals.append(&mut vec![ 0.0, 0.0, 1.0, ]); }  // Front face
-  for _ in 0..6 { normals.append(&mut vec![ 0.0, 0.0, -1.0, ]); } // Back face
-  for _ in 0..6 { normals.append(&mut vec![ 0.0, 1.0,  0.0, ]); } // Top face
-  for _ in 0..6 { normals.append(&mut vec![ 0.0, -1.0, 0.0, ]); } // Bottom face
-  for _ in 0..6 { normals.append(&mut vec![ 1.0, 0.0,  0.0, ]); } // Right face
-  for _ in 0..6 { normals.append(&mut vec![-1.0, 0.0,  0.0, ]); } // Left face
-  normals
-}
+    let cube = CubeWithNormals::new().unwrap();
+    cube.render(&context, &frame).unwrap();
 
+    assert!(recording.state().vertex_array.is_some());
+    assert_eq!(recording.uniform("u_matrix"), Some(UniformValue::Mat4(mvp)));
+    assert_eq!(recording.uniform("u_normalMatrix"), Some(UniformValue::Mat4(algebra::Matrix4::identity().as_slice().try_into().unwrap())));
+    assert_eq!(
+      recording.calls().last(),
+      Some(&Call::DrawElements {
+        mode: web_sys::WebGl2RenderingContext::TRIANGLES,
+        count: 36,
+        data_type: web_sys::WebGl2RenderingContext::UNSIGNED_SHORT,
+        offset: 0,
+      })
+    );
+  }
+}
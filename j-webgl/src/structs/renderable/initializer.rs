@@ -11,8 +11,8 @@ impl Initializer {
 
 impl traits::Renderable for Initializer {
   fn render(&self,
-    context: &web_sys::WebGl2RenderingContext,
-    _camera_mvp: &[f32],
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    _frame: &FrameContext,
   ) -> Result<()>
   {
     // Clear the canvas
@@ -3,3 +3,48 @@ use super::*;
 mod initializer; pub use initializer::Initializer;
 mod cube; pub use cube::Cube;
 mod cube_with_normals; pub use cube_with_normals::CubeWithNormals;
+mod shaded_mesh; pub use shaded_mesh::ShadedMesh;
+mod point_cloud; pub use point_cloud::PointCloud;
+mod textured_mesh; pub use textured_mesh::TexturedMesh;
+mod phong_mesh; pub use phong_mesh::PhongMesh;
+mod pbr_mesh; pub use pbr_mesh::PbrMesh;
+
+/// Draw the depth of a mesh with a program created on the first call, as
+/// the meshes of this module do in [`Renderable::render_depth`](traits::Renderable::render_depth)
+fn render_depth(
+  program: &std::cell::RefCell<Option<programs::Depth>>,
+  mesh: &mesh::Mesh,
+  context: &std::rc::Rc<dyn gl::GlContext>,
+  frame: &FrameContext,
+) -> Result<()> {
+  if program.borrow().is_none() {
+    let mut depth = programs::Depth::new(context)?;
+    depth.with_mesh(mesh)?;
+    *program.borrow_mut() = Some(depth);
+  }
+  let mut binding = program.borrow_mut();
+  let depth = binding.as_mut().unwrap();
+  depth.with_mvp(frame.mvp())?;
+  depth.draw()
+}
+
+/// Draw the identifier of a mesh with a program created on the first call, as
+/// the meshes of this module do in [`Renderable::render_id`](traits::Renderable::render_id)
+fn render_id(
+  program: &std::cell::RefCell<Option<programs::ObjectId>>,
+  mesh: &mesh::Mesh,
+  context: &std::rc::Rc<dyn gl::GlContext>,
+  frame: &FrameContext,
+  id: u32,
+) -> Result<()> {
+  if program.borrow().is_none() {
+    let mut object_id = programs::ObjectId::new(context)?;
+    object_id.with_mesh(mesh)?;
+    *program.borrow_mut() = Some(object_id);
+  }
+  let mut binding = program.borrow_mut();
+  let object_id = binding.as_mut().unwrap();
+  object_id.with_mvp(frame.mvp())?;
+  object_id.with_id(id)?;
+  object_id.draw()
+}
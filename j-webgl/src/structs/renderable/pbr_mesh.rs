@@ -0,0 +1,159 @@
+use super::*;
+
+/// Mesh rendered with a physically based metallic-roughness material
+///
+/// The mesh is lit by the ambient color and the lights registered with the
+/// [`Renderer`](crate::Renderer). It must have texture coordinates; normals
+/// are computed from its triangles when missing. Material maps are uploaded
+/// on the first render.
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub struct PbrMesh {
+  mesh: mesh::Mesh,
+  bounds: Option<algebra::Aabb>,
+  material: structs::material::PbrMaterial,
+  inner: std::cell::RefCell<Option<programs::TrianglesPbr>>,
+  depth: std::cell::RefCell<Option<programs::Depth>>,
+  object_id: std::cell::RefCell<Option<programs::ObjectId>>,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl PbrMesh {
+  /// See [`geometry::uv_sphere`]
+  pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Result<PbrMesh> {
+    PbrMesh::new(geometry::uv_sphere(radius, segments, rings)?, Default::default())
+  }
+
+  /// See [`geometry::torus`]
+  pub fn torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Result<PbrMesh> {
+    PbrMesh::new(geometry::torus(major_radius, minor_radius, major_segments, minor_segments)?, Default::default())
+  }
+
+  /// See [`geometry::plane`]
+  pub fn plane(width: f32, depth: f32, x_segments: u32, z_segments: u32) -> Result<PbrMesh> {
+    PbrMesh::new(geometry::plane(width, depth, x_segments, z_segments)?, Default::default())
+  }
+
+  /// See [`geometry::cuboid`]
+  pub fn cuboid(width: f32, height: f32, depth: f32) -> Result<PbrMesh> {
+    PbrMesh::new(geometry::cuboid(width, height, depth)?, Default::default())
+  }
+
+  /// Set the linear base color factor
+  pub fn set_base_color(&mut self, red: f32, green: f32, blue: f32, alpha: f32) {
+    self.material.base_color = [red, green, blue, alpha];
+  }
+
+  pub fn set_metallic(&mut self, metallic: f32) {
+    self.material.metallic = metallic;
+  }
+
+  pub fn set_roughness(&mut self, roughness: f32) {
+    self.material.roughness = roughness;
+  }
+
+  /// Set the linear emitted color factor
+  pub fn set_emissive(&mut self, red: f32, green: f32, blue: f32) {
+    self.material.emissive = [red, green, blue];
+  }
+}
+
+impl PbrMesh {
+  pub fn new(mesh: mesh::Mesh, material: structs::material::PbrMaterial) -> Result<Self> {
+    if mesh.uvs().is_none() {
+      return Err(Error::InvalidMesh("PBR meshes require texture coordinates".to_string()));
+    }
+    let mesh = match mesh.normals() {
+      Some(_) => mesh,
+      None => mesh.with_computed_normals(),
+    };
+    Ok(PbrMesh {
+      bounds: mesh.bounds(),
+      mesh, material, inner: std::cell::RefCell::new(None), depth: std::cell::RefCell::new(None),
+      object_id: std::cell::RefCell::new(None),
+    })
+  }
+
+  pub fn mesh(&self) -> &mesh::Mesh { &self.mesh }
+
+  pub fn material(&self) -> &structs::material::PbrMaterial { &self.material }
+}
+
+impl traits::Renderable for PbrMesh {
+  fn render(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    if self.inner.borrow().is_none() {
+      let mut program = programs::TrianglesPbr::new(context)?;
+      program.with_material(&self.material)?;
+      program.with_mesh(&self.mesh)?;
+      *self.inner.borrow_mut() = Some(program);
+    }
+
+    let mut binding = self.inner.borrow_mut();
+    let inner = binding.as_mut().unwrap();
+    inner.with_factors(&self.material)?;
+    inner.with_lights(frame.ambient(), frame.lights())?;
+    inner.with_eye_position(frame.eye_position())?;
+    inner.with_mvp(frame.mvp())?;
+    inner.with_model(frame.model().as_slice())?;
+    inner.with_normal_matrix(frame.normal_matrix().as_slice())?;
+    inner.draw()?;
+
+    Ok(())
+  }
+
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    self.bounds
+  }
+
+  fn render_depth(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    render_depth(&self.depth, &self.mesh, context, frame)
+  }
+
+  fn render_id(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+    id: u32,
+  ) -> Result<()>
+  {
+    render_id(&self.object_id, &self.mesh, context, frame, id)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::rc::Rc;
+  use web_sys::WebGl2RenderingContext as GL;
+  use gl::{GlContext, RecordingContext, Call, UniformValue};
+
+  #[test]
+  fn binds_material_maps_to_units() {
+    let recording = Rc::new(RecordingContext::new(64, 64));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let flat = texture::Image::new(1, 1, texture::TextureFormat::Rgb8, vec![128, 128, 255]).unwrap();
+    let material = structs::material::PbrMaterial::new([1.0, 0.5, 0.0, 1.0], 0.0, 0.4)
+      .with_normal_map(flat, 0.5);
+    let mut sphere = PbrMesh::new(geometry::uv_sphere(1.0, 8, 4).unwrap(), material).unwrap();
+    sphere.set_metallic(1.0);
+
+    let frame = FrameContext::from_camera(&structs::camera::Basic::new(64.0, 64.0).unwrap(), (64, 64)).unwrap();
+    sphere.render(&context, &frame).unwrap();
+    assert_eq!(recording.uniform("u_hasNormalTexture"), Some(UniformValue::Int(1)));
+    assert_eq!(recording.uniform("u_normalTexture"), Some(UniformValue::Int(2)));
+    assert_eq!(recording.uniform("u_hasBaseColorTexture"), Some(UniformValue::Int(0)));
+    assert_eq!(recording.uniform("u_metallic"), Some(UniformValue::Float(1.0)));
+    assert_eq!(recording.uniform("u_normalScale"), Some(UniformValue::Float(0.5)));
+    assert!(recording.calls().contains(&Call::ActiveTexture(GL::TEXTURE2)));
+    assert!(!recording.calls().contains(&Call::ActiveTexture(GL::TEXTURE0)));
+  }
+}
@@ -0,0 +1,184 @@
+use super::*;
+
+/// Mesh rendered in a single color with Phong or Blinn-Phong lighting
+///
+/// The mesh is lit by the ambient color and the lights registered with the
+/// [`Renderer`](crate::Renderer), see [`FrameContext::lights`]. Meshes
+/// without normals get normals computed from their triangles. The mesh
+/// casts shadows, and receives the shadow of the frame, see
+/// [`FrameContext::shadow`].
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub struct PhongMesh {
+  mesh: mesh::Mesh,
+  bounds: Option<algebra::Aabb>,
+  color: [f32; 4],
+  specular: [f32; 3],
+  shininess: f32,
+  blinn: bool,
+  inner: std::cell::RefCell<Option<programs::TrianglesPhong>>,
+  shadowed: std::cell::RefCell<Option<programs::TrianglesPhong>>,
+  depth: std::cell::RefCell<Option<programs::Depth>>,
+  object_id: std::cell::RefCell<Option<programs::ObjectId>>,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl PhongMesh {
+  /// See [`geometry::uv_sphere`]
+  pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Result<PhongMesh> {
+    Ok(PhongMesh::new(geometry::uv_sphere(radius, segments, rings)?))
+  }
+
+  /// See [`geometry::icosphere`]
+  pub fn icosphere(radius: f32, subdivisions: u32) -> Result<PhongMesh> {
+    Ok(PhongMesh::new(geometry::icosphere(radius, subdivisions)?))
+  }
+
+  /// See [`geometry::torus`]
+  pub fn torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Result<PhongMesh> {
+    Ok(PhongMesh::new(geometry::torus(major_radius, minor_radius, major_segments, minor_segments)?))
+  }
+
+  /// See [`geometry::plane`]
+  pub fn plane(width: f32, depth: f32, x_segments: u32, z_segments: u32) -> Result<PhongMesh> {
+    Ok(PhongMesh::new(geometry::plane(width, depth, x_segments, z_segments)?))
+  }
+
+  /// See [`geometry::cuboid`]
+  pub fn cuboid(width: f32, height: f32, depth: f32) -> Result<PhongMesh> {
+    Ok(PhongMesh::new(geometry::cuboid(width, height, depth)?))
+  }
+
+  pub fn set_color(&mut self, red: f32, green: f32, blue: f32, alpha: f32) {
+    self.color = [red, green, blue, alpha];
+  }
+
+  /// Set the specular highlight color and exponent, white and 32 by default
+  pub fn set_specular(&mut self, red: f32, green: f32, blue: f32, shininess: f32) {
+    self.specular = [red, green, blue];
+    self.shininess = shininess;
+  }
+
+  /// Use the Blinn-Phong half vector, the default, rather than the Phong reflection vector
+  pub fn set_blinn(&mut self, blinn: bool) {
+    self.blinn = blinn;
+  }
+}
+
+impl PhongMesh {
+  pub fn new(mesh: mesh::Mesh) -> Self {
+    let mesh = match mesh.normals() {
+      Some(_) => mesh,
+      None => mesh.with_computed_normals(),
+    };
+    PhongMesh {
+      bounds: mesh.bounds(),
+      mesh,
+      color: [0.2, 1.0, 0.2, 1.0],
+      specular: [1.0, 1.0, 1.0],
+      shininess: 32.0,
+      blinn: true,
+      inner: std::cell::RefCell::new(None),
+      shadowed: std::cell::RefCell::new(None),
+      depth: std::cell::RefCell::new(None),
+      object_id: std::cell::RefCell::new(None),
+    }
+  }
+
+  pub fn mesh(&self) -> &mesh::Mesh { &self.mesh }
+}
+
+impl From<mesh::Mesh> for PhongMesh {
+  fn from(mesh: mesh::Mesh) -> Self {
+    PhongMesh::new(mesh)
+  }
+}
+
+impl traits::Renderable for PhongMesh {
+  fn render(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    let cell = if frame.shadow().is_some() { &self.shadowed } else { &self.inner };
+    if cell.borrow().is_none() {
+      let mut program = match frame.shadow() {
+        Some(_) => programs::TrianglesPhong::new_shadowed(context)?,
+        None => programs::TrianglesPhong::new(context)?,
+      };
+      program.with_mesh(&self.mesh)?;
+      *cell.borrow_mut() = Some(program);
+    }
+
+    let mut binding = cell.borrow_mut();
+    let inner = binding.as_mut().unwrap();
+    let [red, green, blue, alpha] = self.color;
+    inner.with_color(red, green, blue, alpha)?;
+    inner.with_specular(self.specular, self.shininess)?;
+    inner.with_blinn(self.blinn)?;
+    inner.with_lights(frame.ambient(), frame.lights())?;
+    inner.with_eye_position(frame.eye_position())?;
+    inner.with_mvp(frame.mvp())?;
+    inner.with_model(frame.model().as_slice())?;
+    inner.with_normal_matrix(frame.normal_matrix().as_slice())?;
+    if let Some(shadow) = frame.shadow() {
+      inner.with_shadow(shadow)?;
+    }
+    inner.draw()?;
+
+    Ok(())
+  }
+
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    self.bounds
+  }
+
+  fn render_depth(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    render_depth(&self.depth, &self.mesh, context, frame)
+  }
+
+  fn render_id(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+    id: u32,
+  ) -> Result<()>
+  {
+    render_id(&self.object_id, &self.mesh, context, frame, id)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::rc::Rc;
+  use gl::{GlContext, RecordingContext, UniformValue};
+  use structs::light::Light;
+
+  #[test]
+  fn passes_frame_lights_as_uniforms() {
+    let recording = Rc::new(RecordingContext::new(64, 64));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let mut spot = Light::spot(0.0, 3.0, 0.0, 0.0, -1.0, 0.0, 0.0, std::f32::consts::FRAC_PI_3);
+    spot.set_intensity(2.0);
+    let frame = FrameContext::from_camera(&structs::camera::Basic::new(64.0, 64.0).unwrap(), (64, 64)).unwrap()
+      .with_lights([0.1, 0.2, 0.3], vec![Light::directional(0.0, -1.0, 0.0), spot]);
+
+    let sphere = PhongMesh::icosphere(1.0, 1).unwrap();
+    sphere.render(&context, &frame).unwrap();
+    assert_eq!(recording.uniform("u_lightCount"), Some(UniformValue::Int(2)));
+    assert_eq!(recording.uniform("u_ambient"), Some(UniformValue::Vec3([0.1, 0.2, 0.3])));
+    assert_eq!(recording.uniform("u_lights[1].type"), Some(UniformValue::Int(2)));
+    assert_eq!(recording.uniform("u_lights[1].color"), Some(UniformValue::Vec3([2.0, 2.0, 2.0])));
+    assert_eq!(recording.uniform("u_lights[1].cosOuter"), Some(UniformValue::Float(std::f32::consts::FRAC_PI_3.cos())));
+    assert_eq!(recording.uniform("u_blinn"), Some(UniformValue::Int(1)));
+
+    let too_many = frame.with_lights([0.0; 3], vec![Light::point(0.0, 0.0, 0.0); 9]);
+    assert!(sphere.render(&context, &too_many).is_err());
+  }
+}
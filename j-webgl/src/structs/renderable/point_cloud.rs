@@ -0,0 +1,98 @@
+use super::*;
+
+/// Vertices of a mesh drawn as points in their vertex color
+///
+/// Faces are ignored, each vertex being drawn once. Meshes without colors
+/// are drawn in white.
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub struct PointCloud {
+  mesh: mesh::Mesh,
+  bounds: Option<algebra::Aabb>,
+  point_size: f32,
+  round: bool,
+  inner: std::cell::RefCell<Option<programs::Points>>,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl PointCloud {
+  /// Read a point cloud from the vertices of a PLY file, see [`loaders::parse_ply`]
+  pub fn from_ply(data: &[u8]) -> Result<PointCloud> {
+    PointCloud::new(loaders::parse_ply(data)?.to_mesh()?)
+  }
+
+  /// Set the point diameter, in pixels
+  pub fn set_point_size(&mut self, size: f32) {
+    self.point_size = size;
+  }
+
+  /// Draw round points rather than squares
+  pub fn set_round(&mut self, round: bool) {
+    self.round = round;
+  }
+}
+
+impl PointCloud {
+  pub fn new(mesh: mesh::Mesh) -> Result<Self> {
+    let colors = match mesh.colors() {
+      Some(colors) => colors.to_vec(),
+      None => vec![1.0; mesh.vertex_count() * 4],
+    };
+    let mesh = mesh::Mesh::new(mesh.positions().to_vec())?.with_colors(colors)?;
+    Ok(PointCloud { bounds: mesh.bounds(), mesh, point_size: 1.0, round: false, inner: std::cell::RefCell::new(None) })
+  }
+
+  pub fn mesh(&self) -> &mesh::Mesh { &self.mesh }
+}
+
+impl traits::Renderable for PointCloud {
+  fn render(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    if self.inner.borrow().is_none() {
+      let mut program = programs::Points::new(context)?;
+      program.with_mesh(&self.mesh)?;
+      *self.inner.borrow_mut() = Some(program);
+    }
+
+    let mut binding = self.inner.borrow_mut();
+    let inner = binding.as_mut().unwrap();
+    inner.with_point_size(self.point_size)?;
+    inner.with_round(self.round)?;
+    inner.with_mvp(frame.mvp())?;
+    inner.draw()?;
+
+    Ok(())
+  }
+
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    self.bounds
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::rc::Rc;
+  use web_sys::WebGl2RenderingContext as GL;
+  use gl::{GlContext, RecordingContext, Call, UniformValue};
+
+  #[test]
+  fn draws_each_vertex_as_a_point() {
+    let recording = Rc::new(RecordingContext::new(64, 64));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let mesh = mesh::Mesh::new(vec![0.0; 12]).unwrap()
+      .with_indices(mesh::Indices::compact(vec![0, 1, 2, 2, 3, 0])).unwrap();
+    let mut cloud = PointCloud::new(mesh).unwrap();
+    cloud.set_point_size(3.0);
+    assert_eq!(cloud.mesh().colors(), Some(&[1.0; 16][..]));
+
+    let frame = FrameContext::from_camera(&structs::camera::Basic::new(64.0, 64.0).unwrap(), (64, 64)).unwrap();
+    cloud.render(&context, &frame).unwrap();
+    assert_eq!(recording.uniform("u_pointSize"), Some(UniformValue::Float(3.0)));
+    assert_eq!(recording.uniform("u_round"), Some(UniformValue::Int(0)));
+    assert_eq!(recording.calls().last(), Some(&Call::DrawArrays { mode: GL::POINTS, first: 0, count: 4 }));
+  }
+}
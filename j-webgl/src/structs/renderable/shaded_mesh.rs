@@ -0,0 +1,137 @@
+use super::*;
+
+/// Mesh rendered in a single color lit by a directional light
+///
+/// The associated functions build the [`geometry`] primitives, and any other
+/// [`Mesh`](mesh::Mesh) can be wrapped with [`ShadedMesh::new`]. Meshes
+/// without normals get normals computed from their triangles.
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub struct ShadedMesh {
+  mesh: mesh::Mesh,
+  bounds: Option<algebra::Aabb>,
+  color: [f32; 4],
+  inner: std::cell::RefCell<Option<programs::TrianglesShaded>>,
+  depth: std::cell::RefCell<Option<programs::Depth>>,
+  object_id: std::cell::RefCell<Option<programs::ObjectId>>,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl ShadedMesh {
+  /// See [`geometry::uv_sphere`]
+  pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Result<ShadedMesh> {
+    Ok(ShadedMesh::new(geometry::uv_sphere(radius, segments, rings)?))
+  }
+
+  /// See [`geometry::icosphere`]
+  pub fn icosphere(radius: f32, subdivisions: u32) -> Result<ShadedMesh> {
+    Ok(ShadedMesh::new(geometry::icosphere(radius, subdivisions)?))
+  }
+
+  /// See [`geometry::cylinder`]
+  pub fn cylinder(radius: f32, height: f32, segments: u32) -> Result<ShadedMesh> {
+    Ok(ShadedMesh::new(geometry::cylinder(radius, height, segments)?))
+  }
+
+  /// See [`geometry::cone`]
+  pub fn cone(radius: f32, height: f32, segments: u32) -> Result<ShadedMesh> {
+    Ok(ShadedMesh::new(geometry::cone(radius, height, segments)?))
+  }
+
+  /// See [`geometry::torus`]
+  pub fn torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Result<ShadedMesh> {
+    Ok(ShadedMesh::new(geometry::torus(major_radius, minor_radius, major_segments, minor_segments)?))
+  }
+
+  /// See [`geometry::plane`]
+  pub fn plane(width: f32, depth: f32, x_segments: u32, z_segments: u32) -> Result<ShadedMesh> {
+    Ok(ShadedMesh::new(geometry::plane(width, depth, x_segments, z_segments)?))
+  }
+
+  /// See [`geometry::capsule`]
+  pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Result<ShadedMesh> {
+    Ok(ShadedMesh::new(geometry::capsule(radius, height, segments, rings)?))
+  }
+
+  /// See [`geometry::cuboid`]
+  pub fn cuboid(width: f32, height: f32, depth: f32) -> Result<ShadedMesh> {
+    Ok(ShadedMesh::new(geometry::cuboid(width, height, depth)?))
+  }
+
+  pub fn set_color(&mut self, red: f32, green: f32, blue: f32, alpha: f32) {
+    self.color = [red, green, blue, alpha];
+  }
+}
+
+impl ShadedMesh {
+  pub fn new(mesh: mesh::Mesh) -> Self {
+    let mesh = match mesh.normals() {
+      Some(_) => mesh,
+      None => mesh.with_computed_normals(),
+    };
+    ShadedMesh {
+      bounds: mesh.bounds(),
+      mesh,
+      color: [0.2, 1.0, 0.2, 1.0],
+      inner: std::cell::RefCell::new(None),
+      depth: std::cell::RefCell::new(None),
+      object_id: std::cell::RefCell::new(None),
+    }
+  }
+
+  pub fn mesh(&self) -> &mesh::Mesh { &self.mesh }
+}
+
+impl From<mesh::Mesh> for ShadedMesh {
+  fn from(mesh: mesh::Mesh) -> Self {
+    ShadedMesh::new(mesh)
+  }
+}
+
+impl traits::Renderable for ShadedMesh {
+  fn render(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    if self.inner.borrow().is_none() {
+      let mut program = programs::TrianglesShaded::new(context)?;
+      program.with_reverse_light_direction([0.5, 0.7, 1.0])?;
+      program.with_mesh(&self.mesh)?;
+      *self.inner.borrow_mut() = Some(program);
+    }
+
+    let mut binding = self.inner.borrow_mut();
+    let inner = binding.as_mut().unwrap();
+    let [red, green, blue, alpha] = self.color;
+    inner.with_color(red, green, blue, alpha)?;
+    inner.with_mvp(frame.mvp())?;
+    inner.with_normal_matrix(frame.normal_matrix().as_slice())?;
+    inner.draw()?;
+
+    Ok(())
+  }
+
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    self.bounds
+  }
+
+  fn render_depth(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    render_depth(&self.depth, &self.mesh, context, frame)
+  }
+
+  fn render_id(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+    id: u32,
+  ) -> Result<()>
+  {
+    render_id(&self.object_id, &self.mesh, context, frame, id)
+  }
+}
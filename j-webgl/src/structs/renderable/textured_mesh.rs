@@ -0,0 +1,154 @@
+use super::*;
+
+/// Mesh rendered with a texture, tinted and lit by a directional light
+///
+/// The mesh must have texture coordinates; normals are computed from its
+/// triangles when missing. The texture is uploaded on the first render.
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub struct TexturedMesh {
+  mesh: mesh::Mesh,
+  bounds: Option<algebra::Aabb>,
+  image: texture::Image,
+  sampler: texture::Sampler,
+  color: [f32; 4],
+  inner: std::cell::RefCell<Option<programs::TrianglesTextured>>,
+  depth: std::cell::RefCell<Option<programs::Depth>>,
+  object_id: std::cell::RefCell<Option<programs::ObjectId>>,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+impl TexturedMesh {
+  /// See [`geometry::cuboid`], textured with a PNG file
+  pub fn cuboid(width: f32, height: f32, depth: f32, png: &[u8]) -> Result<TexturedMesh> {
+    TexturedMesh::new(geometry::cuboid(width, height, depth)?, texture::Image::from_png(png)?)
+  }
+
+  /// See [`geometry::plane`], textured with a PNG file
+  pub fn plane(width: f32, depth: f32, x_segments: u32, z_segments: u32, png: &[u8]) -> Result<TexturedMesh> {
+    TexturedMesh::new(geometry::plane(width, depth, x_segments, z_segments)?, texture::Image::from_png(png)?)
+  }
+
+  /// See [`geometry::uv_sphere`], textured with a PNG file
+  pub fn uv_sphere(radius: f32, segments: u32, rings: u32, png: &[u8]) -> Result<TexturedMesh> {
+    TexturedMesh::new(geometry::uv_sphere(radius, segments, rings)?, texture::Image::from_png(png)?)
+  }
+
+  /// Set the tint multiplied with the texture color, white by default
+  pub fn set_color(&mut self, red: f32, green: f32, blue: f32, alpha: f32) {
+    self.color = [red, green, blue, alpha];
+  }
+}
+
+impl TexturedMesh {
+  pub fn new(mesh: mesh::Mesh, image: texture::Image) -> Result<Self> {
+    if mesh.uvs().is_none() {
+      return Err(Error::InvalidMesh("textured meshes require texture coordinates".to_string()));
+    }
+    let mesh = match mesh.normals() {
+      Some(_) => mesh,
+      None => mesh.with_computed_normals(),
+    };
+    Ok(TexturedMesh {
+      bounds: mesh.bounds(),
+      mesh, image,
+      sampler: texture::Sampler::default(),
+      color: [1.0, 1.0, 1.0, 1.0],
+      inner: std::cell::RefCell::new(None),
+      depth: std::cell::RefCell::new(None),
+      object_id: std::cell::RefCell::new(None),
+    })
+  }
+
+  /// Set the sampler, mipmaps being generated if it has a `mipmap_filter`
+  ///
+  /// Takes effect if set before the first render.
+  pub fn with_sampler(mut self, sampler: texture::Sampler) -> Self {
+    self.sampler = sampler;
+    self
+  }
+
+  pub fn mesh(&self) -> &mesh::Mesh { &self.mesh }
+
+  pub fn image(&self) -> &texture::Image { &self.image }
+}
+
+impl traits::Renderable for TexturedMesh {
+  fn render(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    if self.inner.borrow().is_none() {
+      let mut texture = texture::Texture2D::from_image(context, &self.image)?;
+      if self.sampler.mipmap_filter.is_some() {
+        texture.generate_mipmaps()?;
+      }
+      texture.set_sampler(self.sampler)?;
+      let mut program = programs::TrianglesTextured::new(context)?;
+      program.with_reverse_light_direction([0.5, 0.7, 1.0])?;
+      program.with_texture(std::rc::Rc::new(texture))?;
+      program.with_mesh(&self.mesh)?;
+      *self.inner.borrow_mut() = Some(program);
+    }
+
+    let mut binding = self.inner.borrow_mut();
+    let inner = binding.as_mut().unwrap();
+    let [red, green, blue, alpha] = self.color;
+    inner.with_color(red, green, blue, alpha)?;
+    inner.with_mvp(frame.mvp())?;
+    inner.with_normal_matrix(frame.normal_matrix().as_slice())?;
+    inner.draw()?;
+
+    Ok(())
+  }
+
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    self.bounds
+  }
+
+  fn render_depth(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+  ) -> Result<()>
+  {
+    render_depth(&self.depth, &self.mesh, context, frame)
+  }
+
+  fn render_id(
+    &self,
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
+    id: u32,
+  ) -> Result<()>
+  {
+    render_id(&self.object_id, &self.mesh, context, frame, id)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::rc::Rc;
+  use web_sys::WebGl2RenderingContext as GL;
+  use gl::{GlContext, RecordingContext, Call, UniformValue};
+
+  #[test]
+  fn binds_texture_to_unit_zero() {
+    let recording = Rc::new(RecordingContext::new(64, 64));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let image = texture::Image::new(1, 1, texture::TextureFormat::Rgba8, vec![255, 0, 0, 255]).unwrap();
+    let textured = TexturedMesh::new(geometry::cuboid(1.0, 1.0, 1.0).unwrap(), image).unwrap()
+      .with_sampler(texture::Sampler::trilinear());
+    assert!(TexturedMesh::new(mesh::Mesh::new(vec![0.0; 9]).unwrap(), textured.image().clone()).is_err());
+
+    let frame = FrameContext::from_camera(&structs::camera::Basic::new(64.0, 64.0).unwrap(), (64, 64)).unwrap();
+    textured.render(&context, &frame).unwrap();
+    assert_eq!(recording.uniform("u_texture"), Some(UniformValue::Int(0)));
+    assert_eq!(recording.uniform("u_color"), Some(UniformValue::Vec4([1.0, 1.0, 1.0, 1.0])));
+    let calls = recording.calls();
+    assert!(calls.contains(&Call::GenerateMipmap(GL::TEXTURE_2D)));
+    assert!(calls.contains(&Call::ActiveTexture(GL::TEXTURE0)));
+  }
+}
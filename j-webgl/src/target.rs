@@ -0,0 +1,258 @@
+use super::{Result, Error, gl, texture};
+
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as GL;
+use gl::GlContext;
+use texture::{Texture2D, TextureFormat};
+
+/// Attachments of a [`RenderTarget`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderTargetSettings {
+  /// Format of the color texture, `None` for depth-only targets
+  ///
+  /// [`TextureFormat::Rgba8`] targets can always be drawn to, while float
+  /// formats such as [`TextureFormat::Rgba16F`] require the
+  /// `EXT_color_buffer_float` extension, which
+  /// [`RenderTarget::new`] enables.
+  pub color: Option<TextureFormat>,
+  /// Format of the depth texture, [`TextureFormat::Depth32F`] or
+  /// [`TextureFormat::Depth24Stencil8`], `None` for no depth buffer
+  pub depth: Option<TextureFormat>,
+  /// Samples per pixel of multisampled rendering, 0 to draw directly into
+  /// the textures. At most the `MAX_SAMPLES` of the context, typically 4.
+  pub samples: u32,
+}
+
+impl Default for RenderTargetSettings {
+  fn default() -> Self {
+    RenderTargetSettings { color: Some(TextureFormat::Rgba8), depth: Some(TextureFormat::Depth24Stencil8), samples: 0 }
+  }
+}
+
+/// Framebuffer whose color and depth attachments are textures, to render
+/// offscreen and sample the result
+///
+/// Multisampled targets draw into renderbuffers, which
+/// [`resolve`](RenderTarget::resolve) blits into the textures. The
+/// framebuffers and renderbuffers are deleted when dropped, the textures
+/// when no longer referenced.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rc::Rc;
+/// use j_webgl::{gl::{GlContext, RecordingContext}, target::{RenderTarget, RenderTargetSettings}};
+///
+/// # fn example() -> j_webgl::Result<()> {
+/// let context: Rc<dyn GlContext> = Rc::new(RecordingContext::new(640, 480));
+/// let target = RenderTarget::new(&context, 640, 480, RenderTargetSettings { samples: 4, ..Default::default() })?;
+/// target.bind();
+/// // ... draw ...
+/// target.resolve();
+/// let texture = target.color_texture().unwrap().clone();
+/// # Ok(())
+/// # }
+/// # example().unwrap();
+/// ```
+pub struct RenderTarget {
+  context: Rc<dyn GlContext>,
+  settings: RenderTargetSettings,
+  width: u32,
+  height: u32,
+  framebuffer: gl::Framebuffer,
+  color: Option<Rc<Texture2D>>,
+  depth: Option<Rc<Texture2D>>,
+  multisample: Option<Multisample>,
+}
+
+/// Multisampled framebuffer drawn to by a [`RenderTarget`]
+struct Multisample {
+  framebuffer: gl::Framebuffer,
+  renderbuffers: Vec<gl::Renderbuffer>,
+}
+
+impl RenderTarget {
+  /// Create a target of `width` by `height` pixels
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::InvalidTexture`] if the settings have no attachment, a
+  /// depth color format or a color depth format, and
+  /// [`Error::UnsupportedOperation`] if the framebuffer is not complete or
+  /// float color targets are not supported.
+  pub fn new(context: &Rc<dyn GlContext>, width: u32, height: u32, settings: RenderTargetSettings) -> Result<Self> {
+    if settings.color.is_some_and(|f| f.is_depth())
+      || settings.depth.is_some_and(|f| !f.is_depth())
+      || (settings.color.is_none() && settings.depth.is_none()) {
+      return Err(Error::InvalidTexture(format!("invalid render target attachments {:?}", settings)));
+    }
+    if settings.color.is_some_and(|f| f.is_float()) && !context.get_extension("EXT_color_buffer_float") {
+      return Err(Error::UnsupportedOperation("Float render targets require EXT_color_buffer_float".to_string()));
+    }
+    let framebuffer = context.create_framebuffer().ok_or("Unable to create framebuffer")?;
+    let multisample = match settings.samples {
+      0 => None,
+      _ => Some(Multisample {
+        framebuffer: context.create_framebuffer().ok_or("Unable to create framebuffer")?,
+        renderbuffers: Vec::new(),
+      }),
+    };
+    let mut target = RenderTarget {
+      context: context.clone(), settings, width: 0, height: 0, framebuffer, color: None, depth: None, multisample,
+    };
+    target.resize(width, height)?;
+    Ok(target)
+  }
+
+  /// Reallocate the attachments to a new size, at least one pixel
+  ///
+  /// Textures obtained from [`color_texture`](RenderTarget::color_texture)
+  /// and [`depth_texture`](RenderTarget::depth_texture) before a resize are
+  /// no longer drawn to.
+  pub fn resize(&mut self, width: u32, height: u32) -> Result<()> {
+    let (width, height) = (width.max(1), height.max(1));
+    if (width, height) == (self.width, self.height) { return Ok(()); }
+    let context = self.context.clone();
+    let settings = self.settings;
+    let attachments = [(settings.color, GL::COLOR_ATTACHMENT0), (settings.depth, depth_attachment(settings.depth))];
+
+    let empty = |format: Option<TextureFormat>| -> Result<Option<Rc<Texture2D>>> {
+      format.map(|f| Texture2D::empty(&context, width, height, f).map(Rc::new)).transpose()
+    };
+    self.color = empty(settings.color)?;
+    self.depth = empty(settings.depth)?;
+    context.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+    for (texture, (_, attachment)) in [&self.color, &self.depth].into_iter().zip(attachments) {
+      if let Some(texture) = texture {
+        context.framebuffer_texture_2d(GL::FRAMEBUFFER, attachment, GL::TEXTURE_2D, Some(texture.texture()), 0);
+      }
+    }
+    let mut status = context.check_framebuffer_status(GL::FRAMEBUFFER);
+
+    if let Some(multisample) = self.multisample.as_mut() {
+      for renderbuffer in multisample.renderbuffers.drain(..) {
+        context.delete_renderbuffer(Some(&renderbuffer));
+      }
+      context.bind_framebuffer(GL::FRAMEBUFFER, Some(&multisample.framebuffer));
+      for (format, attachment) in attachments {
+        let Some(format) = format else { continue; };
+        let renderbuffer = context.create_renderbuffer().ok_or("Unable to create renderbuffer")?;
+        context.bind_renderbuffer(GL::RENDERBUFFER, Some(&renderbuffer));
+        context.renderbuffer_storage_multisample(
+          GL::RENDERBUFFER, settings.samples.try_into()?, format.internal_format(), width.try_into()?, height.try_into()?,
+        );
+        context.framebuffer_renderbuffer(GL::FRAMEBUFFER, attachment, GL::RENDERBUFFER, Some(&renderbuffer));
+        multisample.renderbuffers.push(renderbuffer);
+      }
+      context.bind_renderbuffer(GL::RENDERBUFFER, None);
+      if status == GL::FRAMEBUFFER_COMPLETE { status = context.check_framebuffer_status(GL::FRAMEBUFFER); }
+    }
+    context.bind_framebuffer(GL::FRAMEBUFFER, None);
+
+    (self.width, self.height) = (width, height);
+    if status != GL::FRAMEBUFFER_COMPLETE {
+      return Err(Error::UnsupportedOperation(format!("Render target framebuffer is incomplete, status {:#x}", status)));
+    }
+    Ok(())
+  }
+
+  /// Bind the framebuffer drawn to and set the viewport to cover the target
+  pub fn bind(&self) {
+    let framebuffer = self.multisample.as_ref().map(|m| &m.framebuffer).unwrap_or(&self.framebuffer);
+    self.context.bind_framebuffer(GL::FRAMEBUFFER, Some(framebuffer));
+    self.context.viewport(0, 0, self.width as i32, self.height as i32);
+  }
+
+  /// Blit the multisampled renderbuffers into the textures, after drawing
+  /// and before sampling them. Does nothing for targets without multisampling.
+  ///
+  /// The drawing buffer is bound on return.
+  pub fn resolve(&self) {
+    let Some(multisample) = self.multisample.as_ref() else { return; };
+    let mut mask = 0;
+    if self.settings.color.is_some() { mask |= GL::COLOR_BUFFER_BIT; }
+    match self.settings.depth {
+      Some(TextureFormat::Depth24Stencil8) => mask |= GL::DEPTH_BUFFER_BIT | GL::STENCIL_BUFFER_BIT,
+      Some(_) => mask |= GL::DEPTH_BUFFER_BIT,
+      None => {},
+    }
+    let (width, height) = (self.width as i32, self.height as i32);
+    let context = &self.context;
+    context.bind_framebuffer(GL::READ_FRAMEBUFFER, Some(&multisample.framebuffer));
+    context.bind_framebuffer(GL::DRAW_FRAMEBUFFER, Some(&self.framebuffer));
+    context.blit_framebuffer(0, 0, width, height, 0, 0, width, height, mask, GL::NEAREST);
+    context.bind_framebuffer(GL::FRAMEBUFFER, None);
+  }
+
+  pub fn width(&self) -> u32 { self.width }
+
+  pub fn height(&self) -> u32 { self.height }
+
+  pub fn settings(&self) -> &RenderTargetSettings { &self.settings }
+
+  /// Framebuffer the textures are attached to, read from after [`resolve`](RenderTarget::resolve)
+  pub fn framebuffer(&self) -> &gl::Framebuffer { &self.framebuffer }
+
+  pub fn color_texture(&self) -> Option<&Rc<Texture2D>> { self.color.as_ref() }
+
+  pub fn depth_texture(&self) -> Option<&Rc<Texture2D>> { self.depth.as_ref() }
+}
+
+impl Drop for RenderTarget {
+  fn drop(&mut self) {
+    self.context.delete_framebuffer(Some(&self.framebuffer));
+    if let Some(multisample) = self.multisample.as_ref() {
+      self.context.delete_framebuffer(Some(&multisample.framebuffer));
+      for renderbuffer in multisample.renderbuffers.iter() {
+        self.context.delete_renderbuffer(Some(renderbuffer));
+      }
+    }
+  }
+}
+
+/// Framebuffer attachment point of a depth format
+fn depth_attachment(format: Option<TextureFormat>) -> u32 {
+  match format {
+    Some(TextureFormat::Depth24Stencil8) => GL::DEPTH_STENCIL_ATTACHMENT,
+    _ => GL::DEPTH_ATTACHMENT,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use gl::{RecordingContext, Call};
+
+  #[test]
+  fn multisampled_target_resolves_into_textures() {
+    let recording = Rc::new(RecordingContext::new(8, 8));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let settings = RenderTargetSettings { color: Some(TextureFormat::Rgba16F), samples: 4, ..Default::default() };
+    let mut target = RenderTarget::new(&context, 32, 16, settings).unwrap();
+    let calls = recording.take_calls();
+    assert!(calls.contains(&Call::RenderbufferStorageMultisample {
+      target: GL::RENDERBUFFER, samples: 4, internal_format: GL::RGBA16F, width: 32, height: 16,
+    }));
+    assert!(calls.iter().any(|c| matches!(c, Call::FramebufferRenderbuffer { attachment: GL::DEPTH_STENCIL_ATTACHMENT, .. })));
+    assert_eq!(recording.state().framebuffer, None);
+
+    target.bind();
+    assert_eq!(recording.state().viewport, [0, 0, 32, 16]);
+    assert_ne!(recording.state().framebuffer, target.framebuffer().0.id());
+    target.resolve();
+    assert!(recording.calls().contains(&Call::BlitFramebuffer {
+      src: [0, 0, 32, 16], dst: [0, 0, 32, 16],
+      mask: GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT | GL::STENCIL_BUFFER_BIT, filter: GL::NEAREST,
+    }));
+
+    let texture = target.color_texture().unwrap().clone();
+    target.resize(32, 16).unwrap();
+    assert!(Rc::ptr_eq(&texture, target.color_texture().unwrap()));
+    target.resize(64, 64).unwrap();
+    assert_eq!((target.color_texture().unwrap().width(), target.height()), (64, 64));
+    assert!(recording.calls().iter().any(|c| matches!(c, Call::DeleteRenderbuffer(_))));
+
+    let invalid = RenderTargetSettings { color: Some(TextureFormat::Depth32F), ..Default::default() };
+    assert!(matches!(RenderTarget::new(&context, 4, 4, invalid), Err(Error::InvalidTexture(_))));
+  }
+}
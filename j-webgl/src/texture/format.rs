@@ -0,0 +1,94 @@
+use web_sys::WebGl2RenderingContext as GL;
+
+/// Pixel layout of a texture
+///
+/// 32-bit float formats are not filterable in WebGL 2.0 without the
+/// `OES_texture_float_linear` extension: sample them with [`Filter::Nearest`](super::Filter::Nearest).
+/// Half float formats are filterable, and can only be drawn to with the
+/// `EXT_color_buffer_float` extension, as can 32-bit float formats. Integer
+/// formats are drawn to and read back, not sampled with filtering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFormat {
+  Rgba8,
+  Rgb8,
+  R8,
+  /// Half float color, uploaded from `f32` values
+  Rgba16F,
+  Rgba32F,
+  R32F,
+  /// 32-bit unsigned integer, to attach as a framebuffer color buffer of identifiers
+  R32UI,
+  /// 32-bit float depth, to attach as a framebuffer depth buffer
+  Depth32F,
+  /// 24-bit depth and 8-bit stencil, to attach as a framebuffer depth-stencil buffer
+  Depth24Stencil8,
+}
+
+impl TextureFormat {
+  /// Number of channels per pixel
+  pub fn channels(&self) -> usize {
+    match self {
+      TextureFormat::Rgba8 | TextureFormat::Rgba16F | TextureFormat::Rgba32F => 4,
+      TextureFormat::Rgb8 => 3,
+      TextureFormat::R8 | TextureFormat::R32F | TextureFormat::R32UI
+        | TextureFormat::Depth32F | TextureFormat::Depth24Stencil8 => 1,
+    }
+  }
+
+  /// Whether pixels are `f32` rather than `u8` values
+  pub fn is_float(&self) -> bool {
+    matches!(self, TextureFormat::Rgba16F | TextureFormat::Rgba32F | TextureFormat::R32F | TextureFormat::Depth32F)
+  }
+
+  /// Whether pixels are unsigned integer values
+  pub fn is_integer(&self) -> bool {
+    matches!(self, TextureFormat::R32UI)
+  }
+
+  /// Whether the format can be sampled with linear filtering without extension
+  pub fn is_filterable(&self) -> bool {
+    (!self.is_float() && !self.is_integer()) || matches!(self, TextureFormat::Rgba16F)
+  }
+
+  /// Whether the format holds depth values rather than colors
+  pub fn is_depth(&self) -> bool {
+    matches!(self, TextureFormat::Depth32F | TextureFormat::Depth24Stencil8)
+  }
+
+  /// WebGL sized internal format
+  pub fn internal_format(&self) -> u32 {
+    match self {
+      TextureFormat::Rgba8 => GL::RGBA8,
+      TextureFormat::Rgb8 => GL::RGB8,
+      TextureFormat::R8 => GL::R8,
+      TextureFormat::Rgba16F => GL::RGBA16F,
+      TextureFormat::Rgba32F => GL::RGBA32F,
+      TextureFormat::R32F => GL::R32F,
+      TextureFormat::R32UI => GL::R32UI,
+      TextureFormat::Depth32F => GL::DEPTH_COMPONENT32F,
+      TextureFormat::Depth24Stencil8 => GL::DEPTH24_STENCIL8,
+    }
+  }
+
+  /// WebGL format of the uploaded pixels
+  pub fn format(&self) -> u32 {
+    match self {
+      TextureFormat::Rgba8 | TextureFormat::Rgba16F | TextureFormat::Rgba32F => GL::RGBA,
+      TextureFormat::Rgb8 => GL::RGB,
+      TextureFormat::R8 | TextureFormat::R32F => GL::RED,
+      TextureFormat::R32UI => GL::RED_INTEGER,
+      TextureFormat::Depth32F => GL::DEPTH_COMPONENT,
+      TextureFormat::Depth24Stencil8 => GL::DEPTH_STENCIL,
+    }
+  }
+
+  /// WebGL type of the uploaded values
+  pub fn data_type(&self) -> u32 {
+    match self {
+      TextureFormat::Depth24Stencil8 => GL::UNSIGNED_INT_24_8,
+      TextureFormat::R32UI => GL::UNSIGNED_INT,
+      _ if self.is_float() => GL::FLOAT,
+      _ => GL::UNSIGNED_BYTE,
+    }
+  }
+}
@@ -0,0 +1,85 @@
+use super::*;
+
+/// Decoded 8-bit image, rows ordered from top to bottom
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+  pub width: u32,
+  pub height: u32,
+  /// [`TextureFormat::Rgba8`], [`TextureFormat::Rgb8`] or [`TextureFormat::R8`]
+  pub format: TextureFormat,
+  pub data: Vec<u8>,
+}
+
+impl Image {
+  /// Wrap pixels, checking that `data` holds `width * height` pixels of `format`
+  pub fn new(width: u32, height: u32, format: TextureFormat, data: Vec<u8>) -> Result<Self> {
+    if format.is_float() || format.is_integer() || format.is_depth() {
+      return Err(Error::InvalidTexture(format!("{:?} is not an 8-bit format", format)));
+    }
+    let expected = width as usize * height as usize * format.channels();
+    if data.len() != expected {
+      return Err(Error::InvalidTexture(format!("{} bytes for a {}x{} {:?} image, expected {}", data.len(), width, height, format, expected)));
+    }
+    Ok(Image { width, height, format, data })
+  }
+
+  /// Decode a PNG file
+  ///
+  /// Palette and low bit depth images are expanded and 16-bit channels are
+  /// reduced to 8 bits. Grayscale images are expanded to RGB, so that they
+  /// render gray rather than red.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::ImageDecode`] for data that is not a valid PNG file.
+  pub fn from_png(data: &[u8]) -> Result<Self> {
+    let error = |e: png::DecodingError| Error::ImageDecode(e.to_string());
+    let mut decoder = png::Decoder::new(std::io::Cursor::new(data));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(error)?;
+    let size = reader.output_buffer_size().ok_or_else(|| Error::ImageDecode("image too large".to_string()))?;
+    let mut pixels = vec![0; size];
+    let info = reader.next_frame(&mut pixels).map_err(error)?;
+    pixels.truncate(info.buffer_size());
+
+    let (format, data) = match info.color_type {
+      png::ColorType::Rgba => (TextureFormat::Rgba8, pixels),
+      png::ColorType::Rgb => (TextureFormat::Rgb8, pixels),
+      png::ColorType::Grayscale => (TextureFormat::Rgb8, pixels.iter().flat_map(|v| [*v; 3]).collect()),
+      png::ColorType::GrayscaleAlpha => (
+        TextureFormat::Rgba8,
+        pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+      ),
+      png::ColorType::Indexed => return Err(Error::ImageDecode("palette was not expanded".to_string())),
+    };
+    Image::new(info.width, info.height, format, data)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode(width: u32, height: u32, color: png::ColorType, depth: png::BitDepth, data: &[u8]) -> Vec<u8> {
+    let mut file = Vec::new();
+    let mut encoder = png::Encoder::new(&mut file, width, height);
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+    encoder.write_header().unwrap().write_image_data(data).unwrap();
+    file
+  }
+
+  #[test]
+  fn decodes_png_color_types() {
+    let rgba = encode(2, 1, png::ColorType::Rgba, png::BitDepth::Eight, &[255, 0, 0, 255, 0, 0, 255, 128]);
+    assert_eq!(Image::from_png(&rgba).unwrap(), Image::new(2, 1, TextureFormat::Rgba8, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap());
+
+    let gray = encode(1, 2, png::ColorType::Grayscale, png::BitDepth::Sixteen, &[0x80, 0x00, 0xFF, 0xFF]);
+    let image = Image::from_png(&gray).unwrap();
+    assert_eq!((image.width, image.height, image.format), (1, 2, TextureFormat::Rgb8));
+    assert_eq!(image.data, vec![0x80, 0x80, 0x80, 0xFF, 0xFF, 0xFF]);
+
+    assert!(matches!(Image::from_png(b"not a png"), Err(Error::ImageDecode(_))));
+    assert!(matches!(Image::new(2, 2, TextureFormat::Rgb8, vec![0; 11]), Err(Error::InvalidTexture(_))));
+  }
+}
@@ -0,0 +1,6 @@
+use super::*;
+
+mod format; pub use format::TextureFormat;
+mod sampler; pub use sampler::{Sampler, Wrap, Filter};
+mod image; pub use image::Image;
+mod texture2d; pub use texture2d::Texture2D;
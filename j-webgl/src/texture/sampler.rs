@@ -0,0 +1,77 @@
+use web_sys::WebGl2RenderingContext as GL;
+
+/// Behaviour of texture coordinates outside `[0, 1]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+  Repeat,
+  ClampToEdge,
+  MirroredRepeat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+  Nearest,
+  Linear,
+}
+
+/// Wrap and filter settings applied to a texture
+///
+/// `mipmap_filter` selects between mipmap levels when minifying; it requires
+/// the texture mipmaps to have been generated, the texture sampling as black
+/// otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sampler {
+  pub wrap_s: Wrap,
+  pub wrap_t: Wrap,
+  pub min_filter: Filter,
+  pub mag_filter: Filter,
+  pub mipmap_filter: Option<Filter>,
+}
+
+impl Default for Sampler {
+  fn default() -> Self {
+    Sampler { wrap_s: Wrap::Repeat, wrap_t: Wrap::Repeat, min_filter: Filter::Linear, mag_filter: Filter::Linear, mipmap_filter: None }
+  }
+}
+
+impl Sampler {
+  /// Nearest filtering without mipmaps, for pixel art and float textures
+  pub fn nearest() -> Self {
+    Sampler { min_filter: Filter::Nearest, mag_filter: Filter::Nearest, ..Default::default() }
+  }
+
+  /// Linear filtering between linearly filtered mipmap levels
+  pub fn trilinear() -> Self {
+    Sampler { mipmap_filter: Some(Filter::Linear), ..Default::default() }
+  }
+
+  pub fn with_wrap(mut self, wrap: Wrap) -> Self {
+    self.wrap_s = wrap;
+    self.wrap_t = wrap;
+    self
+  }
+
+  /// `TEXTURE_WRAP_S`, `TEXTURE_WRAP_T`, `TEXTURE_MIN_FILTER` and `TEXTURE_MAG_FILTER` values
+  pub(crate) fn parameters(&self) -> [(u32, u32); 4] {
+    let wrap = |wrap: Wrap| match wrap {
+      Wrap::Repeat => GL::REPEAT,
+      Wrap::ClampToEdge => GL::CLAMP_TO_EDGE,
+      Wrap::MirroredRepeat => GL::MIRRORED_REPEAT,
+    };
+    let min_filter = match (self.min_filter, self.mipmap_filter) {
+      (Filter::Nearest, None) => GL::NEAREST,
+      (Filter::Linear, None) => GL::LINEAR,
+      (Filter::Nearest, Some(Filter::Nearest)) => GL::NEAREST_MIPMAP_NEAREST,
+      (Filter::Linear, Some(Filter::Nearest)) => GL::LINEAR_MIPMAP_NEAREST,
+      (Filter::Nearest, Some(Filter::Linear)) => GL::NEAREST_MIPMAP_LINEAR,
+      (Filter::Linear, Some(Filter::Linear)) => GL::LINEAR_MIPMAP_LINEAR,
+    };
+    let mag_filter = match self.mag_filter { Filter::Nearest => GL::NEAREST, Filter::Linear => GL::LINEAR };
+    [
+      (GL::TEXTURE_WRAP_S, wrap(self.wrap_s)),
+      (GL::TEXTURE_WRAP_T, wrap(self.wrap_t)),
+      (GL::TEXTURE_MIN_FILTER, min_filter),
+      (GL::TEXTURE_MAG_FILTER, mag_filter),
+    ]
+  }
+}
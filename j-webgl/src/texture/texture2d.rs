@@ -0,0 +1,210 @@
+use super::*;
+
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as GL;
+use gl::GlContext;
+
+/// 2D texture object with its sampler settings
+///
+/// The first row of pixels maps to texture coordinate `t = 0`, which is the
+/// top of images decoded with [`Image::from_png`] and the `v = 0` edge of
+/// the meshes in [`geometry`]. The texture is deleted when dropped.
+pub struct Texture2D {
+  context: Rc<dyn GlContext>,
+  texture: gl::Texture,
+  width: u32,
+  height: u32,
+  format: TextureFormat,
+  sampler: Sampler,
+  has_mipmaps: bool,
+}
+
+impl Texture2D {
+  /// Upload `width * height` pixels of an 8-bit format, rows tightly packed
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::InvalidTexture`] if `format` is a float, integer or
+  /// depth format or if the length of `data` does not match the dimensions.
+  pub fn from_bytes(context: &Rc<dyn GlContext>, width: u32, height: u32, format: TextureFormat, data: &[u8]) -> Result<Self> {
+    if format.is_float() || format.is_integer() || format.is_depth() {
+      return Err(Error::InvalidTexture(format!("{:?} pixels cannot be uploaded from bytes", format)));
+    }
+    check_length(width, height, format, data.len())?;
+    let texture = Texture2D::create(context, width, height, format)?;
+    texture.context.tex_image_2d_with_u8_array(
+      GL::TEXTURE_2D, 0, format.internal_format() as i32, width.try_into()?, height.try_into()?,
+      format.format(), format.data_type(), Some(data),
+    )?;
+    Ok(texture)
+  }
+
+  /// Upload `width * height` pixels of a float format, rows tightly packed
+  ///
+  /// Textures of formats that are not filterable are given a [`Sampler::nearest`] sampler.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::InvalidTexture`] if `format` is not a float format or if
+  /// the length of `data` does not match the dimensions.
+  pub fn from_f32(context: &Rc<dyn GlContext>, width: u32, height: u32, format: TextureFormat, data: &[f32]) -> Result<Self> {
+    if !format.is_float() {
+      return Err(Error::InvalidTexture(format!("{:?} pixels must be uploaded with from_bytes", format)));
+    }
+    check_length(width, height, format, data.len())?;
+    let mut texture = Texture2D::create(context, width, height, format)?;
+    texture.context.tex_image_2d_with_f32_array(
+      GL::TEXTURE_2D, 0, format.internal_format() as i32, width.try_into()?, height.try_into()?,
+      format.format(), data,
+    )?;
+    if !format.is_filterable() {
+      texture.set_sampler(Sampler::nearest())?;
+    }
+    Ok(texture)
+  }
+
+  /// Allocate a texture without pixels, to attach to a framebuffer
+  ///
+  /// Textures of formats that are not filterable are given a [`Sampler::nearest`]
+  /// sampler, with coordinates clamped to the edges for depth textures.
+  pub fn empty(context: &Rc<dyn GlContext>, width: u32, height: u32, format: TextureFormat) -> Result<Self> {
+    let mut texture = Texture2D::create(context, width, height, format)?;
+    texture.context.tex_image_2d_with_u8_array(
+      GL::TEXTURE_2D, 0, format.internal_format() as i32, width.try_into()?, height.try_into()?,
+      format.format(), format.data_type(), None,
+    )?;
+    if format.is_depth() {
+      texture.set_sampler(Sampler::nearest().with_wrap(Wrap::ClampToEdge))?;
+    } else if !format.is_filterable() {
+      texture.set_sampler(Sampler::nearest())?;
+    }
+    Ok(texture)
+  }
+
+  pub fn from_image(context: &Rc<dyn GlContext>, image: &Image) -> Result<Self> {
+    Texture2D::from_bytes(context, image.width, image.height, image.format, &image.data)
+  }
+
+  /// Decode a PNG file, see [`Image::from_png`], and upload it
+  pub fn from_png(context: &Rc<dyn GlContext>, data: &[u8]) -> Result<Self> {
+    Texture2D::from_image(context, &Image::from_png(data)?)
+  }
+
+  /// Upload a loaded HTML image as [`TextureFormat::Rgba8`]
+  pub fn from_html_image_element(context: &Rc<dyn GlContext>, image: &web_sys::HtmlImageElement) -> Result<Self> {
+    let format = TextureFormat::Rgba8;
+    let texture = Texture2D::create(context, image.natural_width(), image.natural_height(), format)?;
+    texture.context.tex_image_2d_with_html_image_element(
+      GL::TEXTURE_2D, 0, format.internal_format() as i32, format.format(), format.data_type(), image,
+    )?;
+    Ok(texture)
+  }
+
+  /// Upload an image bitmap as [`TextureFormat::Rgba8`]
+  pub fn from_image_bitmap(context: &Rc<dyn GlContext>, bitmap: &web_sys::ImageBitmap) -> Result<Self> {
+    let format = TextureFormat::Rgba8;
+    let texture = Texture2D::create(context, bitmap.width(), bitmap.height(), format)?;
+    texture.context.tex_image_2d_with_image_bitmap(
+      GL::TEXTURE_2D, 0, format.internal_format() as i32, format.format(), format.data_type(), bitmap,
+    )?;
+    Ok(texture)
+  }
+
+  fn create(context: &Rc<dyn GlContext>, width: u32, height: u32, format: TextureFormat) -> Result<Self> {
+    let context = context.clone();
+    let texture = context.create_texture().ok_or("Unable to create texture")?;
+    context.bind_texture(GL::TEXTURE_2D, Some(&texture));
+    context.pixel_storei(GL::UNPACK_ALIGNMENT, 1);
+    let mut texture = Texture2D { context, texture, width, height, format, sampler: Sampler::default(), has_mipmaps: false };
+    texture.set_sampler(Sampler::default())?;
+    Ok(texture)
+  }
+
+  /// Generate the mipmap levels used by samplers with a `mipmap_filter`
+  pub fn generate_mipmaps(&mut self) -> Result<()> {
+    if self.format.is_float() || self.format.is_integer() || self.format.is_depth() {
+      return Err(Error::InvalidTexture(format!("mipmaps cannot be generated for {:?} textures", self.format)));
+    }
+    self.context.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+    self.context.generate_mipmap(GL::TEXTURE_2D);
+    self.has_mipmaps = true;
+    Ok(())
+  }
+
+  pub fn set_sampler(&mut self, sampler: Sampler) -> Result<()> {
+    self.context.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+    for (pname, param) in sampler.parameters() {
+      self.context.tex_parameteri(GL::TEXTURE_2D, pname, param as i32);
+    }
+    self.sampler = sampler;
+    Ok(())
+  }
+
+  pub fn sampler(&self) -> Sampler { self.sampler }
+
+  pub fn has_mipmaps(&self) -> bool { self.has_mipmaps }
+
+  /// Bind the texture to texture unit `unit`, the value to give its `sampler2D` uniform
+  pub fn bind(&self, unit: u32) {
+    self.context.active_texture(GL::TEXTURE0 + unit);
+    self.context.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+  }
+
+  pub fn width(&self) -> u32 { self.width }
+
+  pub fn height(&self) -> u32 { self.height }
+
+  pub fn format(&self) -> TextureFormat { self.format }
+
+  pub fn texture(&self) -> &gl::Texture { &self.texture }
+}
+
+impl Drop for Texture2D {
+  fn drop(&mut self) {
+    self.context.delete_texture(Some(&self.texture));
+  }
+}
+
+fn check_length(width: u32, height: u32, format: TextureFormat, length: usize) -> Result<()> {
+  let expected = width as usize * height as usize * format.channels();
+  if length != expected {
+    return Err(Error::InvalidTexture(format!("{} values for a {}x{} {:?} texture, expected {}", length, width, height, format, expected)));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use gl::{RecordingContext, Call};
+
+  #[test]
+  fn uploads_pixels_and_sampler() {
+    let recording = Rc::new(RecordingContext::new(8, 8));
+    let context: Rc<dyn GlContext> = recording.clone();
+    let mut texture = Texture2D::from_bytes(&context, 2, 1, TextureFormat::Rgb8, &[255, 0, 0, 0, 255, 0]).unwrap();
+    texture.set_sampler(Sampler::trilinear().with_wrap(Wrap::ClampToEdge)).unwrap();
+    texture.generate_mipmaps().unwrap();
+    texture.bind(2);
+
+    let calls = recording.calls();
+    assert!(calls.contains(&Call::PixelStorei { pname: GL::UNPACK_ALIGNMENT, param: 1 }));
+    assert!(calls.contains(&Call::TexImage2D {
+      target: GL::TEXTURE_2D, level: 0, internal_format: GL::RGB8 as i32, width: 2, height: 1,
+      format: GL::RGB, data_type: GL::UNSIGNED_BYTE, data: Some(vec![255, 0, 0, 0, 255, 0]),
+    }));
+    assert!(calls.contains(&Call::TexParameteri { target: GL::TEXTURE_2D, pname: GL::TEXTURE_WRAP_S, param: GL::CLAMP_TO_EDGE as i32 }));
+    assert!(calls.contains(&Call::TexParameteri { target: GL::TEXTURE_2D, pname: GL::TEXTURE_MIN_FILTER, param: GL::LINEAR_MIPMAP_LINEAR as i32 }));
+    assert!(calls.contains(&Call::GenerateMipmap(GL::TEXTURE_2D)));
+    assert_eq!(calls[calls.len() - 2], Call::ActiveTexture(GL::TEXTURE2));
+
+    let id = texture.texture().0.id();
+    drop(texture);
+    assert_eq!(recording.calls().last(), Some(&Call::DeleteTexture(id.unwrap())));
+
+    assert!(matches!(Texture2D::from_bytes(&context, 2, 2, TextureFormat::Rgba8, &[0; 15]), Err(Error::InvalidTexture(_))));
+    assert!(matches!(Texture2D::from_f32(&context, 1, 1, TextureFormat::Rgba8, &[0.0; 4]), Err(Error::InvalidTexture(_))));
+    let float = Texture2D::from_f32(&context, 1, 1, TextureFormat::R32F, &[0.5]).unwrap();
+    assert_eq!(float.sampler(), Sampler::nearest());
+  }
+}
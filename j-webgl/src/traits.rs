@@ -1,4 +1,4 @@
-use super::{Result};
+use super::{Result, gl, algebra, FrameContext};
 
 /// Trait to implement for camera/view matrix providers
 ///
@@ -6,18 +6,68 @@ use super::{Result};
 /// for rendering operations. The matrix should be provided as a flat array of
 /// 16 `f32` values representing a 4x4 matrix.
 ///
+/// Cameras can also expose their separate view and projection matrices and
+/// eye position, which renderables receive through [`FrameContext`] for
+/// lighting or billboarding. The default implementations treat the whole
+/// MVP matrix as the projection, with an identity view, so that implementing
+/// [`as_f32_array`](Camera::as_f32_array) is enough.
+///
 pub trait Camera {
   /// Output camera Model-View-Projection matrix
   ///
   /// Returns a reference to a slice of 16 `f32` values representing a 4x4 matrix.
-  /// The matrix format should be column-major (OpenGL/WebGL standard).
+  /// The matrix format should be column-major (OpenGL/WebGL standard), as
+  /// produced by [`Matrix4::as_slice`](crate::algebra::Matrix4::as_slice).
   ///
   /// # Errors
   ///
   /// Returns an error if the matrix cannot be generated or accessed.
-  ///
-  /// TODO: Check if format is column or row based
   fn as_f32_array(&self) -> Result<&[f32]>;
+
+  /// Output the view matrix, transforming world space into view (eye) space
+  fn view(&self) -> Result<algebra::Matrix4> {
+    Ok(algebra::Matrix4::identity())
+  }
+
+  /// Output the projection matrix, transforming view space into clip space
+  fn projection(&self) -> Result<algebra::Matrix4> {
+    self.view_projection()
+  }
+
+  /// Output the product `projection * view`
+  fn view_projection(&self) -> Result<algebra::Matrix4> {
+    Ok(algebra::Matrix4::try_from(self.as_f32_array()?)?)
+  }
+
+  /// Output the eye position in world space
+  fn eye_position(&self) -> Result<algebra::Point3> {
+    let inverse = self.view()?.inverse()
+      .ok_or_else(|| crate::Error::Misc("Camera view matrix is not invertible".to_string()))?;
+    Ok(inverse.transform_point(&algebra::Point3::origin()))
+  }
+
+  /// Output the viewport size (width, height) in CSS pixels the camera was set up for, if known
+  fn viewport_size(&self) -> Option<(f32, f32)> {
+    None
+  }
+
+  /// Output the world space ray through a point of the viewport, for picking
+  /// or measuring on the CPU
+  ///
+  /// `x` and `y` are in CSS pixels from the top left corner of the
+  /// [`viewport_size`](Camera::viewport_size), as the `offsetX` and `offsetY`
+  /// of mouse events. See [`Ray::from_screen`](algebra::Ray::from_screen).
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the viewport size is unknown or the view projection
+  /// matrix is not invertible.
+  fn ray(&self, x: f32, y: f32) -> Result<algebra::Ray> {
+    let (width, height) = self.viewport_size()
+      .ok_or_else(|| crate::Error::Misc("Camera viewport size is unknown".to_string()))?;
+    algebra::Ray::from_screen(&self.view_projection()?, x, y, width, height)
+      .ok_or_else(|| crate::Error::Misc("Camera view projection matrix is not invertible".to_string()))
+  }
 }
 
 /// Trait to be implemented for struct that can be rendered
@@ -26,27 +76,84 @@ pub trait Camera {
 /// drawn during the render pass. The implementation is responsible for setting
 /// up the necessary WebGL state, binding buffers, setting uniforms, and drawing.
 pub trait Renderable {
-  /// Trigger rendering with a given context and camera Model-View-Projection matrix
+  /// Trigger rendering with a given context and frame information
   ///
   /// This method is called by the `Renderer` for each renderable object during
   /// a render pass. The implementation should:
   ///
   /// 1. Set up the shader program
   /// 2. Bind necessary buffers (VAO, VBO, EBO, etc.)
-  /// 3. Set uniforms (including the camera MVP matrix)
+  /// 3. Set uniforms (including the MVP matrix from [`FrameContext::mvp`])
   /// 4. Issue draw calls
   ///
   /// # Parameters
   ///
-  /// - `context`: The WebGL 2.0 rendering context
-  /// - `camera_mvp`: A slice of 16 `f32` values representing the camera's MVP matrix
+  /// - `context`: The rendering context, a WebGL 2.0 context in the browser or
+  ///   an in-memory implementation such as [`gl::RecordingContext`] in tests
+  /// - `frame`: The camera matrices, eye position and viewport for this render pass
   ///
   /// # Errors
   ///
   /// Returns an error if rendering fails (e.g., shader compilation, buffer binding, etc.)
   fn render(
     &self, 
-    context: &web_sys::WebGl2RenderingContext,
-    camera_mvp: &[f32],
+    context: &std::rc::Rc<dyn gl::GlContext>,
+    frame: &FrameContext,
   ) -> Result<()>;
+
+  /// Render the object depth only, from the shadow casting light
+  ///
+  /// This method is called by the `Renderer` during shadow passes, with a
+  /// depth framebuffer bound and a `frame` whose [`FrameContext::mvp`] maps
+  /// the object into the clip space of the light. Objects that cast shadows
+  /// draw their triangles, typically with [`programs::Depth`](crate::programs::Depth).
+  /// The default implementation draws nothing, so that the object casts no shadow.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if rendering fails
+  fn render_depth(
+    &self,
+    _context: &std::rc::Rc<dyn gl::GlContext>,
+    _frame: &FrameContext,
+  ) -> Result<()> {
+    Ok(())
+  }
+
+  /// Box bounding the object in model space, if known
+  ///
+  /// The [`Renderer`](crate::Renderer) skips objects whose bounds are
+  /// outside of the view frustum, see [`FrameContext::is_visible`]. The
+  /// default implementation returns `None`, so that the object is always rendered.
+  fn bounding_box(&self) -> Option<algebra::Aabb> {
+    None
+  }
+
+  /// Sphere bounding the object in model space, if known
+  ///
+  /// The sphere is tested before the box, being cheaper. The default
+  /// implementation returns the sphere around the [`bounding_box`](Renderable::bounding_box).
+  fn bounding_sphere(&self) -> Option<algebra::Sphere> {
+    self.bounding_box().map(|b| algebra::Sphere::new(b.center(), 0.5 * b.size().norm()))
+  }
+
+  /// Render the object as the flat identifier `id`, for picking
+  ///
+  /// This method is called by [`Renderer::pick`](crate::Renderer::pick) with
+  /// an unsigned integer framebuffer bound. Pickable objects draw their
+  /// triangles writing `id`, typically with [`programs::ObjectId`](crate::programs::ObjectId).
+  /// The default implementation draws nothing, so that the object can neither
+  /// be picked nor hide the objects behind it.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if rendering fails
+  fn render_id(
+    &self,
+    _context: &std::rc::Rc<dyn gl::GlContext>,
+    _frame: &FrameContext,
+    _id: u32,
+  ) -> Result<()> {
+    Ok(())
+  }
 }
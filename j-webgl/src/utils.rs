@@ -1,9 +1,9 @@
-use super::{Result, Error};
+use super::{Result, Error, gl, shaders, structs::light::{Light, LightKind}};
 
-pub fn compile_program<V, F>(context: &web_sys::WebGl2RenderingContext,
+pub fn compile_program<V, F>(context: &dyn gl::GlContext,
   vertex_shader_source: V,
   fragment_shader_source: F,
-) -> Result<web_sys::WebGlProgram> 
+) -> Result<gl::Program> 
 where V: std::convert::AsRef<str>,
       F: std::convert::AsRef<str>,
 {
@@ -24,10 +24,7 @@ where V: std::convert::AsRef<str>,
   context.attach_shader(&program, &fragment_shader);
   context.link_program(&program);
 
-  if !context.get_program_parameter(&program, web_sys::WebGl2RenderingContext::LINK_STATUS)
-            .as_bool()
-            .unwrap_or(false)
-  {
+  if !context.get_program_link_status(&program) {
     let error = context
       .get_program_info_log(&program)
       .unwrap_or_else(|| "Unknown error".to_string());
@@ -36,10 +33,10 @@ where V: std::convert::AsRef<str>,
   Ok(program)
 }
 
-pub fn compile_shader<T: std::convert::AsRef<str>>(context: &web_sys::WebGl2RenderingContext,
+pub fn compile_shader<T: std::convert::AsRef<str>>(context: &dyn gl::GlContext,
         shader_type: u32,
         source: T,
-) -> Result<web_sys::WebGlShader> {
+) -> Result<gl::Shader> {
   let shader = context
     .create_shader(shader_type)
     .ok_or_else(|| Error::UnsupportedOperation("Failed to create shader".to_string()))?;
@@ -47,11 +44,7 @@ pub fn compile_shader<T: std::convert::AsRef<str>>(context: &web_sys::WebGl2Rend
   context.shader_source(&shader, source.as_ref());
   context.compile_shader(&shader);
 
-  if !context
-      .get_shader_parameter(&shader, web_sys::WebGl2RenderingContext::COMPILE_STATUS)
-      .as_bool()
-      .unwrap_or(false)
-  {
+  if !context.get_shader_compile_status(&shader) {
     let error = context
       .get_shader_info_log(&shader)
       .unwrap_or_else(|| "Unknown error".to_string());
@@ -61,3 +54,53 @@ pub fn compile_shader<T: std::convert::AsRef<str>>(context: &web_sys::WebGl2Rend
   Ok(shader)
 }
 
+/// Set the `u_ambient`, `u_lightCount` and `u_lights` uniforms of the program in use
+///
+/// The program must declare the lights of [`shaders::fragment::phong`] and
+/// [`shaders::fragment::pbr`].
+///
+/// # Errors
+///
+/// Returns [`Error::UnsupportedOperation`] if there are more than
+/// [`MAX_LIGHTS`](shaders::fragment::MAX_LIGHTS) lights.
+pub fn set_lights(context: &dyn gl::GlContext, program: &gl::Program, ambient: [f32; 3], lights: &[Light]) -> Result<()> {
+  if lights.len() > shaders::fragment::MAX_LIGHTS {
+    return Err(Error::UnsupportedOperation(
+      format!("{} lights, at most {} are supported", lights.len(), shaders::fragment::MAX_LIGHTS)
+    ));
+  }
+  let vector = |name: &str, vector: [f32; 3]| {
+    let location = context.get_uniform_location(program, name);
+    context.uniform3fv_with_f32_array(location.as_ref(), &vector);
+  };
+  let int = |name: &str, value: i32| {
+    let location = context.get_uniform_location(program, name);
+    context.uniform1i(location.as_ref(), value);
+  };
+  let float = |name: &str, value: f32| {
+    let location = context.get_uniform_location(program, name);
+    context.uniform1f(location.as_ref(), value);
+  };
+
+  vector("u_ambient", ambient);
+  int("u_lightCount", lights.len() as i32);
+  for (i, light) in lights.iter().enumerate() {
+    let name = |field: &str| format!("u_lights[{i}].{field}");
+    int(&name("type"), match light.kind() {
+      LightKind::Directional => 0,
+      LightKind::Point => 1,
+      LightKind::Spot => 2,
+    });
+    let position = light.position();
+    vector(&name("position"), [position.x(), position.y(), position.z()]);
+    let direction = light.direction();
+    vector(&name("direction"), [direction.dx(), direction.dy(), direction.dz()]);
+    vector(&name("color"), light.radiance());
+    vector(&name("attenuation"), light.attenuation());
+    let (inner, outer) = light.cone();
+    float(&name("cosOuter"), outer.cos());
+    // smoothstep is undefined for equal edges
+    float(&name("cosInner"), inner.cos().max(outer.cos() + 1e-4));
+  }
+  Ok(())
+}
@@ -0,0 +1,164 @@
+//! Golden-image tests rendering built-in renderables with the software context
+//!
+//! Set `J_WEBGL_UPDATE_GOLDEN=1` to regenerate the reference images.
+
+use std::rc::Rc;
+use j_webgl::{Renderer, Renderable, Result, gl::SoftwareContext, structs};
+
+const WIDTH: i32 = 128;
+const HEIGHT: i32 = 128;
+/// Largest per-channel difference tolerated between a rendering and its golden image
+const TOLERANCE: u8 = 2;
+
+fn render<R: Renderable + 'static>(renderable: R) -> Result<Vec<u8>> {
+  render_lit(renderable, Vec::new())
+}
+
+fn render_lit<R: Renderable + 'static>(renderable: R, lights: Vec<structs::light::Light>) -> Result<Vec<u8>> {
+  let context = Rc::new(SoftwareContext::new(WIDTH, HEIGHT));
+  let mut renderer = Renderer::from_context(context.clone());
+  for (i, light) in lights.into_iter().enumerate() {
+    renderer.with_light(format!("light{i}"), Some(light))?;
+  }
+  renderer.with_renderable("background".to_string(), Some(structs::renderable::Initializer::new()?))?;
+  renderer.with_renderable("object".to_string(), Some(renderable))?;
+  renderer.render(&structs::camera::Basic::new(WIDTH as f32, HEIGHT as f32)?)?;
+  Ok(context.pixels())
+}
+
+fn path(name: &str) -> std::path::PathBuf {
+  std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{name}.png"))
+}
+
+fn write_png(path: &std::path::Path, pixels: &[u8]) {
+  let file = std::fs::File::create(path).unwrap();
+  let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), WIDTH as u32, HEIGHT as u32);
+  encoder.set_color(png::ColorType::Rgba);
+  encoder.set_depth(png::BitDepth::Eight);
+  encoder.write_header().unwrap().write_image_data(pixels).unwrap();
+}
+
+fn read_png(path: &std::path::Path) -> (u32, u32, Vec<u8>) {
+  let decoder = png::Decoder::new(std::io::BufReader::new(std::fs::File::open(path).unwrap()));
+  let mut reader = decoder.read_info().unwrap();
+  let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+  let info = reader.next_frame(&mut pixels).unwrap();
+  pixels.truncate(info.buffer_size());
+  (info.width, info.height, pixels)
+}
+
+fn assert_matches_golden(name: &str, pixels: &[u8]) {
+  let path = path(name);
+  if std::env::var_os("J_WEBGL_UPDATE_GOLDEN").is_some() {
+    write_png(&path, pixels);
+  }
+  let (width, height, golden) = read_png(&path);
+  assert_eq!((width, height), (WIDTH as u32, HEIGHT as u32), "golden image {name} has unexpected size");
+  let mismatches = pixels.chunks(4).zip(golden.chunks(4))
+    .filter(|(a, b)| a.iter().zip(b.iter()).any(|(a, b)| a.abs_diff(*b) > TOLERANCE))
+    .count();
+  assert_eq!(mismatches, 0, "{mismatches} pixels differ from golden image {name}");
+}
+
+#[test]
+fn cube() {
+  let pixels = render(structs::renderable::Cube::new().unwrap()).unwrap();
+  assert_matches_golden("cube", &pixels);
+}
+
+#[test]
+fn cube_with_normals() {
+  let pixels = render(structs::renderable::CubeWithNormals::new().unwrap()).unwrap();
+  assert_matches_golden("cube_with_normals", &pixels);
+}
+
+#[test]
+fn torus() {
+  let pixels = render(structs::renderable::ShadedMesh::torus(1.0, 0.35, 32, 16).unwrap()).unwrap();
+  assert_matches_golden("torus", &pixels);
+}
+
+#[test]
+fn textured_cuboid() {
+  use j_webgl::{geometry, texture};
+  // 4x4 checker board of orange and blue texels
+  let data = (0..16).flat_map(|i| if (i % 4 + i / 4) % 2 == 0 { [240, 140, 20, 255] } else { [30, 80, 200, 255] }).collect();
+  let image = texture::Image::new(4, 4, texture::TextureFormat::Rgba8, data).unwrap();
+  let textured = structs::renderable::TexturedMesh::new(geometry::cuboid(1.5, 1.5, 1.5).unwrap(), image).unwrap()
+    .with_sampler(texture::Sampler::nearest());
+  let pixels = render(textured).unwrap();
+  assert_matches_golden("textured_cuboid", &pixels);
+}
+
+#[test]
+fn phong_lights() {
+  use structs::light::Light;
+  let mut sphere = structs::renderable::PhongMesh::uv_sphere(1.2, 32, 16).unwrap();
+  sphere.set_color(0.8, 0.8, 0.8, 1.0);
+  let mut key = Light::directional(-1.0, -0.5, -1.0);
+  key.set_intensity(0.6);
+  let mut red = Light::point(2.0, 0.5, 0.0);
+  red.set_color(1.0, 0.2, 0.2);
+  let mut blue = Light::spot(0.0, 4.0, 2.0, 0.0, -1.0, -0.3, 0.2, 0.4);
+  blue.set_color(0.2, 0.4, 1.0);
+  blue.set_intensity(3.0);
+  let pixels = render_lit(sphere, vec![key, red, blue]).unwrap();
+  assert_matches_golden("phong_lights", &pixels);
+}
+
+#[test]
+fn pbr_normal_map() {
+  use j_webgl::{geometry, texture, structs::{light::Light, material::PbrMaterial}};
+  // Ridges along the s texture coordinate, tilting the normal back and forth along the tangent
+  let normals = (0..16 * 16).flat_map(|i| {
+    let tilt = ((i % 16) as f32 / 16.0 * std::f32::consts::TAU * 4.0).sin() * 0.7;
+    let z = (1.0 - tilt * tilt).sqrt();
+    [((tilt + 1.0) * 127.5) as u8, 128, ((z + 1.0) * 127.5) as u8]
+  }).collect();
+  let normal_map = texture::Image::new(16, 16, texture::TextureFormat::Rgb8, normals).unwrap();
+  let material = PbrMaterial::new([1.0, 0.6, 0.2, 1.0], 0.3, 0.35).with_normal_map(normal_map, 1.0);
+  let sphere = structs::renderable::PbrMesh::new(geometry::uv_sphere(1.2, 32, 16).unwrap(), material).unwrap();
+  let mut sun = Light::directional(-1.0, -1.0, -0.5);
+  sun.set_intensity(3.0);
+  let mut fill = Light::point(3.0, 1.0, 2.0);
+  fill.set_color(0.6, 0.7, 1.0);
+  fill.set_intensity(4.0);
+  let pixels = render_lit(sphere, vec![sun, fill]).unwrap();
+  assert_matches_golden("pbr_normal_map", &pixels);
+}
+
+#[test]
+fn phong_shadow() {
+  use j_webgl::{algebra, shadow::ShadowSettings, structs::{light::Light, renderable::PhongMesh}};
+  let context = Rc::new(SoftwareContext::new(WIDTH, HEIGHT));
+  let mut renderer = Renderer::from_context(context.clone());
+  let mut sun = Light::directional(-0.6, -1.0, -0.3);
+  sun.set_cast_shadow(true);
+  renderer.with_light("sun".to_string(), Some(sun)).unwrap();
+  renderer.set_shadows(Some(ShadowSettings { size: 256, extent: 3.0, ..Default::default() }));
+  renderer.with_renderable("background".to_string(), Some(structs::renderable::Initializer::new().unwrap())).unwrap();
+  let mut ground = PhongMesh::plane(5.0, 5.0, 1, 1).unwrap();
+  ground.set_color(0.9, 0.9, 0.9, 1.0);
+  renderer.with_renderable("ground".to_string(), Some(ground)).unwrap();
+  renderer.set_transform("ground", algebra::Matrix4::new_translation(0.0, -0.5, 0.0)).unwrap();
+  renderer.with_renderable("box".to_string(), Some(PhongMesh::cuboid(1.0, 1.0, 1.0).unwrap())).unwrap();
+  renderer.render(&structs::camera::Basic::new(WIDTH as f32, HEIGHT as f32).unwrap()).unwrap();
+  assert_matches_golden("phong_shadow", &context.pixels());
+}
+
+#[test]
+fn render_target_resolve() {
+  use j_webgl::{gl::GlContext, target::RenderTargetSettings, exports::web_sys::WebGl2RenderingContext as GL};
+  let context = Rc::new(SoftwareContext::new(WIDTH, HEIGHT));
+  let mut renderer = Renderer::from_context(context.clone());
+  renderer.with_renderable("background".to_string(), Some(structs::renderable::Initializer::new().unwrap())).unwrap();
+  renderer.with_renderable("object".to_string(), Some(structs::renderable::Cube::new().unwrap())).unwrap();
+  renderer.with_render_target("offscreen".to_string(), Some(RenderTargetSettings { samples: 4, ..Default::default() })).unwrap();
+  renderer.render_to(&structs::camera::Basic::new(WIDTH as f32, HEIGHT as f32).unwrap(), "offscreen").unwrap();
+  assert!(context.pixels().iter().all(|&c| c == 0), "the canvas is not drawn to");
+
+  let target = renderer.render_target("offscreen").unwrap();
+  context.bind_framebuffer(GL::READ_FRAMEBUFFER, Some(target.framebuffer()));
+  context.blit_framebuffer(0, 0, WIDTH, HEIGHT, 0, 0, WIDTH, HEIGHT, GL::COLOR_BUFFER_BIT, GL::NEAREST);
+  assert_matches_golden("cube", &context.pixels());
+}
@@ -0,0 +1,207 @@
+//! Model loaders run on the fixture files of `tests/fixtures`
+
+use std::rc::Rc;
+use j_webgl::{loaders, geometry, algebra, mesh::{Mesh, Indices}};
+use j_webgl::{Renderer, gl::{GlContext, RecordingContext, Call}, structs};
+
+const CUBE_OBJ: &[u8] = include_bytes!("fixtures/cube.obj");
+const CUBE_MTL: &[u8] = include_bytes!("fixtures/cube.mtl");
+const TETRAHEDRON_STL: &[u8] = include_bytes!("fixtures/tetrahedron.stl");
+const SCAN_PLY: &[u8] = include_bytes!("fixtures/scan.ply");
+const QUAD_PLY: &[u8] = include_bytes!("fixtures/quad.ply");
+const HIERARCHY_GLTF: &[u8] = include_bytes!("fixtures/hierarchy.gltf");
+const CUBE_GLB: &[u8] = include_bytes!("fixtures/cube.glb");
+
+#[test]
+fn obj_cube_splits_groups_by_material() {
+  let model = loaders::parse_obj(CUBE_OBJ).unwrap();
+  assert_eq!(model.material_libraries, vec!["cube.mtl".to_string()]);
+  assert_eq!(model.groups.len(), 2);
+
+  for (group, material) in model.groups.iter().zip(["red", "blue"]) {
+    assert_eq!(group.name, "cube");
+    assert_eq!(group.material.as_deref(), Some(material));
+    let mesh = &group.mesh;
+    // Three quads, each with its own normal, triangulated
+    assert_eq!(mesh.vertex_count(), 12);
+    assert_eq!(mesh.triangle_count(), 6);
+    assert!(matches!(mesh.indices(), Some(Indices::U16(_))));
+    assert_eq!(mesh.uvs().map(|uvs| uvs.len()), Some(24));
+
+    // Faces wind counter-clockwise around the stored outward normals
+    let normals = mesh.normals().unwrap();
+    for [a, b, c] in mesh.triangles() {
+      let (a, b, c) = (mesh.position(a), mesh.position(b), mesh.position(c));
+      let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+      let face = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+      let centre = [(a[0] + b[0] + c[0]) / 3.0, (a[1] + b[1] + c[1]) / 3.0, (a[2] + b[2] + c[2]) / 3.0];
+      assert!(face.iter().zip(centre).map(|(f, c)| f * c).sum::<f32>() > 0.0);
+    }
+    assert!(normals.chunks(3).all(|n| (n.iter().map(|v| v * v).sum::<f32>() - 1.0).abs() < 1e-6));
+  }
+  // Texture coordinates are flipped to have `v = 0` at the top
+  assert_eq!(&model.groups[0].mesh.uvs().unwrap()[..4], &[0.0, 1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn mtl_cube_materials() {
+  let materials = loaders::parse_mtl(CUBE_MTL).unwrap();
+  assert_eq!(materials.len(), 2);
+
+  let red = &materials[0];
+  assert_eq!(red.name, "red");
+  assert_eq!(red.ambient, [0.1, 0.0, 0.0]);
+  assert_eq!(red.diffuse, [0.8, 0.1, 0.1]);
+  assert_eq!(red.specular, [0.5; 3]);
+  assert_eq!(red.shininess, 32.0);
+  assert_eq!(red.opacity, 1.0);
+  assert_eq!(red.diffuse_map, None);
+
+  let blue = &materials[1];
+  assert_eq!(blue.opacity, 0.75);
+  assert_eq!(blue.diffuse_map.as_deref(), Some("textures/blue.png"));
+}
+
+#[test]
+fn obj_cube_converts_to_scene() {
+  let model = loaders::parse_obj(CUBE_OBJ).unwrap();
+  let materials = loaders::parse_mtl(CUBE_MTL).unwrap();
+  let scene = model.to_scene(&materials).unwrap();
+  assert_eq!(scene.roots().len(), 2);
+  assert!(scene.find("cube").is_some());
+}
+
+#[test]
+fn stl_ascii_tetrahedron() {
+  let model = loaders::parse_stl(TETRAHEDRON_STL).unwrap();
+  assert_eq!(model.name, "tetrahedron");
+  assert_eq!(model.triangle_count(), 4);
+  assert_eq!(model.positions.len(), 36);
+  assert_eq!(&model.normals[..9], &[0.0, 0.0, -1.0].repeat(3)[..]);
+  // The zero normal of the last facet is computed from its vertices
+  let n = 1.0 / 3.0f32.sqrt();
+  assert!(model.normals[27..].iter().all(|v| (v - n).abs() < 1e-6));
+
+  let welded = model.welded().unwrap();
+  assert_eq!(welded.vertex_count(), 4);
+  assert_eq!(welded.triangle_count(), 4);
+  assert_eq!(model.to_mesh().unwrap().vertex_count(), 12);
+}
+
+fn assert_same_triangles(mesh: &Mesh, model: &loaders::StlModel) {
+  let positions: Vec<f32> = mesh.triangles().flatten().flat_map(|i| mesh.position(i)).collect();
+  assert_eq!(positions, model.positions);
+}
+
+#[test]
+fn stl_round_trips_through_both_formats() {
+  let mesh = geometry::uv_sphere(1.5, 12, 6).unwrap();
+  for format in [loaders::StlFormat::Ascii, loaders::StlFormat::Binary] {
+    let data = loaders::write_stl(&mesh, "sphere", format).unwrap();
+    assert_eq!(data.starts_with(b"solid sphere"), format == loaders::StlFormat::Ascii);
+    let model = loaders::parse_stl(&data).unwrap();
+    assert_eq!(model.triangle_count(), mesh.triangle_count());
+    assert_same_triangles(&mesh, &model);
+
+    // Writing the read triangles gives back the same file
+    assert_eq!(loaders::write_stl(&model.to_mesh().unwrap(), "sphere", format).unwrap(), data);
+  }
+}
+
+#[test]
+fn stl_welding_restores_shared_vertices() {
+  let mesh = geometry::cuboid(1.0, 2.0, 3.0).unwrap();
+  let data = loaders::write_stl(&mesh, "", loaders::StlFormat::Binary).unwrap();
+  assert_eq!(data.len(), 84 + 12 * 50);
+  let welded = loaders::parse_stl(&data).unwrap().welded().unwrap();
+  // Corners shared by the faces of the cube
+  assert_eq!(welded.vertex_count(), 8);
+  assert_eq!(welded.triangle_count(), 12);
+  assert!(matches!(welded.indices(), Some(Indices::U16(_))));
+}
+
+#[test]
+fn ply_ascii_point_cloud_keeps_extra_properties() {
+  let model = loaders::parse_ply(SCAN_PLY).unwrap();
+  assert_eq!(model.format, loaders::PlyFormat::Ascii);
+  assert_eq!(model.comments, vec!["lidar scan excerpt".to_string()]);
+  let vertex = model.element("vertex").unwrap();
+  assert_eq!(vertex.property("intensity").unwrap().scalars(), Some(&[0.25, 0.5, 0.75, 1.0][..]));
+  assert_eq!(model.element("camera").unwrap().property("position").unwrap().lists(), Some(&[vec![0.0, 0.0, 5.0]][..]));
+
+  let mesh = model.to_mesh().unwrap();
+  assert_eq!(mesh.vertex_count(), 4);
+  assert_eq!(mesh.indices(), None);
+  assert_eq!(&mesh.colors().unwrap()[..8], &[1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+
+  let cloud = structs::renderable::PointCloud::from_ply(SCAN_PLY).unwrap();
+  assert_eq!(cloud.mesh().vertex_count(), 4);
+}
+
+#[test]
+fn ply_binary_mesh_triangulates_faces() {
+  let model = loaders::parse_ply(QUAD_PLY).unwrap();
+  assert_eq!(model.format, loaders::PlyFormat::BinaryLittleEndian);
+  assert_eq!(model.element("face").unwrap().property("flags").unwrap().scalars(), Some(&[7.0][..]));
+
+  let mesh = model.to_mesh().unwrap();
+  assert_eq!(mesh.position(2), [1.0, 1.0, 0.0]);
+  assert_eq!(mesh.triangle_count(), 2);
+  // 16 bit colors with alpha
+  assert_eq!(&mesh.colors().unwrap()[..4], &[1.0, 0.0, 0.0, 1.0]);
+}
+
+fn assert_close(actual: &algebra::Point3, expected: [f32; 3]) {
+  let expected = algebra::Point3::new(expected[0], expected[1], expected[2]);
+  assert!((actual - &expected).norm() < 1e-5, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn gltf_hierarchy_with_embedded_buffer() {
+  let model = loaders::parse_gltf(HIERARCHY_GLTF).unwrap();
+  assert_eq!(model.roots, vec![0]);
+  assert_eq!(model.nodes[0].children, vec![1]);
+  assert_eq!(model.nodes[1].mesh, Some(0));
+
+  let primitive = &model.meshes[0].primitives[0];
+  assert_eq!(primitive.mesh.vertex_count(), 4);
+  assert_eq!(primitive.mesh.indices(), Some(&Indices::U16(vec![0, 1, 2, 0, 2, 3])));
+  assert_eq!(primitive.mesh.uvs().map(|uvs| uvs.len()), Some(8));
+  assert_eq!(primitive.mesh.normals().unwrap()[..3], [0.0, 0.0, 1.0]);
+
+  let material = &model.materials[primitive.material.unwrap()];
+  assert_eq!(material.name, "red");
+  assert_eq!(material.base_color, [0.8, 0.1, 0.1, 1.0]);
+  assert_eq!((material.metallic, material.roughness), (0.0, 0.5));
+
+  // Scaled by 2, turned a quarter around y, then moved up by the root
+  let (node, world) = model.world_transforms()[1];
+  assert_eq!(node, 1);
+  assert_close(&world.transform_point(&algebra::Point3::new(0.5, 0.0, 0.0)), [0.0, 1.0, -1.0]);
+
+  let scene = model.to_scene().unwrap();
+  let quad = scene.find("quad").unwrap();
+  assert_close(&scene.world_transform(quad).unwrap().transform_point(&algebra::Point3::new(0.5, 0.0, 0.0)), [0.0, 1.0, -1.0]);
+}
+
+#[test]
+fn gltf_binary_cube_added_to_renderer() {
+  let model = loaders::parse_gltf(CUBE_GLB).unwrap();
+  assert_eq!(model.nodes[0].name, "cube");
+  let mesh = &model.meshes[0].primitives[0].mesh;
+  assert_eq!(mesh.vertex_count(), 24);
+  assert_eq!(mesh.triangle_count(), 12);
+  // Normalized unsigned bytes, completed with an opaque alpha
+  let color = &mesh.colors().unwrap()[..4];
+  assert!((color[0] - 1.0).abs() < 1e-6 && (color[1] - 128.0 / 255.0).abs() < 1e-6 && color[3] == 1.0);
+
+  let recording = Rc::new(RecordingContext::new(64, 64));
+  let mut renderer = Renderer::from_context(recording.clone() as Rc<dyn GlContext>);
+  let ids = model.add_to_renderer(&mut renderer).unwrap();
+  assert_eq!(ids, vec!["cube".to_string()]);
+  let transform = renderer.transform("cube").unwrap();
+  assert_close(&transform.transform_point(&algebra::Point3::new(0.5, 0.5, 0.5)), [2.0, 3.0, 4.0]);
+
+  renderer.render(&structs::camera::Basic::new(64.0, 64.0).unwrap()).unwrap();
+  assert!(recording.calls().iter().any(|c| matches!(c, Call::DrawElements { count: 36, .. })));
+}